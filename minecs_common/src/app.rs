@@ -0,0 +1,222 @@
+use std::time::{ Duration, Instant };
+
+use crate::{ CompName, EcsColumns, Schedule, ScheduleErr, System };
+
+//------------------------------------------------------------------------------
+// struct - Time
+
+/// Time of the game loop, stored as a resource of the ECS and updated by `App` before each run of a schedule.
+/// 
+/// During the fixed update `delta` is the fixed step, during render it is the time since the previous frame.
+#[derive( Debug, Clone, Copy, Default, PartialEq, Eq )]
+#[cfg_attr( feature = "serde", derive( serde::Serialize, serde::Deserialize ) )]
+pub struct Time {
+	step: Duration, // fixed step of the update
+	delta: Duration, // time advanced by the current run
+	elapsed: Duration, // time advanced by all fixed updates
+	overstep: Duration, // time accumulated, but not yet advanced by a fixed update
+	ticks: u64, // amount of fixed updates
+}
+
+impl Time {
+	#[inline]
+	pub const fn new () -> Self {
+		Self {
+			step: Duration::ZERO,
+			delta: Duration::ZERO,
+			elapsed: Duration::ZERO,
+			overstep: Duration::ZERO,
+			ticks: 0,
+		}
+	}
+	
+	/// Returns the fixed step of the update.
+	#[inline]
+	pub const fn step ( &self ) -> Duration {
+		self.step
+	}
+	
+	/// Returns the time advanced by the current run.
+	#[inline]
+	pub const fn delta ( &self ) -> Duration {
+		self.delta
+	}
+	
+	/// Returns the time advanced by the current run in seconds.
+	#[inline]
+	pub fn delta_secs ( &self ) -> f64 {
+		self.delta.as_secs_f64()
+	}
+	
+	/// Returns the time advanced by all fixed updates.
+	#[inline]
+	pub const fn elapsed ( &self ) -> Duration {
+		self.elapsed
+	}
+	
+	/// Returns the amount of fixed updates run so far.
+	#[inline]
+	pub const fn ticks ( &self ) -> u64 {
+		self.ticks
+	}
+	
+	/// Returns the fraction of the fixed step accumulated since the last fixed update, fe. to interpolate positions during render.
+	#[inline]
+	pub fn alpha ( &self ) -> f64 {
+		if self.step.is_zero() {
+			0.0
+		} else {
+			self.overstep.as_secs_f64() / self.step.as_secs_f64()
+		}
+	}
+}
+
+// struct - Time
+//------------------------------------------------------------------------------
+// struct - App
+
+/// Fixed-timestep game loop, owning an ECS declared with `#[parallel]` and having a `Time` resource.
+/// 
+/// Each frame runs the update schedule once per fixed step of accumulated time, then the render schedule once.
+/// `App::step` runs the update schedule a given amount of times without render, fe. for deterministic tests.
+#[derive( Debug )]
+pub struct App< W, E > {
+	world: W,
+	update: Schedule< E >,
+	render: Schedule< E >,
+	step: Duration,
+	max_steps: u32,
+	accumulated: Duration,
+}
+
+impl< W: EcsColumns< E >, E: Sync + 'static > App< W, E > {
+	/// Creates app updating the ECS every `step`, at most 8 times per frame.
+	/// Step shorter than a nanosecond is rounded up.
+	#[inline]
+	pub fn new ( world: W, step: Duration ) -> Self {
+		Self {
+			world,
+			update: Schedule::new(),
+			render: Schedule::new(),
+			step: step.max( Duration::from_nanos( 1 )),
+			max_steps: 8,
+			accumulated: Duration::ZERO,
+		}
+	}
+	
+	/// Sets the maximum amount of fixed updates per frame, time exceeding it is dropped so that a slow frame does not cause even slower ones.
+	#[inline]
+	#[must_use]
+	pub const fn with_max_steps ( mut self, max_steps: u32 ) -> Self {
+		self.max_steps = max_steps;
+		self
+	}
+	
+	/// Adds a system to the fixed update schedule.
+	#[inline]
+	pub fn add_update_system ( &mut self, system: System< E > ) -> &mut Self {
+		self.update.add_system( system );
+		self
+	}
+	
+	/// Adds a system to the render schedule.
+	#[inline]
+	pub fn add_render_system ( &mut self, system: System< E > ) -> &mut Self {
+		self.render.add_system( system );
+		self
+	}
+	
+	#[inline]
+	pub const fn world ( &self ) -> &W {
+		&self.world
+	}
+	
+	#[inline]
+	pub fn world_mut ( &mut self ) -> &mut W {
+		&mut self.world
+	}
+	
+	#[inline]
+	pub fn into_world ( self ) -> W {
+		self.world
+	}
+	
+	/// Runs the fixed update schedule `count` times, without render.
+	/// 
+	/// # Errors
+	/// 
+	/// ScheduleErr::MissingResource - when the ECS has no `Time` resource
+	/// 
+	/// Same as `Schedule::run`, remaining updates are not run.
+	#[inline]
+	pub fn step ( &mut self, count: u32 ) -> Result< (), ScheduleErr > {
+		( 0..count ).try_for_each( |_| self.update() )
+	}
+	
+	/// Advances the loop by `delta`, running the fixed update schedule once per accumulated step and the render schedule once.
+	/// On success returns the amount of fixed updates run.
+	/// 
+	/// # Errors
+	/// 
+	/// Same as `App::step`.
+	#[inline]
+	pub fn frame ( &mut self, delta: Duration ) -> Result< u32, ScheduleErr > {
+		self.accumulated = self.accumulated.saturating_add( delta );
+		let mut steps = 0;
+		while self.accumulated >= self.step {
+			if steps == self.max_steps {
+				self.accumulated = Duration::ZERO;
+				break
+			}
+			self.accumulated -= self.step;
+			self.update()?;
+			steps += 1;
+		}
+		
+		let ( step, accumulated ) = ( self.step, self.accumulated );
+		let time = self.time()?;
+		time.step = step;
+		time.delta = delta;
+		time.overstep = accumulated;
+		self.render.run( &mut self.world )?;
+		Ok( steps )
+	}
+	
+	/// Runs frames measuring real time between them, until `done` returns `true` after a frame.
+	/// 
+	/// # Errors
+	/// 
+	/// Same as `App::step`.
+	#[inline]
+	pub fn run_until< F: FnMut( &W ) -> bool > ( &mut self, mut done: F ) -> Result< (), ScheduleErr > {
+		let mut last = Instant::now();
+		loop {
+			let now = Instant::now();
+			self.frame( now.duration_since( last ))?;
+			last = now;
+			if done( &self.world ) {
+				return Ok(())
+			}
+		}
+	}
+	
+	fn update ( &mut self ) -> Result< (), ScheduleErr > {
+		let ( step, accumulated ) = ( self.step, self.accumulated );
+		let time = self.time()?;
+		time.step = step;
+		time.delta = step;
+		time.elapsed = time.elapsed.saturating_add( step );
+		time.overstep = accumulated;
+		time.ticks += 1;
+		self.update.run( &mut self.world )
+	}
+	
+	fn time ( &mut self ) -> Result< &mut Time, ScheduleErr > {
+		self.world.columns_mut()
+			.into_resource::< Time >()
+			.ok_or( ScheduleErr::MissingResource( CompName::of::< Time >() ))
+	}
+}
+
+// struct - App
+//------------------------------------------------------------------------------
@@ -0,0 +1,268 @@
+use std::fmt::Write;
+use crate::EcsErr;
+
+//------------------------------------------------------------------------------
+// --Traits
+
+/// Human-readable dump of the whole ECS, implemented by `minecs!`.
+pub trait EcsDump {
+	/// Writes every entity selected by `options` with its id and components.
+	/// 
+	/// Named components are labelled with the field name, generic ones with `std::any::type_name`.
+	fn dump_with ( &self, options: &DumpOptions ) -> String;
+	
+	/// Writes every entity with its id and components.
+	#[inline]
+	fn dump ( &self ) -> String {
+		self.dump_with( &DumpOptions::new() )
+	}
+}
+
+/// Integrity check of the associations between entities and components, implemented by `minecs!`.
+pub trait EcsValidate {
+	/// Checks that every `CompId` stored in an entity points at a live component associated with that entity,
+	/// and that every live component is present in the entity it is associated with.
+	/// 
+	/// # Errors
+	/// 
+	/// Returns all inconsistencies found:
+	/// EcsErr::CompVecMissingComponent - when component present in entity cannot be found in `CompVec`, fe. its slot was recycled
+	/// EcsErr::ComponentOwnerMismatch - when component present in entity is associated with another entity
+	/// EcsErr::OrphanComponent - when component is not present in the entity it is associated with
+	fn validate ( &self ) -> Result< (), Vec< EcsErr > >;
+	
+	/// Calls `validate` in debug builds and panics listing all inconsistencies, does nothing in release builds.
+	/// 
+	/// It is called after every `Schedule::run`, and so after every update and render of `App`;
+	/// changes made outside of a schedule can be checked by calling it explicitly, fe. after applying a batch of them.
+	/// 
+	/// # Panics
+	/// 
+	/// When `validate` fails in debug build.
+	#[inline]
+	fn debug_validate ( &self ) {
+		if cfg!( debug_assertions ) {
+			if let Err( errors ) = self.validate() {
+				let list: Vec< String > = errors.iter().map( ToString::to_string ).collect();
+				panic!( "ECS integrity check failed:\n{}", list.join( "\n" ));
+			}
+		}
+	}
+}
+
+// --Traits
+//------------------------------------------------------------------------------
+// struct - DumpOptions
+
+/// Filters and limits of `EcsDump::dump_with`.
+#[derive( Debug, Clone, Default, PartialEq )]
+pub struct DumpOptions {
+	entities: Option< Vec< usize > >,
+	components: Option< Vec< String > >,
+	limit: Option< usize >,
+	max_value_len: Option< usize >,
+}
+
+impl DumpOptions {
+	/// Creates options showing everything.
+	#[inline]
+	pub const fn new () -> Self {
+		Self {
+			entities: None,
+			components: None,
+			limit: None,
+			max_value_len: None,
+		}
+	}
+	
+	/// Shows entity with specified id; when used, only selected entities are shown.
+	#[inline]
+	#[must_use]
+	pub fn entity< E > ( mut self, id: crate::EntityId< E > ) -> Self {
+		self.entities.get_or_insert_with( Vec::new ).push( usize::from( id ));
+		self
+	}
+	
+	/// Shows components of type `T`, both generic and named; when used, only selected components are shown.
+	#[inline]
+	#[must_use]
+	pub fn component< T > ( self ) -> Self {
+		self.component_named( std::any::type_name::< T >() )
+	}
+	
+	/// Shows components with specified label, fe. a named field; when used, only selected components are shown.
+	#[inline]
+	#[must_use]
+	pub fn component_named ( mut self, label: &str ) -> Self {
+		self.components.get_or_insert_with( Vec::new ).push( label.to_owned() );
+		self
+	}
+	
+	/// Shows at most `limit` entities.
+	#[inline]
+	#[must_use]
+	pub const fn limit ( mut self, limit: usize ) -> Self {
+		self.limit = Some( limit );
+		self
+	}
+	
+	/// Truncates each component value to at most `len` characters.
+	#[inline]
+	#[must_use]
+	pub const fn max_value_len ( mut self, len: usize ) -> Self {
+		self.max_value_len = Some( len );
+		self
+	}
+	
+	/// Checks whether entity with specified id is shown.
+	#[inline]
+	pub fn shows_entity ( &self, id: usize ) -> bool {
+		self.entities.as_ref().map_or( true, |ids| ids.contains( &id ))
+	}
+	
+	/// Checks whether component with specified label and type name is shown.
+	#[inline]
+	pub fn shows_component ( &self, label: &str, type_name: &str ) -> bool {
+		self.components.as_ref().map_or( true, |names| names.iter().any( |name| name == label || name == type_name ))
+	}
+}
+
+// struct - DumpOptions
+//------------------------------------------------------------------------------
+// struct - DumpWriter
+
+/// Formatter used by generated `EcsDump` impls.
+#[derive( Debug )]
+pub struct DumpWriter< 'a > {
+	options: &'a DumpOptions,
+	out: String,
+	shown: usize,
+	skipped: usize,
+}
+
+impl< 'a > DumpWriter< 'a > {
+	/// Starts the dump of ECS with specified name and amount of entities.
+	#[inline]
+	pub fn new ( ecs_name: &str, entities: usize, options: &'a DumpOptions ) -> Self {
+		let mut out = String::new();
+		_ = writeln!( out, "{ecs_name}: {entities} entities" );
+		
+		Self {
+			options,
+			out,
+			shown: 0,
+			skipped: 0,
+		}
+	}
+	
+	/// Writes an entity with pairs of component label and value formatted with `Debug`.
+	/// 
+	/// When components are filtered, entities without any shown component are omitted.
+	#[inline]
+	pub fn entity ( &mut self, id: usize, components: &[( &str, String )] ) {
+		if self.is_full() {
+			self.skip( !components.is_empty() );
+			return
+		}
+		if self.options.components.is_some() && components.is_empty() {
+			return
+		}
+		self.shown += 1;
+		
+		if components.is_empty() {
+			_ = writeln!( self.out, "entity {id}: no components" );
+			return
+		}
+		
+		_ = writeln!( self.out, "entity {id}:" );
+		for &( label, ref value ) in components {
+			match self.options.max_value_len {
+				Some( len ) if value.chars().count() > len => {
+					let truncated: String = value.chars().take( len ).collect();
+					_ = writeln!( self.out, "\t{label}: {truncated}..." );
+				}
+				_ => _ = writeln!( self.out, "\t{label}: {value}" ),
+			}
+		}
+	}
+	
+	/// Checks whether the limit of shown entities is reached, so that further entities only need to be counted via `DumpWriter::skip`.
+	#[inline]
+	pub fn is_full ( &self ) -> bool {
+		self.options.limit.is_some_and( |limit| self.shown >= limit )
+	}
+	
+	/// Counts an entity omitted due to the limit, without formatting its components.
+	/// 
+	/// When components are filtered, entities without any shown component are not counted.
+	#[inline]
+	pub fn skip ( &mut self, has_components: bool ) {
+		if self.options.components.is_none() || has_components {
+			self.skipped += 1;
+		}
+	}
+	
+	/// Finishes the dump, noting entities omitted due to the limit.
+	#[inline]
+	pub fn finish ( mut self ) -> String {
+		if self.skipped > 0 {
+			_ = writeln!( self.out, "... {} more entities", self.skipped );
+		}
+		self.out
+	}
+}
+
+// struct - DumpWriter
+//------------------------------------------------------------------------------
+
+//------------------------------------------------------------------------------
+// --Tests
+
+#[cfg( test )]
+#[allow( dead_code )]// not every generated method is used by tests
+mod tests {
+	use crate::*;
+	use minecs_macro::minecs;
+	use std::sync::atomic::{ AtomicUsize, Ordering };
+	
+	static FORMATTED: AtomicUsize = AtomicUsize::new( 0 );
+	
+	/// Counts how many times it is formatted.
+	#[derive( Clone, PartialEq )]
+	pub struct Counted;
+	
+	impl std::fmt::Debug for Counted {
+		fn fmt( &self, f: &mut std::fmt::Formatter<'_> ) -> std::fmt::Result {
+			FORMATTED.fetch_add( 1, Ordering::Relaxed );
+			f.write_str( "Counted" )
+		}
+	}
+	
+	minecs!(
+		ecs TestEcs< TestArray, TestEntity > {
+			counted: Counted,
+			name: String,
+		}
+	);
+	
+	#[test]
+	fn limited_dump_formats_only_shown_entities() {
+		let mut ecs = TestEcs::new();
+		for idx in 0..10 {
+			let id = ecs.new_entity();
+			_ = ecs.insert_counted( id, Counted );
+			if idx % 2 == 0 {
+				_ = ecs.insert_name( id, format!( "unit {idx}" ));
+			}
+		}
+		
+		let dump = ecs.dump_with( &DumpOptions::new().limit( 2 ));
+		assert_eq!( FORMATTED.load( Ordering::Relaxed ), 2 );
+		assert!( dump.ends_with( "... 8 more entities\n" ), "{dump}" );
+		
+		// entities without any shown component are omitted rather than counted
+		let names = ecs.dump_with( &DumpOptions::new().component_named( "name" ).limit( 2 ));
+		assert_eq!( FORMATTED.load( Ordering::Relaxed ), 2 );
+		assert!( names.ends_with( "... 3 more entities\n" ), "{names}" );
+	}
+}
@@ -0,0 +1,81 @@
+//------------------------------------------------------------------------------
+// struct - Events
+
+/// Queue of events of type `T`, stored as a resource of the ECS.
+/// 
+/// Systems sending events declare writing the resource, fe. `writes_resource::< Events< T > >()`.
+/// Events are kept until drained by the system reacting to them, or cleared; nothing clears them automatically, not even `App`,
+/// so events nobody drains accumulate.
+#[derive( Debug, Clone, PartialEq )]
+pub struct Events< T > {
+	queue: Vec< T >,
+}
+
+impl< T > Events< T > {
+	#[inline]
+	pub const fn new () -> Self {
+		Self {
+			queue: Vec::new(),
+		}
+	}
+	
+	/// Adds event at the end of the queue.
+	#[inline]
+	pub fn send ( &mut self, event: T ) {
+		self.queue.push( event );
+	}
+	
+	/// Iterates over events in the order they were sent, keeping them in the queue.
+	#[inline]
+	pub fn iter ( &self ) -> std::slice::Iter< '_, T > {
+		self.queue.iter()
+	}
+	
+	/// Removes all events from the queue, returning them in the order they were sent.
+	#[inline]
+	pub fn drain ( &mut self ) -> std::vec::Drain< '_, T > {
+		self.queue.drain( .. )
+	}
+	
+	#[inline]
+	pub fn clear ( &mut self ) {
+		self.queue.clear();
+	}
+	
+	#[inline]
+	pub fn len ( &self ) -> usize {
+		self.queue.len()
+	}
+	
+	#[inline]
+	pub fn is_empty ( &self ) -> bool {
+		self.queue.is_empty()
+	}
+}
+
+impl< T > Default for Events< T > {
+	#[inline]
+	fn default () -> Self {
+		Self::new()
+	}
+}
+
+impl< T > Extend< T > for Events< T > {
+	#[inline]
+	fn extend< I: IntoIterator< Item = T >> ( &mut self, iter: I ) {
+		self.queue.extend( iter );
+	}
+}
+
+impl< 'a, T > IntoIterator for &'a Events< T > {
+	type Item = &'a T;
+	type IntoIter = std::slice::Iter< 'a, T >;
+	
+	#[inline]
+	fn into_iter( self ) -> Self::IntoIter {
+		self.iter()
+	}
+}
+
+// struct - Events
+//------------------------------------------------------------------------------
@@ -0,0 +1,316 @@
+use crate::{ EcsErr, EcsMain, EntityId, EntityMap, SnapComp, SnapErr, SnapSection, SnapValue };
+
+//------------------------------------------------------------------------------
+// --Traits
+
+/// Kind of typed edge between entities, implemented by `minecs!` for every entry of `relations [...]`.
+pub trait Relation: 'static {
+	/// Value carried by each edge, `()` for relations declared without data.
+	type Data;
+}
+
+// per R fn
+pub trait EcsRelationFn< R: Relation > {
+	/// Borrows all edges of relation `R`.
+	fn relations ( &self ) -> &Relations< R >;
+	
+	/// Mutably borrows all edges of relation `R`.
+	fn relations_mut ( &mut self ) -> &mut Relations< R >;
+}
+
+/// Typed many-to-many edges between entities, implemented by `minecs!` for every ECS.
+/// 
+/// Edges are removed automatically when either end is despawned via `EcsHierarchy::despawn`.
+pub trait EcsRelation< E: Clone >: EcsMain< E > {
+	/// Adds an edge of relation `R` from `source` to `target`, replacing the data of an existing one.
+	/// Returns the replaced data in `Ok( Some( R::Data ) )` if the edge was present.
+	/// 
+	/// # Errors
+	/// 
+	/// EcsErr::NoSuchEntityId - when either entity does not exist
+	#[inline]
+	fn relate< R: Relation > ( &mut self, source: EntityId< E >, target: EntityId< E >, data: R::Data ) -> Result< Option< R::Data >, EcsErr >
+	where
+		Self: EcsRelationFn< R >,
+	{
+		for id in [ source, target ] {
+			if self.entity( id ).is_none() {
+				return Err( EcsErr::NoSuchEntityId( usize::from( id )))
+			}
+		}
+		Ok( EcsRelationFn::< R >::relations_mut( self ).insert( source, target, data ))
+	}
+	
+	/// Removes the edge of relation `R` from `source` to `target`, returns its data if present.
+	#[inline]
+	fn unrelate< R: Relation > ( &mut self, source: EntityId< E >, target: EntityId< E > ) -> Option< R::Data >
+	where
+		Self: EcsRelationFn< R >,
+	{
+		EcsRelationFn::< R >::relations_mut( self ).remove( source, target )
+	}
+	
+	/// Returns the data of the edge of relation `R` from `source` to `target` if present.
+	#[inline]
+	fn relation< R: Relation > ( &self, source: EntityId< E >, target: EntityId< E > ) -> Option< &R::Data >
+	where
+		Self: EcsRelationFn< R >,
+	{
+		EcsRelationFn::< R >::relations( self ).get( source, target )
+	}
+	
+	/// Returns an iterator over edges of relation `R` starting at `source`, in the order they were added.
+	#[inline]
+	fn outgoing< R: Relation > ( &self, source: EntityId< E > ) -> Edges< '_, R, E >
+	where
+		Self: EcsRelationFn< R >,
+	{
+		EcsRelationFn::< R >::relations( self ).outgoing( source )
+	}
+	
+	/// Returns an iterator over edges of relation `R` ending at `target`, in the order they were added.
+	#[inline]
+	fn incoming< R: Relation > ( &self, target: EntityId< E > ) -> Edges< '_, R, E >
+	where
+		Self: EcsRelationFn< R >,
+	{
+		EcsRelationFn::< R >::relations( self ).incoming( target )
+	}
+}
+
+// --Traits
+//------------------------------------------------------------------------------
+// struct - Relations
+
+/// All edges of relation `R`, stored in the ECS.
+#[cfg_attr( feature = "serde", derive( serde::Serialize, serde::Deserialize ) )]
+#[cfg_attr( feature = "serde", serde( bound(
+	serialize = "R::Data: serde::Serialize",
+	deserialize = "R::Data: serde::Deserialize< 'de >",
+)))]
+#[derive( Debug, Clone, PartialEq, Eq, Hash )]
+pub struct Relations< R: Relation > {
+	edges: Vec< ( usize, usize, R::Data ) >,
+	marker: std::marker::PhantomData< R >,
+}
+
+impl< R: Relation > Relations< R > {
+	#[inline]
+	pub const fn new () -> Self {
+		Self {
+			edges: Vec::new(),
+			marker: std::marker::PhantomData,
+		}
+	}
+	
+	/// Adds an edge from `source` to `target`, returns the data of the replaced edge if present.
+	/// 
+	/// Existence of the entities is not checked, refer to `EcsRelation::relate`.
+	#[inline]
+	pub fn insert< E > ( &mut self, source: EntityId< E >, target: EntityId< E >, data: R::Data ) -> Option< R::Data > {
+		let key = ( usize::from( source ), usize::from( target ));
+		if let Some( edge ) = self.edges.iter_mut().find( |edge| ( edge.0, edge.1 ) == key ) {
+			return Some( std::mem::replace( &mut edge.2, data ))
+		}
+		self.edges.push(( key.0, key.1, data ));
+		None
+	}
+	
+	/// Removes the edge from `source` to `target`, returns its data if present.
+	#[inline]
+	pub fn remove< E > ( &mut self, source: EntityId< E >, target: EntityId< E > ) -> Option< R::Data > {
+		let key = ( usize::from( source ), usize::from( target ));
+		let idx = self.edges.iter().position( |edge| ( edge.0, edge.1 ) == key )?;
+		Some( self.edges.remove( idx ).2 )
+	}
+	
+	/// Returns the data of the edge from `source` to `target` if present.
+	#[inline]
+	pub fn get< E > ( &self, source: EntityId< E >, target: EntityId< E > ) -> Option< &R::Data > {
+		let key = ( usize::from( source ), usize::from( target ));
+		self.edges.iter().find( |edge| ( edge.0, edge.1 ) == key ).map( |edge| &edge.2 )
+	}
+	
+	/// Removes all edges starting or ending at the entity.
+	#[inline]
+	pub fn detach< E > ( &mut self, id: EntityId< E > ) {
+		let idx = usize::from( id );
+		self.edges.retain( |edge| edge.0 != idx && edge.1 != idx );
+	}
+	
+	/// Returns an iterator over all edges, in the order they were added.
+	#[inline]
+	pub fn iter< E > ( &self ) -> Edges< '_, R, E > {
+		Edges::new( self, None, None )
+	}
+	
+	/// Returns an iterator over edges starting at `source`.
+	#[inline]
+	pub fn outgoing< E > ( &self, source: EntityId< E > ) -> Edges< '_, R, E > {
+		Edges::new( self, Some( usize::from( source )), None )
+	}
+	
+	/// Returns an iterator over edges ending at `target`.
+	#[inline]
+	pub fn incoming< E > ( &self, target: EntityId< E > ) -> Edges< '_, R, E > {
+		Edges::new( self, None, Some( usize::from( target )))
+	}
+	
+	/// Returns the amount of edges.
+	#[inline]
+	pub fn len ( &self ) -> usize {
+		self.edges.len()
+	}
+	
+	#[inline]
+	pub fn is_empty ( &self ) -> bool {
+		self.edges.is_empty()
+	}
+}
+
+impl< R > Relations< R >
+where
+	R: Relation,
+	R::Data: PartialEq,
+{
+	/// This function is intended for internal use only, it is called by generated `PartialEq`.
+	/// 
+	/// Compares edges between entities of `map` with edges between entities of `other_map`, under their new ids, regardless of order.
+	#[inline]
+	pub fn eq_mapped ( &self, map: &EntityMap, other: &Self, other_map: &EntityMap ) -> bool {
+		self.mapped_edges( map ) == other.mapped_edges( other_map )
+	}
+	
+	/// Returns edges between entities of `map` under their new ids, sorted by source and target.
+	fn mapped_edges ( &self, map: &EntityMap ) -> Vec< ( usize, usize, &R::Data ) > {
+		let mut edges: Vec< _ > = self.edges.iter()
+			.filter_map( |edge| match ( map.get( EntityId::< () >::from( edge.0 )), map.get( EntityId::< () >::from( edge.1 ))) {
+				( Some( source ), Some( target )) => Some(( usize::from( source ), usize::from( target ), &edge.2 )),
+				_ => None,
+			})
+			.collect();
+		edges.sort_by_key( |edge| ( edge.0, edge.1 ));
+		edges
+	}
+}
+
+impl< R: Relation > Default for Relations< R > {
+	#[inline]
+	fn default () -> Self {
+		Self::new()
+	}
+}
+
+// struct - Relations
+//------------------------------------------------------------------------------
+// --Impl Snapshot
+
+impl< R > Relations< R >
+where
+	R: Relation,
+	R::Data: SnapComp + Clone,
+{
+	/// This function is intended for internal use only, it is called by generated `EcsSnapshot::snapshot`.
+	/// 
+	/// Returns the section named `key` storing pairs of target and data for each source entity, with ids rewritten by `map`,
+	/// or `None` when there are no edges. Edges with an end absent in `map` are skipped.
+	/// 
+	/// # Errors
+	/// 
+	/// SnapErr::Value - when data cannot be converted
+	#[inline]
+	pub fn to_section ( &self, key: &str, map: &EntityMap ) -> Result< Option< SnapSection >, SnapErr > {
+		let mut sources: Vec< usize > = self.edges.iter().map( |edge| edge.0 ).collect();
+		sources.sort_unstable();
+		sources.dedup();
+		
+		let mut section = SnapSection::new( key );
+		for source in sources {
+			let Some( new_source ) = map.get( EntityId::< () >::from( source )) else {
+				continue
+			};
+			let list = self.edges.iter()
+				.filter( |edge| edge.0 == source )
+				.filter_map( |edge| map.get( EntityId::< () >::from( edge.1 )).map( |target| ( usize::from( target ), &edge.2 )))
+				.map( |( target, data )| Ok( SnapValue::Seq( vec![ target.to_snap()?, data.to_snap()? ] )))
+				.collect::< Result< Vec< _ >, SnapErr >>()?;
+			if !list.is_empty() {
+				section.push( usize::from( new_source ), SnapValue::Seq( list ));
+			}
+		}
+		Ok(( !section.entries.is_empty() ).then_some( section ))
+	}
+	
+	/// This function is intended for internal use only, it is called by generated `EcsSnapshot::from_snapshot`.
+	/// 
+	/// Restores edges from a section created by `Relations::to_section`.
+	/// 
+	/// # Errors
+	/// 
+	/// SnapErr::NoSuchEntityId - when an id is not lower than `entities`
+	/// SnapErr::Value - when a value is not a list of pairs of target and data
+	#[inline]
+	pub fn load_section ( &mut self, section: SnapSection, entities: usize ) -> Result< (), SnapErr > {
+		for ( source, value ) in section.entries {
+			let SnapValue::Seq( list ) = value else {
+				return Err( value.unexpected( "sequence" ))
+			};
+			for pair in list {
+				let ( target, data ) = match pair {
+					SnapValue::Seq( vec ) if vec.len() == 2 => {
+						let mut iter = vec.into_iter();
+						match ( iter.next(), iter.next() ) {
+							( Some( target ), Some( data )) => ( usize::from_snap( target )?, R::Data::from_snap( data )? ),
+							_ => return Err( SnapErr::Value( "pair too short".to_owned() )),
+						}
+					}
+					other => return Err( other.unexpected( "pair of target and data" )),
+				};
+				if let Some( &idx ) = [ source, target ].iter().find( |&&idx| idx >= entities ) {
+					return Err( SnapErr::NoSuchEntityId( idx ))
+				}
+				_ = self.insert( EntityId::< () >::from( source ), EntityId::from( target ), data );
+			}
+		}
+		Ok(())
+	}
+}
+
+// --Impl Snapshot
+//------------------------------------------------------------------------------
+// struct - Edges
+
+/// Iterator over edges of relation `R`, yielding source, target and data of each edge.
+#[derive( Debug )]
+pub struct Edges< 'a, R: Relation, E > {
+	data: core::slice::Iter< 'a, ( usize, usize, R::Data ) >,
+	source: Option< usize >,
+	target: Option< usize >,
+	marker: std::marker::PhantomData< E >,
+}
+
+impl< 'a, R: Relation, E > Edges< 'a, R, E > {
+	fn new ( relations: &'a Relations< R >, source: Option< usize >, target: Option< usize > ) -> Self {
+		Self {
+			data: relations.edges.iter(),
+			source,
+			target,
+			marker: std::marker::PhantomData,
+		}
+	}
+}
+
+impl< 'a, R: Relation, E > Iterator for Edges< 'a, R, E > {
+	type Item = ( EntityId< E >, EntityId< E >, &'a R::Data );
+	
+	#[inline]
+	fn next( &mut self ) -> Option< Self::Item > {
+		let ( source, target ) = ( self.source, self.target );
+		self.data.by_ref()
+			.find( |edge| source.map_or( true, |idx| edge.0 == idx ) && target.map_or( true, |idx| edge.1 == idx ))
+			.map( |edge| ( EntityId::from( edge.0 ), EntityId::from( edge.1 ), &edge.2 ))
+	}
+}
+
+// struct - Edges
+//------------------------------------------------------------------------------
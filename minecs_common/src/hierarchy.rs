@@ -0,0 +1,574 @@
+use std::hash::{ Hash, Hasher };
+
+use crate::{ EcsErr, EcsMain, EntityId, EntityMap, SnapComp, SnapErr, SnapSection };
+
+//------------------------------------------------------------------------------
+// --Traits
+
+/// Parent/child relations between entities, implemented by `minecs!` for every ECS.
+/// 
+/// Both directions are kept consistent: setting a parent adds the entity to the children of the parent,
+/// removing it or despawning either side removes it from both.
+pub trait EcsHierarchy< E: Clone >: EcsMain< E > {
+	/// Borrows the relations of all entities.
+	fn hierarchy ( &self ) -> &Hierarchy;
+	
+	/// Mutably borrows the relations of all entities.
+	fn hierarchy_mut ( &mut self ) -> &mut Hierarchy;
+	
+	/// Removes all components of the entity, detaches it from its parent and children, the children becoming roots,
+	/// and marks it as despawned, after which `EcsMain::entity` returns `None` for its id.
+	/// 
+	/// Entity ids are never reused, the despawned entity keeps its slot. Nothing is changed when an error is returned.
+	/// 
+	/// # Errors
+	/// 
+	/// EcsErr::NoSuchEntityId - when entity with specified id does not exist or was already despawned
+	/// EcsErr::CompVecMissingComponent - when a component present in the entity cannot be borrowed from `CompVec`
+	fn despawn ( &mut self, id: EntityId< E > ) -> Result< (), EcsErr >;
+	
+	/// Checks whether `EcsHierarchy::despawn` of the entity would succeed, without changing anything.
+	/// 
+	/// # Errors
+	/// 
+	/// Same as `EcsHierarchy::despawn`.
+	fn check_despawn ( &self, id: EntityId< E > ) -> Result< (), EcsErr >;
+	
+	/// Despawns the entity along with all its descendants.
+	/// All of them are checked before the first is despawned, so nothing is changed when an error is returned.
+	/// 
+	/// # Errors
+	/// 
+	/// Same as `EcsHierarchy::despawn`.
+	#[inline]
+	fn despawn_recursive ( &mut self, id: EntityId< E > ) -> Result< (), EcsErr > {
+		self.check_despawn( id )?;
+		
+		let mut ids: Vec< EntityId< E > > = self.hierarchy().descendants( id ).collect();
+		for &item in &ids {
+			self.check_despawn( item )?;
+		}
+		
+		ids.push( id );
+		for item in ids {
+			self.despawn( item )?;
+		}
+		Ok(())
+	}
+	
+	/// Makes `parent` the parent of `child`, detaching `child` from its previous parent.
+	/// Returns the previous parent in `Ok( Some( EntityId ) )` if present.
+	/// 
+	/// # Errors
+	/// 
+	/// EcsErr::NoSuchEntityId - when either entity does not exist
+	/// EcsErr::HierarchyCycle - when `parent` is `child` or one of its descendants
+	#[inline]
+	fn set_parent ( &mut self, child: EntityId< E >, parent: EntityId< E > ) -> Result< Option< EntityId< E >>, EcsErr > {
+		for id in [ child, parent ] {
+			if self.entity( id ).is_none() {
+				return Err( EcsErr::NoSuchEntityId( usize::from( id )))
+			}
+		}
+		self.hierarchy_mut().set_parent( child, parent )
+	}
+	
+	/// Detaches the entity from its parent, returns the parent if present.
+	#[inline]
+	fn remove_parent ( &mut self, child: EntityId< E > ) -> Option< EntityId< E >> {
+		self.hierarchy_mut().remove_parent( child )
+	}
+	
+	/// Returns the parent of the entity if present.
+	#[inline]
+	fn parent ( &self, child: EntityId< E > ) -> Option< EntityId< E >> {
+		self.hierarchy().parent( child )
+	}
+	
+	/// Returns an iterator over the children of the entity, in the order they were added.
+	#[inline]
+	fn children ( &self, parent: EntityId< E > ) -> Children< '_, E > {
+		self.hierarchy().children( parent )
+	}
+	
+	/// Returns an iterator over the parent of the entity, its parent and so on up to the root.
+	#[inline]
+	fn ancestors ( &self, child: EntityId< E > ) -> Ancestors< '_, E > {
+		self.hierarchy().ancestors( child )
+	}
+	
+	/// Returns a depth-first iterator over all descendants of the entity, excluding itself.
+	#[inline]
+	fn descendants ( &self, parent: EntityId< E > ) -> Descendants< '_, E > {
+		self.hierarchy().descendants( parent )
+	}
+}
+
+// --Traits
+//------------------------------------------------------------------------------
+// struct - Hierarchy
+
+/// Parents and ordered children of entities, stored in every ECS generated by `minecs!`.
+/// 
+/// Equality and hash consider only entities with children, fe. after `remove_parent` the hierarchy
+/// equals one where the parent was never set.
+#[cfg_attr( feature = "serde", derive( serde::Serialize, serde::Deserialize ) )]
+#[derive( Debug, Clone, Default )]
+pub struct Hierarchy {
+	parents: Vec< Option< usize > >,
+	children: Vec< Vec< usize > >,
+}
+
+impl Hierarchy {
+	/// Name of the snapshot section storing children of entities.
+	pub const SNAPSHOT_KEY: &'static str = "@children";
+	
+	#[inline]
+	pub const fn new () -> Self {
+		Self {
+			parents: Vec::new(),
+			children: Vec::new(),
+		}
+	}
+	
+	/// Returns the parent of the entity if present.
+	#[inline]
+	pub fn parent< E > ( &self, child: EntityId< E > ) -> Option< EntityId< E >> {
+		self.parents.get( usize::from( child )).copied().flatten().map( EntityId::from )
+	}
+	
+	/// Returns an iterator over the children of the entity, in the order they were added.
+	#[inline]
+	pub fn children< E > ( &self, parent: EntityId< E > ) -> Children< '_, E > {
+		Children {
+			data: self.children_of( usize::from( parent )).iter(),
+			marker: std::marker::PhantomData,
+		}
+	}
+	
+	/// Returns an iterator over the parent of the entity, its parent and so on up to the root.
+	#[inline]
+	pub fn ancestors< E > ( &self, child: EntityId< E > ) -> Ancestors< '_, E > {
+		Ancestors {
+			hierarchy: self,
+			current: usize::from( child ),
+			marker: std::marker::PhantomData,
+		}
+	}
+	
+	/// Returns a depth-first iterator over all descendants of the entity, excluding itself.
+	#[inline]
+	pub fn descendants< E > ( &self, parent: EntityId< E > ) -> Descendants< '_, E > {
+		let mut stack: Vec< usize > = self.children_of( usize::from( parent )).to_vec();
+		stack.reverse();
+		
+		Descendants {
+			hierarchy: self,
+			stack,
+			marker: std::marker::PhantomData,
+		}
+	}
+	
+	/// Makes `parent` the parent of `child`, detaching `child` from its previous parent.
+	/// Returns the previous parent in `Ok( Some( EntityId ) )` if present.
+	/// 
+	/// Existence of the entities is not checked, refer to `EcsHierarchy::set_parent`.
+	/// 
+	/// # Errors
+	/// 
+	/// EcsErr::HierarchyCycle - when `parent` is `child` or one of its descendants
+	#[inline]
+	pub fn set_parent< E > ( &mut self, child: EntityId< E >, parent: EntityId< E > ) -> Result< Option< EntityId< E >>, EcsErr > {
+		let child_idx = usize::from( child );
+		let parent_idx = usize::from( parent );
+		
+		let mut current = Some( parent_idx );
+		while let Some( idx ) = current {
+			if idx == child_idx {
+				return Err( EcsErr::HierarchyCycle( child_idx, parent_idx ))
+			}
+			current = self.parents.get( idx ).copied().flatten();
+		}
+		
+		let old = self.detach_parent( child_idx );
+		if self.parents.len() <= child_idx {
+			self.parents.resize( child_idx + 1, None );
+		}
+		if self.children.len() <= parent_idx {
+			self.children.resize_with( parent_idx + 1, Vec::new );
+		}
+		
+		if let Some( slot ) = self.parents.get_mut( child_idx ) {
+			*slot = Some( parent_idx );
+		}
+		if let Some( list ) = self.children.get_mut( parent_idx ) {
+			list.push( child_idx );
+		}
+		Ok( old.map( EntityId::from ))
+	}
+	
+	/// Detaches the entity from its parent, returns the parent if present.
+	#[inline]
+	pub fn remove_parent< E > ( &mut self, child: EntityId< E > ) -> Option< EntityId< E >> {
+		self.detach_parent( usize::from( child )).map( EntityId::from )
+	}
+	
+	/// Detaches the entity from its parent and children, the children becoming roots.
+	#[inline]
+	pub fn detach< E > ( &mut self, id: EntityId< E > ) {
+		let idx = usize::from( id );
+		_ = self.detach_parent( idx );
+		
+		let children = self.children.get_mut( idx ).map( std::mem::take ).unwrap_or_default();
+		for child in children {
+			if let Some( slot ) = self.parents.get_mut( child ) {
+				*slot = None;
+			}
+		}
+	}
+	
+	/// Returns an iterator over entities with children along with their children, parents being implied by them.
+	fn links ( &self ) -> impl Iterator< Item = ( usize, &Vec< usize > ) > {
+		self.children.iter().enumerate().filter( |entry| !entry.1.is_empty() )
+	}
+	
+	/// Returns children of entities of `map` under their new ids, skipping entities without children.
+	fn mapped_links< 'a > ( &'a self, map: &'a EntityMap ) -> impl Iterator< Item = ( usize, Vec< usize > ) > + 'a {
+		map.iter()
+			.map( |( old_idx, new_idx )| {
+				let children = self.children_of( old_idx ).iter()
+					.filter_map( |&child| map.get( EntityId::< () >::from( child )).map( usize::from ))
+					.collect();
+				( new_idx, children )
+			})
+			.filter( |link: &( usize, Vec< usize > )| !link.1.is_empty() )
+	}
+	
+	fn children_of ( &self, idx: usize ) -> &[usize] {
+		self.children.get( idx ).map_or( &[], Vec::as_slice )
+	}
+	
+	fn detach_parent ( &mut self, child: usize ) -> Option< usize > {
+		let old = self.parents.get_mut( child ).and_then( Option::take )?;
+		if let Some( list ) = self.children.get_mut( old ) {
+			list.retain( |&idx| idx != child );
+		}
+		Some( old )
+	}
+}
+
+impl PartialEq for Hierarchy {
+	#[inline]
+	fn eq ( &self, other: &Self ) -> bool {
+		self.links().eq( other.links() )
+	}
+}
+
+impl Hierarchy {
+	/// This function is intended for internal use only, it is called by generated `PartialEq`.
+	/// 
+	/// Compares children of entities of `map` with children of entities of `other_map`, under their new ids.
+	#[inline]
+	pub fn eq_mapped ( &self, map: &EntityMap, other: &Self, other_map: &EntityMap ) -> bool {
+		self.mapped_links( map ).eq( other.mapped_links( other_map ))
+	}
+}
+
+impl Eq for Hierarchy {}
+
+impl Hash for Hierarchy {
+	#[inline]
+	fn hash< H: Hasher > ( &self, state: &mut H ) {
+		for link in self.links() {
+			link.hash( state );
+		}
+	}
+}
+
+// struct - Hierarchy
+//------------------------------------------------------------------------------
+// struct - Despawned
+
+/// Sorted ids of despawned entities, stored in every ECS generated by `minecs!`.
+/// 
+/// Entity ids are never reused, a despawned entity keeps its slot but can no longer be borrowed.
+#[cfg_attr( feature = "serde", derive( serde::Serialize, serde::Deserialize ) )]
+#[derive( Debug, Clone, Default, PartialEq, Eq, Hash )]
+pub struct Despawned {
+	ids: Vec< usize >,
+}
+
+impl Despawned {
+	#[inline]
+	pub const fn new () -> Self {
+		Self {
+			ids: Vec::new(),
+		}
+	}
+	
+	/// Returns true if the entity with specified index was despawned, or false otherwise.
+	#[inline]
+	pub fn contains ( &self, idx: usize ) -> bool {
+		self.ids.binary_search( &idx ).is_ok()
+	}
+	
+	/// Marks the entity with specified index as despawned, returns false if it already was.
+	#[inline]
+	pub fn insert ( &mut self, idx: usize ) -> bool {
+		match self.ids.binary_search( &idx ) {
+			Ok( _ ) => false,
+			Err( pos ) => {
+				self.ids.insert( pos, idx );
+				true
+			}
+		}
+	}
+	
+	/// Returns true if no entity was despawned, or false otherwise.
+	#[inline]
+	pub fn is_empty ( &self ) -> bool {
+		self.ids.is_empty()
+	}
+	
+	/// Maps ids of live entities out of `entities` to consecutive ids, keeping their order, as they are saved by `EcsSnapshot::snapshot`.
+	#[inline]
+	pub fn compact ( &self, entities: usize ) -> EntityMap {
+		let mut map = EntityMap::new();
+		for ( new_idx, old_idx ) in ( 0 .. entities ).filter( |&idx| !self.contains( idx )).enumerate() {
+			_ = map.insert( old_idx, new_idx );
+		}
+		map
+	}
+	
+	/// Borrows sorted ids of despawned entities.
+	#[inline]
+	pub fn as_slice ( &self ) -> &[usize] {
+		&self.ids
+	}
+	
+	/// This function is intended for internal use only, it is called by methods generated by `new_ecs!`.
+	/// 
+	/// Borrows the entity with specified index unless it was despawned.
+	#[inline]
+	pub fn get< 'a, E > ( &self, entities: &'a [E], idx: usize ) -> Option< &'a E > {
+		if self.contains( idx ) { None } else { entities.get( idx ) }
+	}
+	
+	/// This function is intended for internal use only, it is called by methods generated by `new_ecs!`.
+	/// 
+	/// Mutably borrows the entity with specified index unless it was despawned.
+	#[inline]
+	pub fn get_mut< 'a, E > ( &self, entities: &'a mut [E], idx: usize ) -> Option< &'a mut E > {
+		if self.contains( idx ) { None } else { entities.get_mut( idx ) }
+	}
+}
+
+// struct - Despawned
+//------------------------------------------------------------------------------
+// --Impl Snapshot
+
+impl Hierarchy {
+	/// This function is intended for internal use only, it is called by generated `EcsSnapshot::snapshot`.
+	/// 
+	/// Returns the section storing children of entities of `map` under their new ids, or `None` when no entity has children.
+	/// 
+	/// # Errors
+	/// 
+	/// SnapErr::Value - when an id cannot be converted
+	#[inline]
+	pub fn to_section ( &self, map: &EntityMap ) -> Result< Option< SnapSection >, SnapErr > {
+		let mut section = SnapSection::new( Self::SNAPSHOT_KEY );
+		for ( new_idx, children ) in self.mapped_links( map ) {
+			section.push( new_idx, children.to_snap()? );
+		}
+		Ok(( !section.entries.is_empty() ).then_some( section ))
+	}
+	
+	/// This function is intended for internal use only, it is called by generated `EcsSnapshot::from_snapshot`.
+	/// 
+	/// Restores children of entities from a section created by `Hierarchy::to_section`.
+	/// 
+	/// # Errors
+	/// 
+	/// SnapErr::NoSuchEntityId - when an id is not lower than `entities`
+	/// SnapErr::Value - when a value is not a list of ids, or the relations form a cycle
+	#[inline]
+	pub fn load_section ( &mut self, section: SnapSection, entities: usize ) -> Result< (), SnapErr > {
+		for ( parent, value ) in section.entries {
+			for child in < Vec< usize > as SnapComp >::from_snap( value )? {
+				if let Some( &idx ) = [ parent, child ].iter().find( |&&idx| idx >= entities ) {
+					return Err( SnapErr::NoSuchEntityId( idx ))
+				}
+				self.set_parent( EntityId::< () >::from( child ), EntityId::from( parent ))
+					.map_err( |err| SnapErr::Value( err.to_string() ))?;
+			}
+		}
+		Ok(())
+	}
+}
+
+// --Impl Snapshot
+//------------------------------------------------------------------------------
+// struct - Children
+
+/// Iterator over children of an entity, created by `EcsHierarchy::children`.
+#[derive( Debug, Clone )]
+pub struct Children< 'a, E > {
+	data: core::slice::Iter< 'a, usize >,
+	marker: std::marker::PhantomData< E >,
+}
+
+impl< 'a, E > Iterator for Children< 'a, E > {
+	type Item = EntityId< E >;
+	
+	#[inline]
+	fn next( &mut self ) -> Option< Self::Item > {
+		self.data.next().map( |&idx| EntityId::from( idx ))
+	}
+}
+
+// struct - Children
+//------------------------------------------------------------------------------
+// struct - Ancestors
+
+/// Iterator over ancestors of an entity, created by `EcsHierarchy::ancestors`.
+#[derive( Debug, Clone )]
+pub struct Ancestors< 'a, E > {
+	hierarchy: &'a Hierarchy,
+	current: usize,
+	marker: std::marker::PhantomData< E >,
+}
+
+impl< 'a, E > Iterator for Ancestors< 'a, E > {
+	type Item = EntityId< E >;
+	
+	#[inline]
+	fn next( &mut self ) -> Option< Self::Item > {
+		let parent = self.hierarchy.parents.get( self.current ).copied().flatten()?;
+		self.current = parent;
+		Some( EntityId::from( parent ))
+	}
+}
+
+// struct - Ancestors
+//------------------------------------------------------------------------------
+// struct - Descendants
+
+/// Depth-first iterator over descendants of an entity, created by `EcsHierarchy::descendants`.
+#[derive( Debug, Clone )]
+pub struct Descendants< 'a, E > {
+	hierarchy: &'a Hierarchy,
+	stack: Vec< usize >,
+	marker: std::marker::PhantomData< E >,
+}
+
+impl< 'a, E > Iterator for Descendants< 'a, E > {
+	type Item = EntityId< E >;
+	
+	#[inline]
+	fn next( &mut self ) -> Option< Self::Item > {
+		let idx = self.stack.pop()?;
+		self.stack.extend( self.hierarchy.children_of( idx ).iter().rev() );
+		Some( EntityId::from( idx ))
+	}
+}
+
+// struct - Descendants
+//------------------------------------------------------------------------------
+// --Tests
+
+#[cfg( test )]
+#[allow( dead_code )]// not every generated method is used by tests
+mod tests {
+	use std::collections::hash_map::DefaultHasher;
+	use std::hash::{ Hash, Hasher };
+	
+	use crate::*;
+	use minecs_macro::minecs;
+	
+	minecs!( ecs TestEcs< TestArray, TestEntity > { types [ f64, usize ] } );
+	
+	fn hash_of( hierarchy: &Hierarchy ) -> u64 {
+		let mut hasher = DefaultHasher::new();
+		hierarchy.hash( &mut hasher );
+		hasher.finish()
+	}
+	
+	#[test]
+	fn despawned_entity_is_gone() {
+		let mut ecs = TestEcs::new();
+		let [ parent, child ] = [ ecs.new_entity(), ecs.new_entity() ];
+		_ = ecs.insert( child, 1.5_f64 );
+		ecs.set_parent( child, parent ).unwrap();
+		
+		assert_eq!( ecs.despawn( child ), Ok(()));
+		assert!( ecs.entity( child ).is_none() );
+		assert_eq!( ecs.children( parent ).count(), 0 );
+		
+		assert_eq!( ecs.despawn( child ), Err( EcsErr::NoSuchEntityId( 1 )));
+		assert_eq!( ecs.insert( child, 2_usize ), None );
+		assert_eq!( ecs.set_parent( child, parent ), Err( EcsErr::NoSuchEntityId( 1 )));
+		assert_eq!( ecs.despawn_recursive( child ), Err( EcsErr::NoSuchEntityId( 1 )));
+		
+		// ids are not reused
+		assert_eq!( usize::from( ecs.new_entity() ), 2 );
+		assert_eq!( ecs.validate(), Ok(()) );
+	}
+	
+	#[test]
+	fn failed_despawn_changes_nothing() {
+		let mut ecs = TestEcs::new();
+		let [ first, parent, child ] = [ ecs.new_entity(), ecs.new_entity(), ecs.new_entity() ];
+		
+		// the entity points at a removed component, so it cannot be despawned
+		let cid = ecs.insert( first, 1.5_f64 ).unwrap();
+		assert_eq!( ecs.remove::< f64 >( first ), Ok(()));
+		_ = ecs.insert( child, 7_usize );
+		_ = ecs.entity_mut( child ).unwrap().set( cid );
+		ecs.set_parent( child, parent ).unwrap();
+		_ = ecs.insert( parent, 3_usize );
+		
+		let expected = Err( EcsErr::CompVecMissingComponent( CompName::of::< f64 >(), 2, 0 ));
+		assert_eq!( ecs.despawn( child ), expected );
+		assert_eq!( ecs.despawn_recursive( parent ), expected );
+		
+		assert!( ecs.entity( child ).is_some() );
+		assert_eq!( ecs.get_for::< usize >( child ), Ok( &7 ));
+		assert_eq!( ecs.get_for::< usize >( parent ), Ok( &3 ));
+		assert_eq!( ecs.parent( child ), Some( parent ));
+	}
+	
+	#[test]
+	fn despawn_recursive_removes_descendants() {
+		let mut ecs = TestEcs::new();
+		let [ root, panel, button ] = [ ecs.new_entity(), ecs.new_entity(), ecs.new_entity() ];
+		ecs.set_parent( panel, root ).unwrap();
+		ecs.set_parent( button, panel ).unwrap();
+		_ = ecs.insert( button, 1_usize );
+		
+		assert_eq!( ecs.despawn_recursive( panel ), Ok(()));
+		assert!( ecs.entity( panel ).is_none() && ecs.entity( button ).is_none() );
+		assert!( ecs.entity( root ).is_some() );
+		assert_eq!( ecs.children( root ).count(), 0 );
+		assert_eq!( ecs.get_for::< usize >( button ), Err( EcsErr::NoSuchEntityId( 2 )));
+	}
+	
+	#[test]
+	fn equality_ignores_empty_links() {
+		let mut ecs = TestEcs::new();
+		let [ parent, child ] = [ ecs.new_entity(), ecs.new_entity() ];
+		let mut other = ecs.clone();
+		
+		ecs.set_parent( child, parent ).unwrap();
+		assert_ne!( ecs, other );
+		assert_eq!( ecs.remove_parent( child ), Some( parent ));
+		assert_eq!( ecs, other );
+		assert_eq!( ecs.hierarchy(), &Hierarchy::new() );
+		assert_eq!( hash_of( ecs.hierarchy() ), hash_of( &Hierarchy::new() ));
+		
+		other.set_parent( child, parent ).unwrap();
+		ecs.set_parent( child, parent ).unwrap();
+		assert_eq!( ecs, other );
+		assert_eq!( hash_of( ecs.hierarchy() ), hash_of( other.hierarchy() ));
+	}
+}
@@ -178,3 +178,37 @@
 
 mod v1;
 pub use v1::*;
+mod app;
+pub use app::*;
+mod debug;
+pub use debug::*;
+mod event;
+pub use event::*;
+mod graph;
+pub use graph::*;
+mod hierarchy;
+pub use hierarchy::*;
+mod merge;
+pub use merge::*;
+mod observer;
+pub use observer::*;
+mod persist;
+pub use persist::*;
+mod persist_bin;
+pub use persist_bin::*;
+mod persist_delta;
+pub use persist_delta::*;
+mod scene;
+pub use scene::*;
+mod schedule;
+pub use schedule::*;
+mod shared;
+pub use shared::*;
+mod state;
+pub use state::*;
+mod tag;
+pub use tag::*;
+mod timer;
+pub use timer::*;
+#[cfg( feature = "serde" )]
+mod persist_serde;
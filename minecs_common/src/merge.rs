@@ -0,0 +1,119 @@
+use crate::EntityId;
+
+//------------------------------------------------------------------------------
+// --Traits
+
+/// Rewriting of `EntityId`s stored inside a component, after entities received new ids.
+/// 
+/// Can be derived with `#[derive( MapEntities )]`, which maps fields marked `#[entity]`.
+/// Components declared with `#[map_entities]` in `minecs!` are mapped by `EcsMerge::merge` and `EcsSnapshot::load_into`.
+pub trait MapEntities {
+	/// Replaces every stored id found in `map` with its new value; ids absent in `map` are left unchanged.
+	fn map_entities ( &mut self, map: &EntityMap );
+	
+	/// Returns a copy of `self` with stored ids rewritten by `map`.
+	#[inline]
+	#[must_use]
+	fn mapped ( &self, map: &EntityMap ) -> Self
+	where
+		Self: Clone
+	{
+		let mut out = self.clone();
+		out.map_entities( map );
+		out
+	}
+}
+
+/// Moving entities of one ECS into another, implemented by `minecs!`.
+pub trait EcsMerge {
+	/// Spawns a copy of every entity of `other` in `self`, along with all its components.
+	/// 
+	/// Components declared with `#[map_entities]` have their stored ids rewritten. Resources of `self` are left unchanged.
+	/// Returns the mapping of ids in `other` to ids in `self`.
+	fn merge ( &mut self, other: &Self ) -> EntityMap;
+}
+
+// --Traits
+//------------------------------------------------------------------------------
+// struct - EntityMap
+
+/// Mapping of old entity ids to new ones.
+#[derive( Debug, Clone, Default, PartialEq )]
+pub struct EntityMap {
+	ids: Vec< Option< usize > >,
+}
+
+impl EntityMap {
+	#[inline]
+	pub const fn new () -> Self {
+		Self {
+			ids: Vec::new(),
+		}
+	}
+	
+	/// Maps `old` id to `new` one, returns previous mapping of `old` if present.
+	#[inline]
+	pub fn insert ( &mut self, old: usize, new: usize ) -> Option< usize > {
+		if self.ids.len() <= old {
+			self.ids.resize( old + 1, None );
+		}
+		self.ids.get_mut( old ).and_then( |slot| slot.replace( new ))
+	}
+	
+	/// Returns the new id of entity with `old` id.
+	#[inline]
+	pub fn get< E > ( &self, old: EntityId< E > ) -> Option< EntityId< E >> {
+		self.ids.get( usize::from( old )).copied().flatten().map( EntityId::from )
+	}
+	
+	/// Returns the amount of mapped ids.
+	#[inline]
+	pub fn len ( &self ) -> usize {
+		self.ids.iter().flatten().count()
+	}
+	
+	#[inline]
+	pub fn is_empty ( &self ) -> bool {
+		self.ids.iter().all( Option::is_none )
+	}
+	
+	/// Iterates over pairs of old and new ids.
+	#[inline]
+	pub fn iter ( &self ) -> impl Iterator< Item = ( usize, usize ) > + '_ {
+		self.ids.iter().enumerate().filter_map( |( old, new )| new.map( |id| ( old, id )))
+	}
+}
+
+// struct - EntityMap
+//------------------------------------------------------------------------------
+// --Impl MapEntities
+
+impl< E: Clone > MapEntities for EntityId< E > {
+	#[inline]
+	fn map_entities ( &mut self, map: &EntityMap ) {
+		if let Some( id ) = map.get( *self ) {
+			*self = id;
+		}
+	}
+}
+
+impl< T: MapEntities > MapEntities for Option< T > {
+	#[inline]
+	fn map_entities ( &mut self, map: &EntityMap ) {
+		if let Some( ref mut inner ) = *self {
+			inner.map_entities( map );
+		}
+	}
+}
+
+impl< T: MapEntities > MapEntities for Vec< T > {
+	#[inline]
+	fn map_entities ( &mut self, map: &EntityMap ) {
+		for inner in self {
+			inner.map_entities( map );
+		}
+	}
+}
+
+// --Impl MapEntities
+//------------------------------------------------------------------------------
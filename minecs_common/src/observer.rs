@@ -0,0 +1,330 @@
+use std::any::{ Any, TypeId };
+use std::sync::Arc;
+
+use crate::{ EcsErr, EcsHierarchy, EntityId };
+
+//------------------------------------------------------------------------------
+// --Traits
+
+/// Type of values passed to observers by `EcsObserve::trigger`.
+pub trait Trigger: 'static {
+	/// Whether the trigger propagates from its target up the parent chain by default, observers can change it via `Triggered::propagate`.
+	const PROPAGATE: bool = false;
+}
+
+/// Callbacks run on triggers, implemented by `minecs!` for every ECS.
+/// 
+/// Observers are registered either globally, or on an entity, and removed along with it by `EcsHierarchy::despawn`.
+pub trait EcsObserve< E: Clone + 'static >: EcsHierarchy< E > + Sized + 'static {
+	/// Borrows the observers registered in the ECS.
+	fn observers ( &self ) -> &Observers< Self, E >;
+	
+	/// Mutably borrows the observers registered in the ECS.
+	fn observers_mut ( &mut self ) -> &mut Observers< Self, E >;
+	
+	/// Registers an observer run on every trigger of type `Tr`, returns its id.
+	#[inline]
+	fn observe< Tr, F > ( &mut self, observer: F ) -> ObserverId
+	where
+		Tr: Trigger,
+		F: Fn( &mut Self, &mut Triggered< Tr, E > ) + Send + Sync + 'static,
+	{
+		self.observers_mut().add( None, observer )
+	}
+	
+	/// Registers an observer run on triggers of type `Tr` targeted at `entity`, or propagated to it, returns its id.
+	#[inline]
+	fn observe_entity< Tr, F > ( &mut self, entity: EntityId< E >, observer: F ) -> ObserverId
+	where
+		Tr: Trigger,
+		F: Fn( &mut Self, &mut Triggered< Tr, E > ) + Send + Sync + 'static,
+	{
+		self.observers_mut().add( Some( usize::from( entity )), observer )
+	}
+	
+	/// Removes an observer, returns `true` if it was registered.
+	#[inline]
+	fn unobserve ( &mut self, id: ObserverId ) -> bool {
+		self.observers_mut().remove( id )
+	}
+	
+	/// Runs observers of `Tr` registered on `target`, then, while the trigger propagates, those registered on its ancestors,
+	/// and finally global observers. Observers are run in the order they were registered, each with mutable access to the ECS.
+	/// On success returns the trigger, as modified by observers.
+	/// 
+	/// # Errors
+	/// 
+	/// EcsErr::NoSuchEntityId - when `target` does not exist or was despawned, no observer is run
+	#[inline]
+	fn trigger< Tr: Trigger > ( &mut self, trigger: Tr, target: EntityId< E > ) -> Result< Tr, EcsErr > {
+		if self.entity( target ).is_none() {
+			return Err( EcsErr::NoSuchEntityId( usize::from( target )))
+		}
+		
+		let mut triggered = Triggered::new( trigger, Some( target ));
+		let mut current = Some( target );
+		while let Some( entity ) = current {
+			triggered.entity = Some( entity );
+			for observer in self.observers().matching::< Tr >( Some( usize::from( entity ))) {
+				observer( self, &mut triggered );
+			}
+			current = if triggered.propagate { self.parent( entity ) } else { None };
+		}
+		
+		triggered.entity = None;
+		for observer in self.observers().matching::< Tr >( None ) {
+			observer( self, &mut triggered );
+		}
+		Ok( triggered.trigger )
+	}
+	
+	/// Runs global observers of `Tr` only, returns the trigger, as modified by observers.
+	#[inline]
+	fn trigger_global< Tr: Trigger > ( &mut self, trigger: Tr ) -> Tr {
+		let mut triggered = Triggered::new( trigger, None );
+		for observer in self.observers().matching::< Tr >( None ) {
+			observer( self, &mut triggered );
+		}
+		triggered.trigger
+	}
+}
+
+// --Traits
+//------------------------------------------------------------------------------
+// struct - Triggered
+
+/// Trigger passed to an observer, along with the entity it targets.
+#[derive( Debug )]
+pub struct Triggered< Tr, E > {
+	trigger: Tr,
+	target: Option< EntityId< E > >, // entity the trigger was targeted at
+	entity: Option< EntityId< E > >, // entity whose observers are run, `None` for global observers
+	propagate: bool,
+}
+
+impl< Tr: Trigger, E: Clone > Triggered< Tr, E > {
+	const fn new ( trigger: Tr, target: Option< EntityId< E > > ) -> Self {
+		Self {
+			trigger,
+			target,
+			entity: target,
+			propagate: Tr::PROPAGATE,
+		}
+	}
+}
+
+impl< Tr, E: Clone > Triggered< Tr, E > {
+	#[inline]
+	pub const fn get ( &self ) -> &Tr {
+		&self.trigger
+	}
+	
+	#[inline]
+	pub fn get_mut ( &mut self ) -> &mut Tr {
+		&mut self.trigger
+	}
+	
+	/// Returns the entity the trigger was targeted at, `None` for `EcsObserve::trigger_global`.
+	#[inline]
+	pub const fn target ( &self ) -> Option< EntityId< E > > {
+		self.target
+	}
+	
+	/// Returns the entity whose observers are run, differing from the target once the trigger propagated, `None` for global observers.
+	#[inline]
+	pub const fn entity ( &self ) -> Option< EntityId< E > > {
+		self.entity
+	}
+	
+	/// Sets whether the trigger propagates to the parent, after the remaining observers of the current entity.
+	#[inline]
+	pub fn propagate ( &mut self, propagate: bool ) {
+		self.propagate = propagate;
+	}
+}
+
+// struct - Triggered
+//------------------------------------------------------------------------------
+// struct - Observers
+
+type ObserverFn< W, Tr, E > = dyn Fn( &mut W, &mut Triggered< Tr, E > ) + Send + Sync;
+
+/// Id of an observer, returned when it is registered.
+#[derive( Debug, Clone, Copy, PartialEq, Eq, Hash )]
+pub struct ObserverId( u64 );
+
+#[derive( Clone )]
+struct Observer {
+	id: ObserverId,
+	trigger: TypeId,
+	entity: Option< usize >,
+	run: Arc< dyn Any + Send + Sync >, // `Box< ObserverFn< W, Tr, E > >`
+}
+
+/// Observers registered in the ECS, stored in every ECS generated by `minecs!`.
+/// 
+/// Observers are behaviour rather than data: clones of the ECS share them, comparison and hashing of the ECS ignore them,
+/// they are serialized as a unit, and neither merged nor saved in snapshots.
+pub struct Observers< W, E > {
+	list: Vec< Observer >,
+	next_id: u64,
+	marker: std::marker::PhantomData< fn() -> ( W, E ) >,// `fn() -> ( W, E )` keeps auto traits independent of `W` and `E`
+}
+
+impl< W: 'static, E: 'static > Observers< W, E > {
+	#[inline]
+	pub const fn new () -> Self {
+		Self {
+			list: Vec::new(),
+			next_id: 0,
+			marker: std::marker::PhantomData,
+		}
+	}
+	
+	/// Returns the amount of registered observers.
+	#[inline]
+	pub fn len ( &self ) -> usize {
+		self.list.len()
+	}
+	
+	#[inline]
+	pub fn is_empty ( &self ) -> bool {
+		self.list.is_empty()
+	}
+	
+	/// Removes observers registered on the entity.
+	#[inline]
+	pub fn detach ( &mut self, entity: EntityId< E > ) {
+		let idx = Some( usize::from( entity ));
+		self.list.retain( |observer| observer.entity != idx );
+	}
+	
+	fn add< Tr, F > ( &mut self, entity: Option< usize >, observer: F ) -> ObserverId
+	where
+		Tr: Trigger,
+		F: Fn( &mut W, &mut Triggered< Tr, E > ) + Send + Sync + 'static,
+	{
+		let id = ObserverId( self.next_id );
+		self.next_id += 1;
+		let run: Box< ObserverFn< W, Tr, E > > = Box::new( observer );
+		self.list.push( Observer {
+			id,
+			trigger: TypeId::of::< Tr >(),
+			entity,
+			run: Arc::new( run ),
+		});
+		id
+	}
+	
+	fn remove ( &mut self, id: ObserverId ) -> bool {
+		let len = self.list.len();
+		self.list.retain( |observer| observer.id != id );
+		self.list.len() != len
+	}
+	
+	/// Clones observers of `Tr` registered on the entity, or global ones for `None`, so that they can be run with the ECS borrowed mutably.
+	fn matching< Tr: Trigger > ( &self, entity: Option< usize > ) -> Vec< Arc< Box< ObserverFn< W, Tr, E > > > > {
+		self.list.iter()
+			.filter( |observer| observer.trigger == TypeId::of::< Tr >() && observer.entity == entity )
+			.filter_map( |observer| Arc::clone( &observer.run ).downcast().ok() )
+			.collect()
+	}
+}
+
+impl< W: 'static, E: 'static > Default for Observers< W, E > {
+	#[inline]
+	fn default () -> Self {
+		Self::new()
+	}
+}
+
+impl< W, E > Clone for Observers< W, E > {
+	#[inline]
+	fn clone ( &self ) -> Self {
+		Self {
+			list: self.list.clone(),
+			next_id: self.next_id,
+			marker: std::marker::PhantomData,
+		}
+	}
+}
+
+impl< W, E > std::fmt::Debug for Observers< W, E > {
+	#[inline]
+	fn fmt( &self, f: &mut std::fmt::Formatter<'_> ) -> std::fmt::Result {
+		f.debug_struct( "Observers" )
+			.field( "len", &self.list.len() )
+			.finish_non_exhaustive()
+	}
+}
+
+#[cfg( feature = "serde" )]
+impl< W, E > serde::Serialize for Observers< W, E > {
+	#[inline]
+	fn serialize< S: serde::Serializer > ( &self, serializer: S ) -> Result< S::Ok, S::Error > {
+		serializer.serialize_unit()
+	}
+}
+
+#[cfg( feature = "serde" )]
+impl< 'de, W: 'static, E: 'static > serde::Deserialize< 'de > for Observers< W, E > {
+	#[inline]
+	fn deserialize< D: serde::Deserializer< 'de > > ( deserializer: D ) -> Result< Self, D::Error > {
+		<()>::deserialize( deserializer ).map( |()| Self::new() )
+	}
+}
+
+// struct - Observers
+//------------------------------------------------------------------------------
+// --Tests
+
+#[cfg( test )]
+#[allow( dead_code )]// not every generated method is used by tests
+mod tests {
+	use crate::*;
+	use minecs_macro::minecs;
+	
+	minecs!( ecs TestEcs< TestArray, TestEntity > { health: u32, } );
+	
+	struct Heal( u32 );
+	
+	impl Trigger for Heal {}
+	
+	fn heal ( ecs: &mut TestEcs, heal: &mut Triggered< Heal, TestEntity > ) {
+		if let Some( health ) = heal.entity().and_then( |id| ecs.health_mut( id )) {
+			*health += heal.get().0;
+		}
+	}
+	
+	#[test]
+	fn dead_targets_are_rejected() {
+		let mut ecs = TestEcs::new();
+		let [ alive, dead ] = [ ecs.new_entity(), ecs.new_entity() ];
+		_ = ecs.insert_health( alive, 10 );
+		_ = ecs.observe_entity( alive, heal );
+		_ = ecs.observe( |_, heal: &mut Triggered< Heal, TestEntity >| heal.get_mut().0 = 0 );
+		assert_eq!( ecs.despawn( dead ), Ok(()));
+		
+		assert!( matches!( ecs.trigger( Heal( 5 ), dead ), Err( EcsErr::NoSuchEntityId( 1 ))));
+		assert!( matches!( ecs.trigger( Heal( 5 ), EntityId::from( 7 )), Err( EcsErr::NoSuchEntityId( 7 ))));
+		assert_eq!( ecs.health( alive ), Some( &10 ));
+		
+		assert_eq!( ecs.trigger( Heal( 5 ), alive ).map( |heal| heal.0 ).ok(), Some( 0 ));
+		assert_eq!( ecs.health( alive ), Some( &15 ));
+	}
+	
+	#[test]
+	fn equality_ignores_observers() {
+		let mut ecs = TestEcs::new();
+		let id = ecs.new_entity();
+		let other = ecs.clone();
+		
+		let observer = ecs.observe_entity( id, heal );
+		assert_eq!( ecs, other );
+		assert!( ecs.unobserve( observer ));
+		assert!( !ecs.unobserve( observer ));
+		
+		_ = ecs.insert_health( id, 1 );
+		assert_ne!( ecs, other );
+	}
+}
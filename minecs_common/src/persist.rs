@@ -0,0 +1,740 @@
+use crate::{ EcsMerge, EntityMap };
+
+//------------------------------------------------------------------------------
+// --Traits
+
+/// Conversion between a component and its type-erased form stored in a `Snapshot`.
+/// 
+/// With the `serde` feature enabled, this trait is implemented for every type which implements both `serde::Serialize` and `serde::de::DeserializeOwned`.
+/// Otherwise it is implemented for primitives, `String`, `Option`, `Vec` and tuples, and needs to be implemented by hand for other types.
+pub trait SnapComp: Sized {
+	/// Attempts to convert `self` into a `SnapValue`.
+	/// 
+	/// # Errors
+	/// 
+	/// SnapErr::Value - when the value cannot be represented, fe. `i128` exceeding `i64`
+	fn to_snap ( &self ) -> Result< SnapValue, SnapErr >;
+	
+	/// Attempts to recreate value from a `SnapValue`.
+	/// 
+	/// # Errors
+	/// 
+	/// SnapErr::Value - when the value has unexpected shape
+	fn from_snap ( value: SnapValue ) -> Result< Self, SnapErr >;
+}
+
+/// Saving and loading of the whole ECS, implemented by `minecs!` for ECS declared with `#[snapshot]`.
+pub trait EcsSnapshot: Sized {
+	/// Creates a snapshot of all entities and their components.
+	/// 
+	/// Only live entities are saved, their ids are compacted to `0 .. Snapshot::entities` keeping their order.
+	/// Ids stored by the hierarchy, relations, tags and components declared with `#[map_entities]` are rewritten along.
+	/// 
+	/// # Errors
+	/// 
+	/// SnapErr::Value - when a component cannot be converted into `SnapValue`
+	fn snapshot ( &self ) -> Result< Snapshot, SnapErr >;
+	
+	/// Creates a new ECS out of a snapshot, rebuilding storage from scratch.
+	/// 
+	/// Components absent in the snapshot, fe. added to the ECS after the snapshot was made, are left absent.
+	/// 
+	/// # Errors
+	/// 
+	/// SnapErr::UnsupportedFormat - when the snapshot was created by an incompatible version of this library
+	/// SnapErr::UnknownComponent - when the snapshot contains a component not present in the ECS
+	/// SnapErr::NoSuchEntityId - when a component refers to an entity not present in the snapshot
+	/// SnapErr::Value - when a component cannot be recreated from its saved value
+	fn from_snapshot ( snapshot: Snapshot ) -> Result< Self, SnapErr >;
+	
+	/// Upgrades the snapshot with `migrations` and then creates a new ECS out of it.
+	/// 
+	/// # Errors
+	/// 
+	/// SnapErr::UnsupportedVersion - when the snapshot is newer than `migrations` support
+	/// SnapErr::DuplicateComponent - when a migration renames a component to a name already present in the snapshot
+	/// Any error returned by a migration or `from_snapshot`
+	#[inline]
+	fn from_snapshot_with ( mut snapshot: Snapshot, migrations: &Migrations ) -> Result< Self, SnapErr > {
+		migrations.apply( &mut snapshot )?;
+		Self::from_snapshot( snapshot )
+	}
+	
+	/// Loads the snapshot into an existing, possibly non-empty ECS, spawning its entities with new ids.
+	/// 
+	/// Components declared with `#[map_entities]` have their stored ids rewritten. Returns the mapping of ids in the snapshot to ids in `self`,
+	/// ids in the snapshot being compacted by `EcsSnapshot::snapshot`.
+	/// 
+	/// # Errors
+	/// 
+	/// Same as `from_snapshot`, in which case `self` is left unchanged.
+	#[inline]
+	fn load_into ( &mut self, snapshot: Snapshot ) -> Result< EntityMap, SnapErr >
+	where
+		Self: EcsMerge
+	{
+		let loaded = Self::from_snapshot( snapshot )?;
+		Ok( self.merge( &loaded ))
+	}
+}
+
+// --Traits
+//------------------------------------------------------------------------------
+// enum - SnapValue
+
+/// Type-erased value of a single component.
+#[cfg_attr( feature = "serde", derive( serde::Serialize, serde::Deserialize ) )]
+#[derive( Debug, Clone, PartialEq )]
+pub enum SnapValue {
+	Unit,
+	Bool( bool ),
+	Int( i64 ),
+	UInt( u64 ),
+	Float( f64 ),
+	Char( char ),
+	Str( String ),
+	Bytes( Vec< u8 > ),
+	None,
+	Some( Box< SnapValue > ),
+	Seq( Vec< SnapValue > ),
+	Map( Vec< ( SnapValue, SnapValue ) > ),
+	Struct( Vec< ( String, SnapValue ) > ),
+	Variant( String, Box< SnapValue > ),
+}
+
+impl SnapValue {
+	/// Returns a short name of the variant, used in error messages.
+	#[inline]
+	pub const fn kind ( &self ) -> &'static str {
+		match *self {
+			Self::Unit => "unit",
+			Self::Bool( _ ) => "bool",
+			Self::Int( _ ) => "int",
+			Self::UInt( _ ) => "uint",
+			Self::Float( _ ) => "float",
+			Self::Char( _ ) => "char",
+			Self::Str( _ ) => "string",
+			Self::Bytes( _ ) => "bytes",
+			Self::None | Self::Some( _ ) => "option",
+			Self::Seq( _ ) => "sequence",
+			Self::Map( _ ) => "map",
+			Self::Struct( _ ) => "struct",
+			Self::Variant( _, _ ) => "enum variant",
+		}
+	}
+	
+	/// Creates `SnapErr::Value` describing a mismatch between `self` and the `expected` kind.
+	#[inline]
+	#[must_use]
+	pub fn unexpected ( &self, expected: &str ) -> SnapErr {
+		SnapErr::Value( format!( "expected {expected}, found {}", self.kind() ))
+	}
+}
+
+// enum - SnapValue
+//------------------------------------------------------------------------------
+// struct - Snapshot
+
+/// Saved state of an ECS, keyed by component names instead of the internal layout.
+/// 
+/// Named components are keyed by the field name, fe. `health`, generic components by their type, fe. `Vec<Points>`.
+#[cfg_attr( feature = "serde", derive( serde::Serialize, serde::Deserialize ) )]
+#[derive( Debug, Clone, PartialEq )]
+pub struct Snapshot {
+	/// Version of the layout of `Snapshot` itself, see `Snapshot::FORMAT`.
+	pub format: u32,
+	/// Version of the saved data, chosen by the user.
+	pub version: u32,
+	/// Amount of saved entities, their ids are `0 .. entities`.
+	pub entities: usize,
+	/// Saved components, one section per component.
+	pub components: Vec< SnapSection >,
+}
+
+impl Snapshot {
+	/// Current version of the layout of `Snapshot`.
+	pub const FORMAT: u32 = 1;
+	
+	/// Creates an empty snapshot of `entities` entities.
+	#[inline]
+	pub const fn new ( entities: usize ) -> Self {
+		Self {
+			format: Self::FORMAT,
+			version: 0,
+			entities,
+			components: Vec::new(),
+		}
+	}
+	
+	/// Checks whether the snapshot can be loaded by this version of the library.
+	/// 
+	/// # Errors
+	/// 
+	/// SnapErr::UnsupportedFormat - when the format of the snapshot differs from `Snapshot::FORMAT`
+	#[inline]
+	pub const fn check_format ( &self ) -> Result< (), SnapErr > {
+		if self.format == Self::FORMAT {
+			Ok(())
+		} else {
+			Err( SnapErr::UnsupportedFormat( self.format ))
+		}
+	}
+	
+	/// Returns the section with specified name if present.
+	#[inline]
+	pub fn section ( &self, name: &str ) -> Option< &SnapSection > {
+		self.components.iter().find( |section| section.name == name )
+	}
+	
+	/// Returns the section with specified name if present.
+	#[inline]
+	pub fn section_mut ( &mut self, name: &str ) -> Option< &mut SnapSection > {
+		self.components.iter_mut().find( |section| section.name == name )
+	}
+}
+
+/// All saved components of a single kind.
+#[cfg_attr( feature = "serde", derive( serde::Serialize, serde::Deserialize ) )]
+#[derive( Debug, Clone, PartialEq )]
+pub struct SnapSection {
+	/// Name of the component.
+	pub name: String,
+	/// Pairs of entity id and saved value.
+	pub entries: Vec< ( usize, SnapValue ) >,
+}
+
+impl SnapSection {
+	/// Creates an empty section.
+	#[inline]
+	pub fn new ( name: &str ) -> Self {
+		Self {
+			name: name.to_owned(),
+			entries: Vec::new(),
+		}
+	}
+	
+	/// Adds value of the component of entity with specified id.
+	#[inline]
+	pub fn push ( &mut self, entity: usize, value: SnapValue ) {
+		self.entries.push(( entity, value ));
+	}
+}
+
+// struct - Snapshot
+//------------------------------------------------------------------------------
+// struct - Migrations
+
+/// Conversion of a single saved value, used by `MigrationStep::Convert`.
+pub type SnapConvert = fn( SnapValue ) -> Result< SnapValue, SnapErr >;
+
+/// Single change of a snapshot's components.
+#[derive( Debug, Clone )]
+pub enum MigrationStep {
+	Rename { from: String, to: String }, // renames component section
+	Convert { name: String, convert: SnapConvert }, // converts every saved value of the component
+	Drop( String ), // removes the component from the snapshot
+}
+
+impl MigrationStep {
+	/// Applies the step to the snapshot. Steps referring to components absent in the snapshot do nothing.
+	/// 
+	/// # Errors
+	/// 
+	/// SnapErr::DuplicateComponent - when renaming to a name already present in the snapshot
+	/// Any error returned by `convert`
+	#[inline]
+	pub fn apply ( &self, snapshot: &mut Snapshot ) -> Result< (), SnapErr > {
+		match *self {
+			Self::Rename { ref from, ref to } => {
+				if snapshot.section( from ).is_some() && snapshot.section( to ).is_some() {
+					return Err( SnapErr::DuplicateComponent( to.clone() ))
+				}
+				if let Some( section ) = snapshot.section_mut( from ) {
+					section.name.clone_from( to );
+				}
+			}
+			Self::Convert { ref name, convert } => {
+				if let Some( section ) = snapshot.section_mut( name ) {
+					for entry in &mut section.entries {
+						let value = std::mem::replace( &mut entry.1, SnapValue::Unit );
+						entry.1 = convert( value )?;
+					}
+				}
+			}
+			Self::Drop( ref name ) => snapshot.components.retain( |section| section.name != *name ),
+		}
+		
+		Ok(())
+	}
+}
+
+/// Registry of steps upgrading snapshots made by older versions of an ECS.
+/// 
+/// Steps registered for version `n` upgrade a snapshot from version `n` to `n + 1`, they run in order of registration.
+/// All steps run on the `Snapshot` before any value reaches the ECS, so the component types can change freely between versions.
+/// 
+/// ```
+/// # use minecs_common::*;
+/// fn double ( value: SnapValue ) -> Result< SnapValue, SnapErr > {
+/// 	let val = u32::from_snap( value )?;
+/// 	( val * 2 ).to_snap()
+/// }
+/// 
+/// let migrations = Migrations::new( 2 )
+/// 	.rename( 0, "armor", "armor_stats" )
+/// 	.drop( 0, "old_cache" )
+/// 	.convert( 1, "armor_stats", double );
+/// 
+/// let mut armor = SnapSection::new( "armor" );
+/// armor.push( 0, 5_u32.to_snap().unwrap() );
+/// let mut snapshot = Snapshot::new( 1 );
+/// snapshot.components.push( armor );
+/// snapshot.components.push( SnapSection::new( "old_cache" ));
+/// 
+/// migrations.apply( &mut snapshot ).unwrap();
+/// assert_eq!( snapshot.version, 2 );
+/// assert_eq!( snapshot.components.len(), 1 );
+/// assert_eq!( snapshot.section( "armor_stats" ).unwrap().entries, vec![( 0, 10_u32.to_snap().unwrap() )] );
+/// ```
+#[derive( Debug, Clone )]
+pub struct Migrations {
+	version: u32,
+	steps: Vec< ( u32, MigrationStep ) >,
+}
+
+impl Migrations {
+	/// Creates an empty registry, upgrading snapshots up to `version`, the current version of the ECS.
+	#[inline]
+	pub const fn new ( version: u32 ) -> Self {
+		Self {
+			version,
+			steps: Vec::new(),
+		}
+	}
+	
+	/// Returns the version snapshots are upgraded to.
+	#[inline]
+	pub const fn version ( &self ) -> u32 {
+		self.version
+	}
+	
+	/// Registers a step upgrading snapshots of version `from_version`.
+	#[inline]
+	#[must_use]
+	pub fn step ( mut self, from_version: u32, step: MigrationStep ) -> Self {
+		self.steps.push(( from_version, step ));
+		self
+	}
+	
+	/// Registers renaming of a component in snapshots of version `from_version`.
+	#[inline]
+	#[must_use]
+	pub fn rename ( self, from_version: u32, from: &str, to: &str ) -> Self {
+		self.step( from_version, MigrationStep::Rename { from: from.to_owned(), to: to.to_owned() })
+	}
+	
+	/// Registers conversion of values of a component in snapshots of version `from_version`.
+	#[inline]
+	#[must_use]
+	pub fn convert ( self, from_version: u32, name: &str, convert: SnapConvert ) -> Self {
+		self.step( from_version, MigrationStep::Convert { name: name.to_owned(), convert })
+	}
+	
+	/// Registers removal of a component from snapshots of version `from_version`.
+	#[inline]
+	#[must_use]
+	pub fn drop ( self, from_version: u32, name: &str ) -> Self {
+		self.step( from_version, MigrationStep::Drop( name.to_owned() ))
+	}
+	
+	/// Upgrades the snapshot to `self.version()`, running steps of each version it passes through.
+	/// 
+	/// # Errors
+	/// 
+	/// SnapErr::UnsupportedVersion - when the snapshot is newer than `self.version()`
+	/// SnapErr::DuplicateComponent - when renaming to a name already present in the snapshot
+	/// Any error returned by a conversion
+	#[inline]
+	pub fn apply ( &self, snapshot: &mut Snapshot ) -> Result< (), SnapErr > {
+		if snapshot.version > self.version {
+			return Err( SnapErr::UnsupportedVersion( snapshot.version ))
+		}
+		
+		while snapshot.version < self.version {
+			let current = snapshot.version;
+			for step in self.steps.iter().filter( |pair| pair.0 == current ).map( |pair| &pair.1 ) {
+				step.apply( snapshot )?;
+			}
+			snapshot.version += 1;
+		}
+		
+		Ok(())
+	}
+}
+
+// struct - Migrations
+//------------------------------------------------------------------------------
+// enum - SnapErr
+
+#[derive( Debug, Clone, PartialEq )]
+pub enum SnapErr {
+	NoSuchEntityId( usize ), // component refers to entity not present in the snapshot
+	UnknownComponent( String ), // component with specified name is not present in the ECS
+	UnsupportedFormat( u32 ), // snapshot was created by an incompatible version of the library
+	UnsupportedVersion( u32 ), // snapshot is newer than the registered migrations
+	DuplicateComponent( String ), // migration renames component to a name already present in the snapshot
+	Decode( String ), // binary snapshot is truncated or invalid
+	ChecksumMismatch, // binary snapshot was corrupted
+	DeltaMismatch, // delta was created from a different snapshot
+	Parse( usize, String ), // prefab text is invalid at specified line
+	UnknownPrefabComponent { prefab: String, component: String, expected: Vec< &'static str > }, // prefab contains component not present in the ECS
+	Value( String ), // value cannot be converted into, or recreated from `SnapValue`
+}
+
+#[allow( clippy::min_ident_chars )]
+impl std::fmt::Display for SnapErr {
+	#[inline]
+	fn fmt( &self, f: &mut std::fmt::Formatter<'_> ) -> std::fmt::Result {
+		match *self {
+			Self::NoSuchEntityId( id ) => format!( "snapshot refers to entity with id: {id}, which does not exist" ),
+			Self::UnknownComponent( ref name ) => format!( "snapshot contains unknown component: {name}" ),
+			Self::UnsupportedFormat( format ) => format!( "snapshot format: {format} is not supported, expected: {}", Snapshot::FORMAT ),
+			Self::UnsupportedVersion( version ) => format!( "snapshot version: {version} is newer than supported" ),
+			Self::DuplicateComponent( ref name ) => format!( "snapshot already contains component: {name}" ),
+			Self::Decode( ref msg ) => format!( "invalid binary snapshot: {msg}" ),
+			Self::ChecksumMismatch => "binary snapshot checksum mismatch".to_owned(),
+			Self::DeltaMismatch => "delta was created from a different snapshot".to_owned(),
+			Self::Parse( line, ref msg ) => format!( "invalid prefab at line {line}: {msg}" ),
+			Self::UnknownPrefabComponent { ref prefab, ref component, ref expected } => format!( "prefab: {prefab} contains unknown component: {component}, expected one of: {}", expected.join( ", " )),
+			Self::Value( ref msg ) => format!( "invalid snapshot value: {msg}" ),
+		}.fmt(f)
+	}
+}
+impl std::error::Error for SnapErr {}
+
+// enum - SnapErr
+//------------------------------------------------------------------------------
+// --Impl SnapComp without serde
+
+#[cfg( not( feature = "serde" ))]
+macro_rules! impl_snap_comp {
+	( $variant: ident, $conv: ty; $( $t: ty ),+ ) => {
+		$(
+			impl SnapComp for $t {
+				#[inline]
+				fn to_snap ( &self ) -> Result< SnapValue, SnapErr > {
+					Ok( SnapValue::$variant( <$conv>::from( *self )))
+				}
+				
+				#[inline]
+				fn from_snap ( value: SnapValue ) -> Result< Self, SnapErr > {
+					match value {
+						SnapValue::Int( val ) => Self::try_from( val ).map_err( |err| SnapErr::Value( err.to_string() )),
+						SnapValue::UInt( val ) => Self::try_from( val ).map_err( |err| SnapErr::Value( err.to_string() )),
+						other => Err( other.unexpected( stringify!( $t ))),
+					}
+				}
+			}
+		)+
+	};
+}
+
+#[cfg( not( feature = "serde" ))]
+impl_snap_comp!( Int, i64; i8, i16, i32, i64 );
+#[cfg( not( feature = "serde" ))]
+impl_snap_comp!( UInt, u64; u8, u16, u32, u64 );
+
+#[cfg( not( feature = "serde" ))]
+impl SnapComp for isize {
+	#[inline]
+	fn to_snap ( &self ) -> Result< SnapValue, SnapErr > {
+		i64::try_from( *self ).map( SnapValue::Int ).map_err( |err| SnapErr::Value( err.to_string() ))
+	}
+	
+	#[inline]
+	fn from_snap ( value: SnapValue ) -> Result< Self, SnapErr > {
+		i64::from_snap( value ).and_then( |val| Self::try_from( val ).map_err( |err| SnapErr::Value( err.to_string() )))
+	}
+}
+
+#[cfg( not( feature = "serde" ))]
+impl SnapComp for usize {
+	#[inline]
+	fn to_snap ( &self ) -> Result< SnapValue, SnapErr > {
+		u64::try_from( *self ).map( SnapValue::UInt ).map_err( |err| SnapErr::Value( err.to_string() ))
+	}
+	
+	#[inline]
+	fn from_snap ( value: SnapValue ) -> Result< Self, SnapErr > {
+		u64::from_snap( value ).and_then( |val| Self::try_from( val ).map_err( |err| SnapErr::Value( err.to_string() )))
+	}
+}
+
+#[cfg( not( feature = "serde" ))]
+impl SnapComp for f64 {
+	#[inline]
+	fn to_snap ( &self ) -> Result< SnapValue, SnapErr > {
+		Ok( SnapValue::Float( *self ))
+	}
+	
+	#[inline]
+	#[allow( clippy::cast_precision_loss )]
+	fn from_snap ( value: SnapValue ) -> Result< Self, SnapErr > {
+		match value {
+			SnapValue::Float( val ) => Ok( val ),
+			SnapValue::Int( val ) => Ok( val as Self ),
+			SnapValue::UInt( val ) => Ok( val as Self ),
+			other => Err( other.unexpected( "f64" )),
+		}
+	}
+}
+
+#[cfg( not( feature = "serde" ))]
+impl SnapComp for f32 {
+	#[inline]
+	fn to_snap ( &self ) -> Result< SnapValue, SnapErr > {
+		Ok( SnapValue::Float( f64::from( *self )))
+	}
+	
+	#[inline]
+	#[allow( clippy::cast_possible_truncation )]
+	fn from_snap ( value: SnapValue ) -> Result< Self, SnapErr > {
+		f64::from_snap( value ).map( |val| val as Self )
+	}
+}
+
+#[cfg( not( feature = "serde" ))]
+impl SnapComp for bool {
+	#[inline]
+	fn to_snap ( &self ) -> Result< SnapValue, SnapErr > {
+		Ok( SnapValue::Bool( *self ))
+	}
+	
+	#[inline]
+	fn from_snap ( value: SnapValue ) -> Result< Self, SnapErr > {
+		match value {
+			SnapValue::Bool( val ) => Ok( val ),
+			other => Err( other.unexpected( "bool" )),
+		}
+	}
+}
+
+#[cfg( not( feature = "serde" ))]
+impl SnapComp for char {
+	#[inline]
+	fn to_snap ( &self ) -> Result< SnapValue, SnapErr > {
+		Ok( SnapValue::Char( *self ))
+	}
+	
+	#[inline]
+	fn from_snap ( value: SnapValue ) -> Result< Self, SnapErr > {
+		match value {
+			SnapValue::Char( val ) => Ok( val ),
+			other => Err( other.unexpected( "char" )),
+		}
+	}
+}
+
+#[cfg( not( feature = "serde" ))]
+impl SnapComp for String {
+	#[inline]
+	fn to_snap ( &self ) -> Result< SnapValue, SnapErr > {
+		Ok( SnapValue::Str( self.clone() ))
+	}
+	
+	#[inline]
+	fn from_snap ( value: SnapValue ) -> Result< Self, SnapErr > {
+		match value {
+			SnapValue::Str( val ) => Ok( val ),
+			other => Err( other.unexpected( "string" )),
+		}
+	}
+}
+
+#[cfg( not( feature = "serde" ))]
+impl SnapComp for () {
+	#[inline]
+	fn to_snap ( &self ) -> Result< SnapValue, SnapErr > {
+		Ok( SnapValue::Unit )
+	}
+	
+	#[inline]
+	fn from_snap ( value: SnapValue ) -> Result< Self, SnapErr > {
+		match value {
+			SnapValue::Unit => Ok(()),
+			other => Err( other.unexpected( "unit" )),
+		}
+	}
+}
+
+#[cfg( not( feature = "serde" ))]
+impl< T: SnapComp > SnapComp for Option< T > {
+	#[inline]
+	fn to_snap ( &self ) -> Result< SnapValue, SnapErr > {
+		match *self {
+			Some( ref val ) => Ok( SnapValue::Some( Box::new( val.to_snap()? ))),
+			None => Ok( SnapValue::None ),
+		}
+	}
+	
+	#[inline]
+	fn from_snap ( value: SnapValue ) -> Result< Self, SnapErr > {
+		match value {
+			SnapValue::None => Ok( None ),
+			SnapValue::Some( val ) => T::from_snap( *val ).map( Some ),
+			other => Err( other.unexpected( "option" )),
+		}
+	}
+}
+
+#[cfg( not( feature = "serde" ))]
+impl< T: SnapComp > SnapComp for Vec< T > {
+	#[inline]
+	fn to_snap ( &self ) -> Result< SnapValue, SnapErr > {
+		self.iter().map( SnapComp::to_snap ).collect::< Result< _, _ >>().map( SnapValue::Seq )
+	}
+	
+	#[inline]
+	fn from_snap ( value: SnapValue ) -> Result< Self, SnapErr > {
+		match value {
+			SnapValue::Seq( vec ) => vec.into_iter().map( T::from_snap ).collect(),
+			other => Err( other.unexpected( "sequence" )),
+		}
+	}
+}
+
+#[cfg( not( feature = "serde" ))]
+macro_rules! impl_snap_comp_tuple {
+	( $len: literal; $( $name: ident ),+ ) => {
+		impl< $( $name: SnapComp ),+ > SnapComp for ( $( $name, )+ ) {
+			#[inline]
+			#[allow( non_snake_case )]
+			fn to_snap ( &self ) -> Result< SnapValue, SnapErr > {
+				let ( $( ref $name, )+ ) = *self;
+				Ok( SnapValue::Seq( vec![ $( $name.to_snap()? ),+ ] ))
+			}
+			
+			#[inline]
+			fn from_snap ( value: SnapValue ) -> Result< Self, SnapErr > {
+				match value {
+					SnapValue::Seq( vec ) if vec.len() == $len => {
+						let mut iter = vec.into_iter();
+						Ok(( $( $name::from_snap( iter.next().ok_or( SnapErr::Value( "tuple too short".to_owned() ))? )?, )+ ))
+					}
+					other => Err( other.unexpected( concat!( "tuple of ", $len ))),
+				}
+			}
+		}
+	};
+}
+
+#[cfg( not( feature = "serde" ))]
+impl_snap_comp_tuple!( 1; T0 );
+#[cfg( not( feature = "serde" ))]
+impl_snap_comp_tuple!( 2; T0, T1 );
+#[cfg( not( feature = "serde" ))]
+impl_snap_comp_tuple!( 3; T0, T1, T2 );
+#[cfg( not( feature = "serde" ))]
+impl_snap_comp_tuple!( 4; T0, T1, T2, T3 );
+
+// --Impl SnapComp without serde
+//------------------------------------------------------------------------------
+// --Tests
+
+#[cfg( test )]
+#[allow( dead_code )]// not every generated method is used by tests
+mod tests {
+	use crate::*;
+	use minecs_macro::minecs;
+	
+	minecs!(
+		#[snapshot]
+		ecs TestEcs< TestArray, TestEntity > {
+			types [ f64 ]
+			name: String,
+		}
+	);
+	
+	#[cfg( feature = "serde" )]
+	minecs!(
+		#[snapshot]
+		ecs LinkedEcs< LinkedArray, LinkedEntity > {
+			#[map_entities] target: EntityId< LinkedEntity >,
+		}
+	);
+	
+	#[test]
+	fn only_live_entities_are_saved() {
+		let mut ecs = TestEcs::new();
+		let [ first, second, third ] = [ ecs.new_entity(), ecs.new_entity(), ecs.new_entity() ];
+		_ = ecs.insert_name( first, "first".to_owned() );
+		_ = ecs.insert_name( third, "third".to_owned() );
+		_ = ecs.insert( second, 1.5_f64 );
+		assert_eq!( ecs.set_parent( third, first ), Ok( None ));
+		assert_eq!( ecs.despawn( second ), Ok(()));
+		
+		let snapshot = ecs.snapshot().unwrap();
+		assert_eq!( snapshot.entities, 2 );
+		assert!( snapshot.section( "f64" ).unwrap().entries.is_empty() );
+		
+		// the third entity takes the id of the despawned one
+		let loaded = TestEcs::from_snapshot( snapshot ).unwrap();
+		assert_eq!( loaded.name( first ).map( String::as_str ), Some( "first" ));
+		assert_eq!( loaded.name( second ).map( String::as_str ), Some( "third" ));
+		assert_eq!( loaded.parent( second ), Some( first ));
+		assert!( loaded.entity( third ).is_none() );
+	}
+	
+	#[cfg( feature = "serde" )]
+	#[test]
+	fn stored_ids_are_compacted() {
+		let mut ecs = LinkedEcs::new();
+		let [ first, second, third ] = [ ecs.new_entity(), ecs.new_entity(), ecs.new_entity() ];
+		_ = ecs.insert_target( first, third );
+		_ = ecs.insert_target( third, first );
+		assert_eq!( ecs.despawn( second ), Ok(()));
+		
+		let loaded = LinkedEcs::from_snapshot( ecs.snapshot().unwrap() ).unwrap();
+		assert_eq!( loaded.target( first ), Some( &second ));
+		assert_eq!( loaded.target( second ), Some( &first ));
+	}
+	
+	#[test]
+	fn round_trip_after_removals_is_equal() {
+		let mut ecs = TestEcs::new();
+		let ids = [ 0, 1, 2, 3_u8 ].map( |idx| {
+			let id = ecs.new_entity();
+			_ = ecs.insert( id, f64::from( idx ));
+			_ = ecs.insert_name( id, format!( "unit {idx}" ));
+			id
+		});
+		let [ first, second, third, _ ] = ids;
+		assert_eq!( EcsFn::remove::< f64 >( &mut ecs, first ), Ok(()));
+		assert_eq!( EcsFn::remove::< f64 >( &mut ecs, third ), Ok(()));
+		assert_eq!( ecs.take::< f64 >( second ), Ok( 1.0 ));
+		
+		// removed components leave retained slots behind, which the loaded ECS does not have
+		let loaded = TestEcs::from_snapshot( ecs.snapshot().unwrap() ).unwrap();
+		assert_eq!( loaded, ecs );
+		
+		let mut other = loaded.clone();
+		_ = other.insert( first, 0.0_f64 );
+		assert_ne!( other, ecs );
+	}
+	
+	#[test]
+	fn entities_without_data_round_trip() {
+		let mut ecs = TestEcs::new();
+		let first = ecs.new_entity();
+		_ = ecs.insert( first, 1.5_f64 );
+		_ = ecs.new_entity();
+		_ = ecs.new_entity();
+		
+		let snapshot = ecs.snapshot().unwrap();
+		assert_eq!( snapshot.entities, 3 );
+		assert_eq!( TestEcs::from_snapshot( snapshot ), Ok( ecs ));
+		
+		let empty = TestEcs::new();
+		assert_eq!( TestEcs::from_snapshot( empty.snapshot().unwrap() ), Ok( empty ));
+	}
+}
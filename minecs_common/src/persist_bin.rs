@@ -0,0 +1,1038 @@
+use std::collections::HashMap;
+
+use crate::{ SnapErr, SnapSection, SnapValue, Snapshot };
+
+//------------------------------------------------------------------------------
+// --Traits
+
+/// Hand-written binary encoding, used by `Snapshot::to_bytes` and `Snapshot::from_bytes`.
+/// 
+/// All numbers are little-endian, `usize` is stored as `u64`, and collections are prefixed with their length.
+/// 
+/// Components may implement it to store themselves more compactly than via `SnapComp`;
+/// `BinCodec::to_snap_bytes` and `BinCodec::from_snap_bytes` can then be used to implement `SnapComp`.
+pub trait BinCodec: Sized {
+	/// Appends encoded `self` to `out`.
+	fn encode ( &self, out: &mut Vec< u8 > );
+	
+	/// Decodes value from the front of `reader`.
+	/// 
+	/// # Errors
+	/// 
+	/// SnapErr::Decode - when the input is truncated or invalid
+	fn decode ( reader: &mut BinReader<'_> ) -> Result< Self, SnapErr >;
+	
+	/// Encodes `self` into `SnapValue::Bytes`.
+	#[inline]
+	fn to_snap_bytes ( &self ) -> SnapValue {
+		let mut out = Vec::new();
+		self.encode( &mut out );
+		SnapValue::Bytes( out )
+	}
+	
+	/// Decodes value out of `SnapValue::Bytes`.
+	/// 
+	/// # Errors
+	/// 
+	/// SnapErr::Value - when the value is not `SnapValue::Bytes`
+	/// SnapErr::Decode - when the bytes are truncated, invalid or not fully consumed
+	#[inline]
+	fn from_snap_bytes ( value: SnapValue ) -> Result< Self, SnapErr > {
+		let SnapValue::Bytes( bytes ) = value else {
+			return Err( value.unexpected( "bytes" ))
+		};
+		let mut reader = BinReader::new( &bytes );
+		let out = Self::decode( &mut reader )?;
+		reader.finish()?;
+		Ok( out )
+	}
+}
+
+// --Traits
+//------------------------------------------------------------------------------
+// struct - BinReader
+
+/// Cursor over encoded bytes, tracking nesting of values so that corrupted input cannot overflow the stack.
+#[derive( Debug, Clone )]
+pub struct BinReader<'a> {
+	bytes: &'a [u8],
+	depth: usize,
+}
+
+impl<'a> BinReader<'a> {
+	/// Maximum nesting of values, fe. `SnapValue::Seq` inside another, decoded by a single reader.
+	pub const MAX_DEPTH: usize = 64;
+	
+	#[inline]
+	pub const fn new ( bytes: &'a [u8] ) -> Self {
+		Self {
+			bytes,
+			depth: 0,
+		}
+	}
+	
+	/// Enters a nested value, each call must be paired with `BinReader::leave`.
+	/// 
+	/// # Errors
+	/// 
+	/// SnapErr::Decode - when values are nested deeper than `BinReader::MAX_DEPTH`
+	#[inline]
+	pub fn enter ( &mut self ) -> Result< (), SnapErr > {
+		if self.depth >= Self::MAX_DEPTH {
+			return Err( SnapErr::Decode( format!( "values nested deeper than {}", Self::MAX_DEPTH )))
+		}
+		self.depth += 1;
+		Ok(())
+	}
+	
+	/// Leaves a nested value entered by `BinReader::enter`.
+	#[inline]
+	pub fn leave ( &mut self ) {
+		self.depth = self.depth.saturating_sub( 1 );
+	}
+	
+	/// Returns the amount of bytes not read yet.
+	#[inline]
+	pub const fn remaining ( &self ) -> usize {
+		self.bytes.len()
+	}
+	
+	/// Takes next `len` bytes.
+	/// 
+	/// # Errors
+	/// 
+	/// SnapErr::Decode - when there are less than `len` bytes left
+	#[inline]
+	pub fn take ( &mut self, len: usize ) -> Result< &'a [u8], SnapErr > {
+		if len > self.bytes.len() {
+			return Err( SnapErr::Decode( format!( "unexpected end of input, expected {len} more bytes, found {}", self.bytes.len() )))
+		}
+		let ( head, tail ) = self.bytes.split_at( len );
+		self.bytes = tail;
+		Ok( head )
+	}
+	
+	/// Takes next `N` bytes as an array.
+	/// 
+	/// # Errors
+	/// 
+	/// SnapErr::Decode - when there are less than `N` bytes left
+	#[inline]
+	pub fn take_array< const N: usize > ( &mut self ) -> Result< [u8; N], SnapErr > {
+		let mut out = [0; N];
+		out.copy_from_slice( self.take( N )? );
+		Ok( out )
+	}
+	
+	/// Reads a length prefix, checking it against remaining input so that corrupted lengths do not cause huge allocations.
+	/// 
+	/// # Errors
+	/// 
+	/// SnapErr::Decode - when the length exceeds remaining input
+	#[inline]
+	pub fn len_prefix ( &mut self ) -> Result< usize, SnapErr > {
+		let len = usize::decode( self )?;
+		if len > self.bytes.len() {
+			return Err( SnapErr::Decode( format!( "length: {len} exceeds remaining input: {}", self.bytes.len() )))
+		}
+		Ok( len )
+	}
+	
+	/// Reads an unsigned LEB128 varint, 7 bits per byte, lowest first.
+	/// 
+	/// # Errors
+	/// 
+	/// SnapErr::Decode - when the input is truncated, or the value exceeds `u64`
+	#[inline]
+	pub fn varint ( &mut self ) -> Result< u64, SnapErr > {
+		let mut out = 0_u64;
+		for shift in ( 0 .. u64::BITS ).step_by( 7 ) {
+			let [ byte ] = self.take_array::< 1 >()?;
+			let bits = u64::from( byte & 0x7f );
+			if bits.leading_zeros() < shift {
+				return Err( SnapErr::Decode( "varint exceeds 64 bits".to_owned() ))
+			}
+			out |= bits << shift;
+			if byte & 0x80 == 0 {
+				return Ok( out )
+			}
+		}
+		Err( SnapErr::Decode( "varint exceeds 64 bits".to_owned() ))
+	}
+	
+	/// Reads a varint count of items, checking it against remaining input so that corrupted counts do not cause huge allocations.
+	/// 
+	/// # Errors
+	/// 
+	/// SnapErr::Decode - when the count exceeds remaining input
+	#[inline]
+	pub fn count ( &mut self ) -> Result< usize, SnapErr > {
+		let count = usize::try_from( self.varint()? ).map_err( |err| SnapErr::Decode( err.to_string() ))?;
+		if count > self.bytes.len() {
+			return Err( SnapErr::Decode( format!( "count: {count} exceeds remaining input: {}", self.bytes.len() )))
+		}
+		Ok( count )
+	}
+	
+	/// Checks whether all of the input was read.
+	/// 
+	/// # Errors
+	/// 
+	/// SnapErr::Decode - when there are bytes left
+	#[inline]
+	pub fn finish ( &self ) -> Result< (), SnapErr > {
+		if self.bytes.is_empty() {
+			Ok(())
+		} else {
+			Err( SnapErr::Decode( format!( "{} unexpected trailing bytes", self.bytes.len() )))
+		}
+	}
+}
+
+// struct - BinReader
+//------------------------------------------------------------------------------
+// --Impl Snapshot
+
+impl Snapshot {
+	/// Magic bytes at the start of every encoded snapshot.
+	pub const MAGIC: [u8; 4] = *b"MECS";
+	
+	/// Version of the binary layout written by `Snapshot::to_bytes`.
+	pub const BIN_FORMAT: u32 = 2;
+	
+	/// Encodes the snapshot into a compact binary form.
+	/// 
+	/// Layout: `MAGIC`, `BIN_FORMAT: u32`, then `format`, `version` and `entities` as varints, a table of struct field,
+	/// enum variant and section names, and each section as its name, byte length, the shape shared by its values,
+	/// and entries of delta-encoded entity id and value, followed by a `u32` checksum ( FNV-1a ) of all preceding bytes.
+	/// 
+	/// Values are written without tags or names, those are stored once per section in the shape.
+	/// 
+	/// # Errors
+	/// 
+	/// SnapErr::Value - when values are nested deeper than `BinReader::MAX_DEPTH`, so that `Snapshot::from_bytes` would reject them
+	#[inline]
+	pub fn to_bytes ( &self ) -> Result< Vec< u8 >, SnapErr > {
+		let mut writer = SnapWriter::default();
+		let mut sections = Vec::new();
+		write_varint( &mut sections, self.components.len() as u64 );
+		
+		let mut section_bytes = Vec::new();
+		for section in &self.components {
+			section_bytes.clear();
+			let shape = section.entries.iter().fold( Shape::Never, |shape, entry| shape.unify( Shape::of( &entry.1 )));
+			writer.shape( &mut section_bytes, &shape )?;
+			write_varint( &mut section_bytes, section.entries.len() as u64 );
+			
+			let mut prev = 0_usize;
+			for &( idx, ref value ) in &section.entries {
+				write_varint( &mut section_bytes, zigzag( idx.wrapping_sub( prev ) as u64 ));
+				prev = idx;
+				writer.value( &mut section_bytes, &shape, value )?;
+			}
+			
+			let name = writer.name( &section.name );
+			write_varint( &mut sections, name );
+			write_varint( &mut sections, section_bytes.len() as u64 );
+			sections.extend_from_slice( &section_bytes );
+		}
+		
+		let mut out = Vec::new();
+		out.extend_from_slice( &Self::MAGIC );
+		Self::BIN_FORMAT.encode( &mut out );
+		write_varint( &mut out, u64::from( self.format ));
+		write_varint( &mut out, u64::from( self.version ));
+		write_varint( &mut out, self.entities as u64 );
+		write_varint( &mut out, writer.names.len() as u64 );
+		for name in &writer.names {
+			write_varint( &mut out, name.len() as u64 );
+			out.extend_from_slice( name.as_bytes() );
+		}
+		out.extend_from_slice( &sections );
+		
+		checksum( &out ).encode( &mut out );
+		Ok( out )
+	}
+	
+	/// Decodes a snapshot created by `Snapshot::to_bytes`.
+	/// 
+	/// # Errors
+	/// 
+	/// SnapErr::Decode - when the input is truncated, invalid, nested too deep or does not start with `Snapshot::MAGIC`
+	/// SnapErr::ChecksumMismatch - when the input was corrupted
+	/// SnapErr::UnsupportedFormat - when the snapshot was created by an incompatible version of this library
+	#[inline]
+	pub fn from_bytes ( bytes: &[u8] ) -> Result< Self, SnapErr > {
+		let Some( body_len ) = bytes.len().checked_sub( 4 ) else {
+			return Err( SnapErr::Decode( "input too short".to_owned() ))
+		};
+		let ( body, tail ) = bytes.split_at( body_len );
+		let mut tail_reader = BinReader::new( tail );
+		if u32::decode( &mut tail_reader )? != checksum( body ) {
+			return Err( SnapErr::ChecksumMismatch )
+		}
+		
+		let mut reader = BinReader::new( body );
+		if reader.take_array::< 4 >()? != Self::MAGIC {
+			return Err( SnapErr::Decode( "not a snapshot, invalid magic bytes".to_owned() ))
+		}
+		let bin_format = u32::decode( &mut reader )?;
+		if bin_format != Self::BIN_FORMAT {
+			return Err( SnapErr::Decode( format!( "binary layout: {bin_format} is not supported, expected: {}", Self::BIN_FORMAT )))
+		}
+		
+		let format = read_u32( &mut reader )?;
+		if format != Self::FORMAT {
+			return Err( SnapErr::UnsupportedFormat( format ))
+		}
+		let version = read_u32( &mut reader )?;
+		let entities = usize::try_from( reader.varint()? ).map_err( |err| SnapErr::Decode( err.to_string() ))?;
+		let mut snapshot = Self::new( entities );
+		snapshot.version = version;
+		
+		let mut names = Vec::new();
+		for _ in 0 .. reader.count()? {
+			let len = reader.count()?;
+			let name = std::str::from_utf8( reader.take( len )? ).map_err( |err| SnapErr::Decode( err.to_string() ))?;
+			names.push( name.to_owned() );
+		}
+		let snap_reader = SnapReader { names };
+		
+		for _ in 0 .. reader.count()? {
+			let name = snap_reader.name( &mut reader )?;
+			let len = reader.count()?;
+			let mut section_reader = BinReader::new( reader.take( len )? );
+			let shape = snap_reader.shape( &mut section_reader )?;
+			
+			let mut section = SnapSection { name, entries: Vec::new() };
+			let mut prev = 0_usize;
+			for _ in 0 .. section_reader.count()? {
+				let idx = prev.wrapping_add( usize::try_from( unzigzag( section_reader.varint()? )).unwrap_or( usize::MAX ));
+				prev = idx;
+				section.push( idx, snap_reader.value( &mut section_reader, &shape )? );
+			}
+			section_reader.finish()?;
+			snapshot.components.push( section );
+		}
+		
+		reader.finish()?;
+		Ok( snapshot )
+	}
+}
+
+/// FNV-1a hash of `bytes`.
+fn checksum ( bytes: &[u8] ) -> u32 {
+	bytes.iter().fold( 0x811c_9dc5, |hash, &byte| ( hash ^ u32::from( byte )).wrapping_mul( 0x0100_0193 ))
+}
+
+/// Appends `val` as an unsigned LEB128 varint.
+fn write_varint ( out: &mut Vec< u8 >, mut val: u64 ) {
+	while val >= 0x80 {
+		out.push( val.to_le_bytes()[0] | 0x80 );
+		val >>= 7;
+	}
+	out.push( val.to_le_bytes()[0] );
+}
+
+/// Maps a two's complement number to an unsigned one, small negative numbers becoming small as well.
+const fn zigzag ( val: u64 ) -> u64 {
+	( val << 1 ) ^ 0_u64.wrapping_sub( val >> 63 )
+}
+
+/// Reverses `zigzag`.
+const fn unzigzag ( val: u64 ) -> u64 {
+	( val >> 1 ) ^ 0_u64.wrapping_sub( val & 1 )
+}
+
+fn read_u32 ( reader: &mut BinReader<'_> ) -> Result< u32, SnapErr > {
+	u32::try_from( reader.varint()? ).map_err( |err| SnapErr::Decode( err.to_string() ))
+}
+
+static ANY: Shape = Shape::Any;
+
+/// Shape shared by values of a section, so that their tags and names are stored only once.
+#[derive( Debug, Clone, PartialEq )]
+enum Shape {
+	/// No value seen yet, fe. elements of empty sequences.
+	Never,
+	Unit,
+	Bool,
+	Int,
+	UInt,
+	Float,
+	Char,
+	Str,
+	Bytes,
+	Option( Box< Shape > ),
+	Seq( Box< Shape > ),
+	Map( Box< Shape >, Box< Shape > ),
+	Struct( Vec< ( String, Shape ) > ),
+	Variant( Box< Shape > ),
+	/// Values of different shapes, each stored along with its own shape.
+	Any,
+}
+
+impl Shape {
+	/// Returns the shape of a single value.
+	fn of ( value: &SnapValue ) -> Self {
+		match *value {
+			SnapValue::Unit => Self::Unit,
+			SnapValue::Bool( _ ) => Self::Bool,
+			SnapValue::Int( _ ) => Self::Int,
+			SnapValue::UInt( _ ) => Self::UInt,
+			SnapValue::Float( _ ) => Self::Float,
+			SnapValue::Char( _ ) => Self::Char,
+			SnapValue::Str( _ ) => Self::Str,
+			SnapValue::Bytes( _ ) => Self::Bytes,
+			SnapValue::None => Self::Option( Box::new( Self::Never )),
+			SnapValue::Some( ref val ) => Self::Option( Box::new( Self::of( val ))),
+			SnapValue::Seq( ref list ) => Self::Seq( Box::new( list.iter().fold( Self::Never, |shape, val| shape.unify( Self::of( val ))))),
+			SnapValue::Map( ref pairs ) => {
+				let ( keys, vals ) = pairs.iter().fold(( Self::Never, Self::Never ), |( keys, vals ), pair| {
+					( keys.unify( Self::of( &pair.0 )), vals.unify( Self::of( &pair.1 )))
+				});
+				Self::Map( Box::new( keys ), Box::new( vals ))
+			}
+			SnapValue::Struct( ref fields ) => Self::Struct( fields.iter().map( |field| ( field.0.clone(), Self::of( &field.1 ))).collect() ),
+			SnapValue::Variant( _, ref val ) => Self::Variant( Box::new( Self::of( val ))),
+		}
+	}
+	
+	/// Returns a shape fitting values of both shapes, `Shape::Any` when they differ.
+	fn unify ( self, other: Self ) -> Self {
+		match ( self, other ) {
+			( Self::Never, shape ) | ( shape, Self::Never ) => shape,
+			( Self::Option( first ), Self::Option( second )) => Self::Option( Box::new( first.unify( *second ))),
+			( Self::Seq( first ), Self::Seq( second )) => Self::Seq( Box::new( first.unify( *second ))),
+			( Self::Map( keys, vals ), Self::Map( other_keys, other_vals )) => Self::Map( Box::new( keys.unify( *other_keys )), Box::new( vals.unify( *other_vals ))),
+			( Self::Struct( fields ), Self::Struct( other_fields )) if fields.len() == other_fields.len() && fields.iter().zip( &other_fields ).all( |pair| pair.0.0 == pair.1.0 ) => {
+				Self::Struct( fields.into_iter().zip( other_fields ).map( |( field, other_field )| ( field.0, field.1.unify( other_field.1 ))).collect() )
+			}
+			( Self::Variant( first ), Self::Variant( second )) => Self::Variant( Box::new( first.unify( *second ))),
+			( first, second ) if first == second => first,
+			_ => Self::Any,
+		}
+	}
+	
+	/// Returns the shape of values nested in `Shape::Option`, `Shape::Seq` or `Shape::Variant`.
+	fn inner ( &self ) -> &Self {
+		match *self {
+			Self::Option( ref inner ) | Self::Seq( ref inner ) | Self::Variant( ref inner ) => inner,
+			_ => &ANY,
+		}
+	}
+	
+	/// Returns the shapes of keys and values of `Shape::Map`.
+	fn pair ( &self ) -> ( &Self, &Self ) {
+		match *self {
+			Self::Map( ref keys, ref vals ) => ( keys, vals ),
+			_ => ( &ANY, &ANY ),
+		}
+	}
+	
+	/// Returns the names and shapes of fields of `Shape::Struct`.
+	fn fields ( &self ) -> &[( String, Self )] {
+		match *self {
+			Self::Struct( ref fields ) => fields,
+			_ => &[],
+		}
+	}
+	
+	const fn tag ( &self ) -> u8 {
+		match *self {
+			Self::Never => 0,
+			Self::Unit => 1,
+			Self::Bool => 2,
+			Self::Int => 3,
+			Self::UInt => 4,
+			Self::Float => 5,
+			Self::Char => 6,
+			Self::Str => 7,
+			Self::Bytes => 8,
+			Self::Option( _ ) => 9,
+			Self::Seq( _ ) => 10,
+			Self::Map( _, _ ) => 11,
+			Self::Struct( _ ) => 12,
+			Self::Variant( _ ) => 13,
+			Self::Any => 14,
+		}
+	}
+}
+
+/// Encoder of shapes and values, interning struct field, enum variant and section names.
+/// 
+/// Tracks nesting the same way `BinReader` does when decoding, so that every encoded snapshot can be decoded.
+#[derive( Debug, Default )]
+struct SnapWriter {
+	names: Vec< String >,
+	index: HashMap< String, u64 >,
+	depth: usize,
+}
+
+impl SnapWriter {
+	/// Enters a nested shape or value, mirroring `BinReader::enter`.
+	fn enter ( &mut self ) -> Result< (), SnapErr > {
+		if self.depth >= BinReader::MAX_DEPTH {
+			return Err( SnapErr::Value( format!( "values nested deeper than {}", BinReader::MAX_DEPTH )))
+		}
+		self.depth += 1;
+		Ok(())
+	}
+	
+	fn leave ( &mut self ) {
+		self.depth = self.depth.saturating_sub( 1 );
+	}
+	
+	/// Returns the index of the name in the table, adding it if not present.
+	fn name ( &mut self, name: &str ) -> u64 {
+		if let Some( &idx ) = self.index.get( name ) {
+			return idx
+		}
+		let idx = self.names.len() as u64;
+		self.names.push( name.to_owned() );
+		_ = self.index.insert( name.to_owned(), idx );
+		idx
+	}
+	
+	fn shape ( &mut self, out: &mut Vec< u8 >, shape: &Shape ) -> Result< (), SnapErr > {
+		self.enter()?;
+		out.push( shape.tag() );
+		match *shape {
+			Shape::Option( ref inner ) | Shape::Seq( ref inner ) | Shape::Variant( ref inner ) => self.shape( out, inner )?,
+			Shape::Map( ref keys, ref vals ) => {
+				self.shape( out, keys )?;
+				self.shape( out, vals )?;
+			}
+			Shape::Struct( ref fields ) => {
+				write_varint( out, fields.len() as u64 );
+				for field in fields {
+					let name = self.name( &field.0 );
+					write_varint( out, name );
+					self.shape( out, &field.1 )?;
+				}
+			}
+			Shape::Never | Shape::Unit | Shape::Bool | Shape::Int | Shape::UInt | Shape::Float | Shape::Char | Shape::Str | Shape::Bytes | Shape::Any => {}
+		}
+		self.leave();
+		Ok(())
+	}
+	
+	/// Appends `value` without its tag and names, which are stored in `shape` inferred from it.
+	fn value ( &mut self, out: &mut Vec< u8 >, shape: &Shape, value: &SnapValue ) -> Result< (), SnapErr > {
+		self.enter()?;
+		if *shape == Shape::Any {
+			let own = Shape::of( value );
+			self.shape( out, &own )?;
+			self.value( out, &own, value )?;
+			self.leave();
+			return Ok(())
+		}
+		
+		// the shape was inferred from the value, so it is only needed for nested values
+		match *value {
+			SnapValue::Unit => {}
+			SnapValue::None => out.push( 0 ),
+			SnapValue::Bool( val ) => out.push( u8::from( val )),
+			SnapValue::Int( val ) => write_varint( out, zigzag( u64::from_le_bytes( val.to_le_bytes() ))),
+			SnapValue::UInt( val ) => write_varint( out, val ),
+			SnapValue::Float( val ) => val.encode( out ),
+			SnapValue::Char( val ) => write_varint( out, u64::from( u32::from( val ))),
+			SnapValue::Str( ref val ) => {
+				write_varint( out, val.len() as u64 );
+				out.extend_from_slice( val.as_bytes() );
+			}
+			SnapValue::Bytes( ref val ) => {
+				write_varint( out, val.len() as u64 );
+				out.extend_from_slice( val );
+			}
+			SnapValue::Some( ref val ) => {
+				out.push( 1 );
+				self.value( out, shape.inner(), val )?;
+			}
+			SnapValue::Seq( ref list ) => {
+				write_varint( out, list.len() as u64 );
+				for val in list {
+					self.value( out, shape.inner(), val )?;
+				}
+			}
+			SnapValue::Map( ref pairs ) => {
+				let ( keys, vals ) = shape.pair();
+				write_varint( out, pairs.len() as u64 );
+				for pair in pairs {
+					self.value( out, keys, &pair.0 )?;
+					self.value( out, vals, &pair.1 )?;
+				}
+			}
+			SnapValue::Struct( ref vals ) => {
+				for ( field, val ) in shape.fields().iter().zip( vals ) {
+					self.value( out, &field.1, &val.1 )?;
+				}
+			}
+			SnapValue::Variant( ref name, ref val ) => {
+				let idx = self.name( name );
+				write_varint( out, idx );
+				self.value( out, shape.inner(), val )?;
+			}
+		}
+		self.leave();
+		Ok(())
+	}
+}
+
+/// Decoder of shapes and values, resolving names through the table stored before sections.
+#[derive( Debug )]
+struct SnapReader {
+	names: Vec< String >,
+}
+
+impl SnapReader {
+	fn name ( &self, reader: &mut BinReader<'_> ) -> Result< String, SnapErr > {
+		let idx = reader.varint()?;
+		usize::try_from( idx ).ok()
+			.and_then( |pos| self.names.get( pos ))
+			.cloned()
+			.ok_or_else( || SnapErr::Decode( format!( "name index: {idx} exceeds the table of {} names", self.names.len() )))
+	}
+	
+	fn shape ( &self, reader: &mut BinReader<'_> ) -> Result< Shape, SnapErr > {
+		reader.enter()?;
+		let [ tag ] = reader.take_array::< 1 >()?;
+		let shape = match tag {
+			0 => Shape::Never,
+			1 => Shape::Unit,
+			2 => Shape::Bool,
+			3 => Shape::Int,
+			4 => Shape::UInt,
+			5 => Shape::Float,
+			6 => Shape::Char,
+			7 => Shape::Str,
+			8 => Shape::Bytes,
+			9 => Shape::Option( Box::new( self.shape( reader )? )),
+			10 => Shape::Seq( Box::new( self.shape( reader )? )),
+			11 => Shape::Map( Box::new( self.shape( reader )? ), Box::new( self.shape( reader )? )),
+			12 => {
+				let mut fields = Vec::new();
+				for _ in 0 .. reader.count()? {
+					fields.push(( self.name( reader )?, self.shape( reader )? ));
+				}
+				Shape::Struct( fields )
+			}
+			13 => Shape::Variant( Box::new( self.shape( reader )? )),
+			14 => Shape::Any,
+			other => return Err( SnapErr::Decode( format!( "invalid shape tag: {other}" ))),
+		};
+		reader.leave();
+		Ok( shape )
+	}
+	
+	fn value ( &self, reader: &mut BinReader<'_>, shape: &Shape ) -> Result< SnapValue, SnapErr > {
+		reader.enter()?;
+		let value = match *shape {
+			Shape::Never => return Err( SnapErr::Decode( "value of a shape without values".to_owned() )),
+			Shape::Unit => SnapValue::Unit,
+			Shape::Bool => SnapValue::Bool( bool::decode( reader )? ),
+			Shape::Int => SnapValue::Int( i64::from_le_bytes( unzigzag( reader.varint()? ).to_le_bytes() )),
+			Shape::UInt => SnapValue::UInt( reader.varint()? ),
+			Shape::Float => SnapValue::Float( f64::decode( reader )? ),
+			Shape::Char => {
+				let val = read_u32( reader )?;
+				SnapValue::Char( char::from_u32( val ).ok_or_else( || SnapErr::Decode( format!( "invalid char: {val}" )))? )
+			}
+			Shape::Str => {
+				let len = reader.count()?;
+				let text = std::str::from_utf8( reader.take( len )? ).map_err( |err| SnapErr::Decode( err.to_string() ))?;
+				SnapValue::Str( text.to_owned() )
+			}
+			Shape::Bytes => {
+				let len = reader.count()?;
+				SnapValue::Bytes( reader.take( len )?.to_vec() )
+			}
+			Shape::Option( ref inner ) => {
+				if bool::decode( reader )? {
+					SnapValue::Some( Box::new( self.value( reader, inner )? ))
+				} else {
+					SnapValue::None
+				}
+			}
+			Shape::Seq( ref inner ) => {
+				let len = reader.count()?;
+				let mut list = Vec::with_capacity( len );
+				for _ in 0 .. len {
+					list.push( self.value( reader, inner )? );
+				}
+				SnapValue::Seq( list )
+			}
+			Shape::Map( ref keys, ref vals ) => {
+				let len = reader.count()?;
+				let mut pairs = Vec::with_capacity( len );
+				for _ in 0 .. len {
+					pairs.push(( self.value( reader, keys )?, self.value( reader, vals )? ));
+				}
+				SnapValue::Map( pairs )
+			}
+			Shape::Struct( ref fields ) => {
+				let mut vals = Vec::with_capacity( fields.len() );
+				for field in fields {
+					vals.push(( field.0.clone(), self.value( reader, &field.1 )? ));
+				}
+				SnapValue::Struct( vals )
+			}
+			Shape::Variant( ref inner ) => SnapValue::Variant( self.name( reader )?, Box::new( self.value( reader, inner )? )),
+			Shape::Any => {
+				let own = self.shape( reader )?;
+				self.value( reader, &own )?
+			}
+		};
+		reader.leave();
+		Ok( value )
+	}
+}
+
+// --Impl Snapshot
+//------------------------------------------------------------------------------
+// --Impl BinCodec
+
+macro_rules! impl_bin_codec {
+	( $( $t: ty ),+ ) => {
+		$(
+			impl BinCodec for $t {
+				#[inline]
+				fn encode ( &self, out: &mut Vec< u8 > ) {
+					out.extend_from_slice( &self.to_le_bytes() );
+				}
+				
+				#[inline]
+				fn decode ( reader: &mut BinReader<'_> ) -> Result< Self, SnapErr > {
+					Ok( Self::from_le_bytes( reader.take_array()? ))
+				}
+			}
+		)+
+	};
+}
+
+impl_bin_codec!( u8, u16, u32, u64, i8, i16, i32, i64, f32, f64 );
+
+impl BinCodec for usize {
+	#[inline]
+	fn encode ( &self, out: &mut Vec< u8 > ) {
+		// `usize` is at most 64 bits wide on all supported platforms
+		#[allow( clippy::cast_possible_truncation )]
+		( *self as u64 ).encode( out );
+	}
+	
+	#[inline]
+	fn decode ( reader: &mut BinReader<'_> ) -> Result< Self, SnapErr > {
+		Self::try_from( u64::decode( reader )? ).map_err( |err| SnapErr::Decode( err.to_string() ))
+	}
+}
+
+impl BinCodec for bool {
+	#[inline]
+	fn encode ( &self, out: &mut Vec< u8 > ) {
+		out.push( u8::from( *self ));
+	}
+	
+	#[inline]
+	fn decode ( reader: &mut BinReader<'_> ) -> Result< Self, SnapErr > {
+		match u8::decode( reader )? {
+			0 => Ok( false ),
+			1 => Ok( true ),
+			other => Err( SnapErr::Decode( format!( "invalid bool: {other}" ))),
+		}
+	}
+}
+
+impl BinCodec for char {
+	#[inline]
+	fn encode ( &self, out: &mut Vec< u8 > ) {
+		u32::from( *self ).encode( out );
+	}
+	
+	#[inline]
+	fn decode ( reader: &mut BinReader<'_> ) -> Result< Self, SnapErr > {
+		let val = u32::decode( reader )?;
+		Self::from_u32( val ).ok_or_else( || SnapErr::Decode( format!( "invalid char: {val}" )))
+	}
+}
+
+impl BinCodec for String {
+	#[inline]
+	fn encode ( &self, out: &mut Vec< u8 > ) {
+		self.len().encode( out );
+		out.extend_from_slice( self.as_bytes() );
+	}
+	
+	#[inline]
+	fn decode ( reader: &mut BinReader<'_> ) -> Result< Self, SnapErr > {
+		let len = reader.len_prefix()?;
+		let bytes = reader.take( len )?;
+		Self::from_utf8( bytes.to_vec() ).map_err( |err| SnapErr::Decode( err.to_string() ))
+	}
+}
+
+impl< T: BinCodec > BinCodec for Option< T > {
+	#[inline]
+	fn encode ( &self, out: &mut Vec< u8 > ) {
+		match *self {
+			None => out.push( 0 ),
+			Some( ref val ) => {
+				out.push( 1 );
+				val.encode( out );
+			}
+		}
+	}
+	
+	#[inline]
+	fn decode ( reader: &mut BinReader<'_> ) -> Result< Self, SnapErr > {
+		if bool::decode( reader )? {
+			T::decode( reader ).map( Some )
+		} else {
+			Ok( None )
+		}
+	}
+}
+
+impl< T: BinCodec > BinCodec for Vec< T > {
+	#[inline]
+	fn encode ( &self, out: &mut Vec< u8 > ) {
+		self.len().encode( out );
+		for val in self {
+			val.encode( out );
+		}
+	}
+	
+	#[inline]
+	fn decode ( reader: &mut BinReader<'_> ) -> Result< Self, SnapErr > {
+		// every element takes at least one byte, so the length is checked against remaining input
+		let len = reader.len_prefix()?;
+		let mut out = Self::with_capacity( len );
+		for _ in 0 .. len {
+			out.push( T::decode( reader )? );
+		}
+		Ok( out )
+	}
+}
+
+impl< T0: BinCodec, T1: BinCodec > BinCodec for ( T0, T1 ) {
+	#[inline]
+	fn encode ( &self, out: &mut Vec< u8 > ) {
+		self.0.encode( out );
+		self.1.encode( out );
+	}
+	
+	#[inline]
+	fn decode ( reader: &mut BinReader<'_> ) -> Result< Self, SnapErr > {
+		Ok(( T0::decode( reader )?, T1::decode( reader )? ))
+	}
+}
+
+impl BinCodec for SnapValue {
+	#[inline]
+	fn encode ( &self, out: &mut Vec< u8 > ) {
+		match *self {
+			Self::Unit => out.push( 0 ),
+			Self::Bool( val ) => {
+				out.push( 1 );
+				val.encode( out );
+			}
+			Self::Int( val ) => {
+				out.push( 2 );
+				val.encode( out );
+			}
+			Self::UInt( val ) => {
+				out.push( 3 );
+				val.encode( out );
+			}
+			Self::Float( val ) => {
+				out.push( 4 );
+				val.encode( out );
+			}
+			Self::Char( val ) => {
+				out.push( 5 );
+				val.encode( out );
+			}
+			Self::Str( ref val ) => {
+				out.push( 6 );
+				val.encode( out );
+			}
+			Self::Bytes( ref val ) => {
+				out.push( 7 );
+				val.encode( out );
+			}
+			Self::None => out.push( 8 ),
+			Self::Some( ref val ) => {
+				out.push( 9 );
+				val.encode( out );
+			}
+			Self::Seq( ref val ) => {
+				out.push( 10 );
+				val.encode( out );
+			}
+			Self::Map( ref val ) => {
+				out.push( 11 );
+				val.encode( out );
+			}
+			Self::Struct( ref val ) => {
+				out.push( 12 );
+				val.encode( out );
+			}
+			Self::Variant( ref name, ref val ) => {
+				out.push( 13 );
+				name.encode( out );
+				val.encode( out );
+			}
+		}
+	}
+	
+	#[inline]
+	fn decode ( reader: &mut BinReader<'_> ) -> Result< Self, SnapErr > {
+		reader.enter()?;
+		let value = match u8::decode( reader )? {
+			0 => Self::Unit,
+			1 => Self::Bool( BinCodec::decode( reader )? ),
+			2 => Self::Int( BinCodec::decode( reader )? ),
+			3 => Self::UInt( BinCodec::decode( reader )? ),
+			4 => Self::Float( BinCodec::decode( reader )? ),
+			5 => Self::Char( BinCodec::decode( reader )? ),
+			6 => Self::Str( BinCodec::decode( reader )? ),
+			7 => Self::Bytes( BinCodec::decode( reader )? ),
+			8 => Self::None,
+			9 => Self::Some( Box::new( BinCodec::decode( reader )? )),
+			10 => Self::Seq( BinCodec::decode( reader )? ),
+			11 => Self::Map( BinCodec::decode( reader )? ),
+			12 => Self::Struct( BinCodec::decode( reader )? ),
+			13 => Self::Variant( BinCodec::decode( reader )?, Box::new( BinCodec::decode( reader )? )),
+			tag => return Err( SnapErr::Decode( format!( "invalid value tag: {tag}" ))),
+		};
+		reader.leave();
+		Ok( value )
+	}
+}
+
+// --Impl BinCodec
+//------------------------------------------------------------------------------
+// --Tests
+
+#[cfg( test )]
+mod tests {
+	use crate::*;
+	use super::{ checksum, write_varint };
+	
+	fn unit( name: &str, hp: u64 ) -> SnapValue {
+		SnapValue::Struct( vec![
+			( "name".to_owned(), SnapValue::Str( name.to_owned() )),
+			( "hp".to_owned(), SnapValue::UInt( hp )),
+			( "state".to_owned(), SnapValue::Variant( if hp == 0 { "Dead" } else { "Alive" }.to_owned(), Box::new( SnapValue::Unit ))),
+		])
+	}
+	
+	fn sample() -> Snapshot {
+		let mut snapshot = Snapshot::new( 4 );
+		snapshot.version = 3;
+		
+		let mut units = SnapSection::new( "unit" );
+		units.push( 0, unit( "orc", 10 ));
+		units.push( 3, unit( "ghost", 0 ));
+		units.push( 1, unit( "elf", 7 ));
+		snapshot.components.push( units );
+		
+		let mut mixed = SnapSection::new( "mixed" );
+		mixed.push( 0, SnapValue::Seq( vec![ SnapValue::Int( -5 ), SnapValue::Int( i64::MIN ) ] ));
+		mixed.push( 1, SnapValue::Some( Box::new( SnapValue::Float( 1.5 ))));
+		mixed.push( 2, SnapValue::None );
+		mixed.push( 3, SnapValue::Map( vec![( SnapValue::Char( 'x' ), SnapValue::Bytes( vec![ 1, 2 ] ))] ));
+		snapshot.components.push( mixed );
+		
+		snapshot.components.push( SnapSection::new( "empty" ));
+		snapshot
+	}
+	
+	/// Wraps `body` in `Snapshot::MAGIC`, `Snapshot::BIN_FORMAT` and the checksum.
+	fn encoded( body: &[u8] ) -> Vec< u8 > {
+		let mut out = Snapshot::MAGIC.to_vec();
+		Snapshot::BIN_FORMAT.encode( &mut out );
+		out.extend_from_slice( body );
+		checksum( &out ).encode( &mut out );
+		out
+	}
+	
+	#[test]
+	fn round_trip() {
+		let snapshot = sample();
+		assert_eq!( Snapshot::from_bytes( &snapshot.to_bytes().unwrap() ), Ok( snapshot ));
+	}
+	
+	#[test]
+	fn names_are_stored_once() {
+		let bytes = sample().to_bytes().unwrap();
+		let count = |name: &[u8]| bytes.windows( name.len() ).filter( |window| *window == name ).count();
+		
+		assert_eq!( count( b"name" ), 1 );
+		assert_eq!( count( b"Alive" ), 1 );
+		assert_eq!( count( b"orc" ), 1 );
+	}
+	
+	#[test]
+	fn corrupt_input_is_rejected() {
+		let bytes = sample().to_bytes().unwrap();
+		for len in 0 .. bytes.len() {
+			assert!( Snapshot::from_bytes( bytes.get( ..len ).unwrap_or_default() ).is_err() );
+		}
+		
+		for idx in 0 .. bytes.len() {
+			let mut copy = bytes.clone();
+			if let Some( byte ) = copy.get_mut( idx ) {
+				*byte ^= 0x40;
+			}
+			assert!( Snapshot::from_bytes( &copy ).is_err() );
+		}
+	}
+	
+	#[test]
+	fn counts_are_checked_against_remaining_input() {
+		let mut body = Vec::new();
+		for val in [ 1, 0, 0, u64::MAX >> 1 ] {
+			write_varint( &mut body, val );
+		}
+		assert!( matches!( Snapshot::from_bytes( &encoded( &body )), Err( SnapErr::Decode( _ ))));
+	}
+	
+	#[test]
+	fn nesting_is_limited() {
+		let nested = |depth: usize| ( 0 .. depth ).fold( SnapValue::Unit, |val, _| SnapValue::Some( Box::new( val )));
+		
+		let mut snapshot = Snapshot::new( 1 );
+		let mut section = SnapSection::new( "deep" );
+		section.push( 0, nested( BinReader::MAX_DEPTH / 2 ));
+		snapshot.components.push( section );
+		assert_eq!( Snapshot::from_bytes( &snapshot.to_bytes().unwrap() ).as_ref(), Ok( &snapshot ));
+		
+		if let Some( deep ) = snapshot.section_mut( "deep" ) {
+			deep.entries = vec![( 0, nested( BinReader::MAX_DEPTH * 2 ))];
+		}
+		assert!( matches!( snapshot.to_bytes(), Err( SnapErr::Value( _ ))));
+		
+		// whatever encodes also decodes, including values stored along with their own shape
+		for depth in BinReader::MAX_DEPTH - 4 .. BinReader::MAX_DEPTH + 4 {
+			for mixed in [ false, true ] {
+				let mut limit = SnapSection::new( "deep" );
+				limit.push( 0, nested( depth ));
+				if mixed {
+					limit.push( 1, SnapValue::Int( 1 ));
+				}
+				snapshot.components = vec![ limit ];
+				if let Ok( bytes ) = snapshot.to_bytes() {
+					assert_eq!( Snapshot::from_bytes( &bytes ).as_ref(), Ok( &snapshot ));
+				}
+			}
+		}
+		if let Some( deep ) = snapshot.section_mut( "deep" ) {
+			deep.entries = vec![( 0, nested( BinReader::MAX_DEPTH - 1 ))];
+		}
+		assert!( snapshot.to_bytes().is_ok() );
+		
+		let mut bytes = Vec::new();
+		nested( BinReader::MAX_DEPTH * 2 ).encode( &mut bytes );
+		assert!( matches!( SnapValue::decode( &mut BinReader::new( &bytes )), Err( SnapErr::Decode( _ ))));
+	}
+}
@@ -0,0 +1,328 @@
+use crate::{ BinCodec, BinReader, SnapErr, SnapSection, SnapValue, Snapshot };
+
+//------------------------------------------------------------------------------
+// struct - SnapDelta
+
+/// Difference between two snapshots of the same ECS, created by `Snapshot::delta`.
+/// 
+/// Applying it to the older snapshot via `Snapshot::apply_delta` reproduces the newer one.
+/// 
+/// Entity ids are those of the snapshots, `0 .. entities`, which are compacted on save. Despawning an entity thus shifts
+/// the ids of later ones, listing their components as modified and the last id as despawned.
+#[cfg_attr( feature = "serde", derive( serde::Serialize, serde::Deserialize ) )]
+#[derive( Debug, Clone, PartialEq )]
+pub struct SnapDelta {
+	/// Version of the newer snapshot.
+	pub version: u32,
+	/// Amount of entities in the older snapshot.
+	pub base_entities: usize,
+	/// Amount of entities in the newer snapshot.
+	pub entities: usize,
+	/// Changed components, one section per component.
+	pub components: Vec< SectionDelta >,
+	/// Components absent in the newer snapshot.
+	pub dropped: Vec< String >,
+}
+
+impl SnapDelta {
+	/// Returns ids of entities present only in the newer snapshot, `0 .. entities` without `0 .. base_entities`.
+	#[inline]
+	pub fn spawned ( &self ) -> std::ops::Range< usize > {
+		self.base_entities .. self.entities.max( self.base_entities )
+	}
+	
+	/// Returns ids of entities present only in the older snapshot, `0 .. base_entities` without `0 .. entities`.
+	#[inline]
+	pub fn despawned ( &self ) -> std::ops::Range< usize > {
+		self.entities .. self.base_entities.max( self.entities )
+	}
+	
+	/// Checks whether the snapshots were equal.
+	#[inline]
+	pub fn is_empty ( &self ) -> bool {
+		self.base_entities == self.entities && self.components.is_empty() && self.dropped.is_empty()
+	}
+}
+
+/// Changes of a single component.
+#[cfg_attr( feature = "serde", derive( serde::Serialize, serde::Deserialize ) )]
+#[derive( Debug, Clone, PartialEq )]
+pub struct SectionDelta {
+	/// Name of the component.
+	pub name: String,
+	/// Components added to entities, which did not have them.
+	pub inserted: Vec< ( usize, SnapValue ) >,
+	/// Ids of entities which lost the component.
+	pub removed: Vec< usize >,
+	/// Components whose value changed.
+	pub modified: Vec< ( usize, SnapValue ) >,
+}
+
+impl SectionDelta {
+	/// Compares two versions of a section, `old` being `None` when the component is new.
+	fn new ( old: Option< &SnapSection >, new: &SnapSection ) -> Self {
+		let mut old_entries: Vec< &( usize, SnapValue ) > = old.map( |section| section.entries.iter().collect() ).unwrap_or_default();
+		let mut new_entries: Vec< &( usize, SnapValue ) > = new.entries.iter().collect();
+		old_entries.sort_by_key( |entry| entry.0 );
+		new_entries.sort_by_key( |entry| entry.0 );
+		
+		let mut delta = Self {
+			name: new.name.clone(),
+			inserted: Vec::new(),
+			removed: Vec::new(),
+			modified: Vec::new(),
+		};
+		
+		for &&( id, ref value ) in &new_entries {
+			match old_entries.binary_search_by_key( &id, |entry| entry.0 ).ok().and_then( |idx| old_entries.get( idx )) {
+				Some( old_entry ) if old_entry.1 == *value => {}
+				Some( _ ) => delta.modified.push(( id, value.clone() )),
+				None => delta.inserted.push(( id, value.clone() )),
+			}
+		}
+		
+		for &&( id, _ ) in &old_entries {
+			if new_entries.binary_search_by_key( &id, |entry| entry.0 ).is_err() {
+				delta.removed.push( id );
+			}
+		}
+		
+		delta
+	}
+	
+	/// Checks whether the component did not change.
+	#[inline]
+	pub fn is_empty ( &self ) -> bool {
+		self.inserted.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+	}
+	
+	/// Applies the changes to the older version of the section.
+	fn apply ( &self, section: &mut SnapSection ) -> Result< (), SnapErr > {
+		for &id in &self.removed {
+			let idx = section.entries.iter().position( |entry| entry.0 == id ).ok_or( SnapErr::DeltaMismatch )?;
+			_ = section.entries.remove( idx );
+		}
+		
+		for &( id, ref value ) in &self.modified {
+			let entry = section.entries.iter_mut().find( |entry| entry.0 == id ).ok_or( SnapErr::DeltaMismatch )?;
+			entry.1.clone_from( value );
+		}
+		
+		for &( id, ref value ) in &self.inserted {
+			if section.entries.iter().any( |entry| entry.0 == id ) {
+				return Err( SnapErr::DeltaMismatch )
+			}
+			section.push( id, value.clone() );
+		}
+		
+		section.entries.sort_by_key( |entry| entry.0 );
+		Ok(())
+	}
+}
+
+// struct - SnapDelta
+//------------------------------------------------------------------------------
+// --Impl Snapshot
+
+impl Snapshot {
+	/// Creates a delta, which turns `self` into `newer`.
+	/// 
+	/// Both snapshots are expected to come from the same ECS, so that sections are in the same order.
+	/// Entries within a section are kept sorted by entity id.
+	#[inline]
+	pub fn delta ( &self, newer: &Self ) -> SnapDelta {
+		let components = newer.components.iter()
+			.map( |section| ( self.section( &section.name ), section ))
+			.map( |( old, new )| ( old.is_none(), SectionDelta::new( old, new )))
+			.filter( |&( is_new, ref delta )| is_new || !delta.is_empty() )
+			.map( |( _, delta )| delta )
+			.collect();
+		
+		let dropped = self.components.iter()
+			.filter( |section| newer.section( &section.name ).is_none() )
+			.map( |section| section.name.clone() )
+			.collect();
+		
+		SnapDelta {
+			version: newer.version,
+			base_entities: self.entities,
+			entities: newer.entities,
+			components,
+			dropped,
+		}
+	}
+	
+	/// Applies a delta created by `Snapshot::delta`.
+	/// 
+	/// # Errors
+	/// 
+	/// SnapErr::DeltaMismatch - when the delta was created from a different snapshot; `self` may be partially modified
+	#[inline]
+	pub fn apply_delta ( &mut self, delta: &SnapDelta ) -> Result< (), SnapErr > {
+		if self.entities != delta.base_entities {
+			return Err( SnapErr::DeltaMismatch )
+		}
+		
+		self.components.retain( |section| !delta.dropped.contains( &section.name ));
+		
+		for section_delta in &delta.components {
+			if let Some( section ) = self.section_mut( &section_delta.name ) {
+				section_delta.apply( section )?;
+			} else {
+				let mut section = SnapSection::new( &section_delta.name );
+				section_delta.apply( &mut section )?;
+				self.components.push( section );
+			}
+		}
+		
+		self.entities = delta.entities;
+		self.version = delta.version;
+		Ok(())
+	}
+}
+
+// --Impl Snapshot
+//------------------------------------------------------------------------------
+// --Impl BinCodec
+
+impl BinCodec for SectionDelta {
+	#[inline]
+	fn encode ( &self, out: &mut Vec< u8 > ) {
+		self.name.encode( out );
+		self.inserted.encode( out );
+		self.removed.encode( out );
+		self.modified.encode( out );
+	}
+	
+	#[inline]
+	fn decode ( reader: &mut BinReader<'_> ) -> Result< Self, SnapErr > {
+		Ok( Self {
+			name: BinCodec::decode( reader )?,
+			inserted: BinCodec::decode( reader )?,
+			removed: BinCodec::decode( reader )?,
+			modified: BinCodec::decode( reader )?,
+		})
+	}
+}
+
+impl BinCodec for SnapDelta {
+	#[inline]
+	fn encode ( &self, out: &mut Vec< u8 > ) {
+		self.version.encode( out );
+		self.base_entities.encode( out );
+		self.entities.encode( out );
+		self.components.encode( out );
+		self.dropped.encode( out );
+	}
+	
+	#[inline]
+	fn decode ( reader: &mut BinReader<'_> ) -> Result< Self, SnapErr > {
+		Ok( Self {
+			version: BinCodec::decode( reader )?,
+			base_entities: BinCodec::decode( reader )?,
+			entities: BinCodec::decode( reader )?,
+			components: BinCodec::decode( reader )?,
+			dropped: BinCodec::decode( reader )?,
+		})
+	}
+}
+
+// --Impl BinCodec
+//------------------------------------------------------------------------------
+// --Tests
+
+#[cfg( test )]
+#[allow( dead_code )]// not every generated method is used by tests
+mod tests {
+	use crate::*;
+	use minecs_macro::minecs;
+	
+	minecs!(
+		#[snapshot]
+		ecs TestEcs< TestArray, TestEntity > {
+			types [ f64 ]
+			name: String,
+		}
+	);
+	
+	/// Returns an ECS of three named entities, the first one having `f64` too.
+	fn sample() -> ( TestEcs, [EntityId< TestEntity >; 3] ) {
+		let mut ecs = TestEcs::new();
+		let ids = [ "first", "second", "third" ].map( |name| {
+			let id = ecs.new_entity();
+			_ = ecs.insert_name( id, name.to_owned() );
+			id
+		});
+		let [ first, _, _ ] = ids;
+		_ = ecs.insert( first, 1.5_f64 );
+		( ecs, ids )
+	}
+	
+	/// Applies the delta between `old` and the current state of `ecs` to `old`, and checks that loading it reproduces `ecs`.
+	fn applied( old: &Snapshot, ecs: &TestEcs ) -> SnapDelta {
+		let delta = old.delta( &ecs.snapshot().unwrap() );
+		let mut applied = old.clone();
+		assert_eq!( applied.apply_delta( &delta ), Ok(()));
+		assert_eq!( TestEcs::from_snapshot( applied ).as_ref(), Ok( ecs ));
+		delta
+	}
+	
+	#[test]
+	fn unchanged_ecs_gives_empty_delta() {
+		let ( ecs, _ ) = sample();
+		let delta = applied( &ecs.snapshot().unwrap(), &ecs );
+		assert!( delta.is_empty() );
+	}
+	
+	#[test]
+	fn spawned_entities_are_listed() {
+		let ( mut ecs, _ ) = sample();
+		let old = ecs.snapshot().unwrap();
+		let spawned = ecs.new_entity();
+		_ = ecs.insert( spawned, 2.5_f64 );
+		_ = ecs.new_entity();
+		
+		let delta = applied( &old, &ecs );
+		assert_eq!( delta.spawned(), 3 .. 5 );
+		assert!( delta.despawned().is_empty() );
+	}
+	
+	#[test]
+	fn despawned_entities_are_listed() {
+		let ( mut ecs, [ _, second, _ ] ) = sample();
+		let old = ecs.snapshot().unwrap();
+		assert_eq!( ecs.despawn( second ), Ok(()));
+		
+		// the third entity moves to the id of the second one
+		let delta = applied( &old, &ecs );
+		assert_eq!( delta.despawned(), 2 .. 3 );
+		assert!( delta.spawned().is_empty() );
+		let [ ref names ] = *delta.components.as_slice() else {
+			panic!( "expected changes of names only, found: {:?}", delta.components )
+		};
+		assert_eq!( names.removed, vec![ 2 ] );
+		assert_eq!( names.modified.len(), 1 );
+	}
+	
+	#[test]
+	fn component_changes_are_listed() {
+		let ( mut ecs, [ first, second, third ] ) = sample();
+		let old = ecs.snapshot().unwrap();
+		_ = ecs.insert( second, 0.5_f64 );
+		assert_eq!( ecs.remove_name( third ), Ok(()));
+		if let Ok( val ) = ecs.get_for_mut::< f64 >( first ) {
+			*val += 1.0;
+		}
+		
+		let delta = applied( &old, &ecs );
+		assert!( delta.spawned().is_empty() && delta.despawned().is_empty() );
+		let [ ref floats, ref names ] = *delta.components.as_slice() else {
+			panic!( "expected changes of two components, found: {:?}", delta.components )
+		};
+		assert_eq!( floats.inserted.iter().map( |entry| entry.0 ).collect::< Vec< _ >>(), vec![ 1 ] );
+		assert_eq!( floats.modified.iter().map( |entry| entry.0 ).collect::< Vec< _ >>(), vec![ 0 ] );
+		assert!( floats.removed.is_empty() );
+		assert_eq!( names.removed, vec![ 2 ] );
+		assert!( names.inserted.is_empty() && names.modified.is_empty() );
+	}
+}
@@ -0,0 +1,668 @@
+use serde::{ ser, de, Serialize };
+use serde::de::IntoDeserializer;
+
+use crate::{ Prefab, SnapComp, SnapErr, SnapValue };
+
+//------------------------------------------------------------------------------
+// --Impl SnapComp with serde
+
+impl< T > SnapComp for T
+where
+	T: Serialize + de::DeserializeOwned,
+{
+	#[inline]
+	fn to_snap ( &self ) -> Result< SnapValue, SnapErr > {
+		self.serialize( ValueSerializer )
+	}
+	
+	#[inline]
+	fn from_snap ( value: SnapValue ) -> Result< Self, SnapErr > {
+		T::deserialize( value )
+	}
+}
+
+impl ser::Error for SnapErr {
+	#[inline]
+	fn custom< T: std::fmt::Display >( msg: T ) -> Self {
+		Self::Value( msg.to_string() )
+	}
+}
+
+impl de::Error for SnapErr {
+	#[inline]
+	fn custom< T: std::fmt::Display >( msg: T ) -> Self {
+		Self::Value( msg.to_string() )
+	}
+}
+
+// --Impl SnapComp with serde
+//------------------------------------------------------------------------------
+// struct - ValueSerializer
+
+/// Serializes any `serde::Serialize` value into a `SnapValue`.
+struct ValueSerializer;
+
+impl ser::Serializer for ValueSerializer {
+	type Ok = SnapValue;
+	type Error = SnapErr;
+	
+	type SerializeSeq = SeqSerializer;
+	type SerializeTuple = SeqSerializer;
+	type SerializeTupleStruct = SeqSerializer;
+	type SerializeTupleVariant = SeqSerializer;
+	type SerializeMap = MapSerializer;
+	type SerializeStruct = StructSerializer;
+	type SerializeStructVariant = StructSerializer;
+	
+	fn serialize_bool( self, val: bool ) -> Result< SnapValue, SnapErr > {
+		Ok( SnapValue::Bool( val ))
+	}
+	
+	fn serialize_i8( self, val: i8 ) -> Result< SnapValue, SnapErr > {
+		Ok( SnapValue::Int( i64::from( val )))
+	}
+	
+	fn serialize_i16( self, val: i16 ) -> Result< SnapValue, SnapErr > {
+		Ok( SnapValue::Int( i64::from( val )))
+	}
+	
+	fn serialize_i32( self, val: i32 ) -> Result< SnapValue, SnapErr > {
+		Ok( SnapValue::Int( i64::from( val )))
+	}
+	
+	fn serialize_i64( self, val: i64 ) -> Result< SnapValue, SnapErr > {
+		Ok( SnapValue::Int( val ))
+	}
+	
+	fn serialize_u8( self, val: u8 ) -> Result< SnapValue, SnapErr > {
+		Ok( SnapValue::UInt( u64::from( val )))
+	}
+	
+	fn serialize_u16( self, val: u16 ) -> Result< SnapValue, SnapErr > {
+		Ok( SnapValue::UInt( u64::from( val )))
+	}
+	
+	fn serialize_u32( self, val: u32 ) -> Result< SnapValue, SnapErr > {
+		Ok( SnapValue::UInt( u64::from( val )))
+	}
+	
+	fn serialize_u64( self, val: u64 ) -> Result< SnapValue, SnapErr > {
+		Ok( SnapValue::UInt( val ))
+	}
+	
+	fn serialize_f32( self, val: f32 ) -> Result< SnapValue, SnapErr > {
+		Ok( SnapValue::Float( f64::from( val )))
+	}
+	
+	fn serialize_f64( self, val: f64 ) -> Result< SnapValue, SnapErr > {
+		Ok( SnapValue::Float( val ))
+	}
+	
+	fn serialize_char( self, val: char ) -> Result< SnapValue, SnapErr > {
+		Ok( SnapValue::Char( val ))
+	}
+	
+	fn serialize_str( self, val: &str ) -> Result< SnapValue, SnapErr > {
+		Ok( SnapValue::Str( val.to_owned() ))
+	}
+	
+	fn serialize_bytes( self, val: &[u8] ) -> Result< SnapValue, SnapErr > {
+		Ok( SnapValue::Bytes( val.to_vec() ))
+	}
+	
+	fn serialize_none( self ) -> Result< SnapValue, SnapErr > {
+		Ok( SnapValue::None )
+	}
+	
+	fn serialize_some< T: ?Sized + Serialize >( self, val: &T ) -> Result< SnapValue, SnapErr > {
+		Ok( SnapValue::Some( Box::new( val.serialize( self )? )))
+	}
+	
+	fn serialize_unit( self ) -> Result< SnapValue, SnapErr > {
+		Ok( SnapValue::Unit )
+	}
+	
+	fn serialize_unit_struct( self, _name: &'static str ) -> Result< SnapValue, SnapErr > {
+		Ok( SnapValue::Unit )
+	}
+	
+	fn serialize_unit_variant( self, _name: &'static str, _idx: u32, variant: &'static str ) -> Result< SnapValue, SnapErr > {
+		Ok( SnapValue::Variant( variant.to_owned(), Box::new( SnapValue::Unit )))
+	}
+	
+	fn serialize_newtype_struct< T: ?Sized + Serialize >( self, _name: &'static str, val: &T ) -> Result< SnapValue, SnapErr > {
+		val.serialize( self )
+	}
+	
+	fn serialize_newtype_variant< T: ?Sized + Serialize >( self, _name: &'static str, _idx: u32, variant: &'static str, val: &T ) -> Result< SnapValue, SnapErr > {
+		Ok( SnapValue::Variant( variant.to_owned(), Box::new( val.serialize( self )? )))
+	}
+	
+	fn serialize_seq( self, len: Option< usize > ) -> Result< SeqSerializer, SnapErr > {
+		Ok( SeqSerializer { variant: None, items: Vec::with_capacity( len.unwrap_or( 0 )) } )
+	}
+	
+	fn serialize_tuple( self, len: usize ) -> Result< SeqSerializer, SnapErr > {
+		Ok( SeqSerializer { variant: None, items: Vec::with_capacity( len ) } )
+	}
+	
+	fn serialize_tuple_struct( self, _name: &'static str, len: usize ) -> Result< SeqSerializer, SnapErr > {
+		Ok( SeqSerializer { variant: None, items: Vec::with_capacity( len ) } )
+	}
+	
+	fn serialize_tuple_variant( self, _name: &'static str, _idx: u32, variant: &'static str, len: usize ) -> Result< SeqSerializer, SnapErr > {
+		Ok( SeqSerializer { variant: Some( variant ), items: Vec::with_capacity( len ) } )
+	}
+	
+	fn serialize_map( self, len: Option< usize > ) -> Result< MapSerializer, SnapErr > {
+		Ok( MapSerializer { key: None, entries: Vec::with_capacity( len.unwrap_or( 0 )) } )
+	}
+	
+	fn serialize_struct( self, _name: &'static str, len: usize ) -> Result< StructSerializer, SnapErr > {
+		Ok( StructSerializer { variant: None, fields: Vec::with_capacity( len ) } )
+	}
+	
+	fn serialize_struct_variant( self, _name: &'static str, _idx: u32, variant: &'static str, len: usize ) -> Result< StructSerializer, SnapErr > {
+		Ok( StructSerializer { variant: Some( variant ), fields: Vec::with_capacity( len ) } )
+	}
+}
+
+struct SeqSerializer {
+	variant: Option< &'static str >,
+	items: Vec< SnapValue >,
+}
+
+impl SeqSerializer {
+	fn push< T: ?Sized + Serialize >( &mut self, val: &T ) -> Result< (), SnapErr > {
+		self.items.push( val.serialize( ValueSerializer )? );
+		Ok(())
+	}
+	
+	fn finish( self ) -> SnapValue {
+		let seq = SnapValue::Seq( self.items );
+		match self.variant {
+			Some( variant ) => SnapValue::Variant( variant.to_owned(), Box::new( seq )),
+			None => seq,
+		}
+	}
+}
+
+impl ser::SerializeSeq for SeqSerializer {
+	type Ok = SnapValue;
+	type Error = SnapErr;
+	
+	fn serialize_element< T: ?Sized + Serialize >( &mut self, val: &T ) -> Result< (), SnapErr > {
+		self.push( val )
+	}
+	
+	fn end( self ) -> Result< SnapValue, SnapErr > {
+		Ok( self.finish() )
+	}
+}
+
+impl ser::SerializeTuple for SeqSerializer {
+	type Ok = SnapValue;
+	type Error = SnapErr;
+	
+	fn serialize_element< T: ?Sized + Serialize >( &mut self, val: &T ) -> Result< (), SnapErr > {
+		self.push( val )
+	}
+	
+	fn end( self ) -> Result< SnapValue, SnapErr > {
+		Ok( self.finish() )
+	}
+}
+
+impl ser::SerializeTupleStruct for SeqSerializer {
+	type Ok = SnapValue;
+	type Error = SnapErr;
+	
+	fn serialize_field< T: ?Sized + Serialize >( &mut self, val: &T ) -> Result< (), SnapErr > {
+		self.push( val )
+	}
+	
+	fn end( self ) -> Result< SnapValue, SnapErr > {
+		Ok( self.finish() )
+	}
+}
+
+impl ser::SerializeTupleVariant for SeqSerializer {
+	type Ok = SnapValue;
+	type Error = SnapErr;
+	
+	fn serialize_field< T: ?Sized + Serialize >( &mut self, val: &T ) -> Result< (), SnapErr > {
+		self.push( val )
+	}
+	
+	fn end( self ) -> Result< SnapValue, SnapErr > {
+		Ok( self.finish() )
+	}
+}
+
+struct MapSerializer {
+	key: Option< SnapValue >,
+	entries: Vec< ( SnapValue, SnapValue ) >,
+}
+
+impl ser::SerializeMap for MapSerializer {
+	type Ok = SnapValue;
+	type Error = SnapErr;
+	
+	fn serialize_key< T: ?Sized + Serialize >( &mut self, key: &T ) -> Result< (), SnapErr > {
+		self.key = Some( key.serialize( ValueSerializer )? );
+		Ok(())
+	}
+	
+	fn serialize_value< T: ?Sized + Serialize >( &mut self, val: &T ) -> Result< (), SnapErr > {
+		let key = self.key.take().ok_or_else( || SnapErr::Value( "map value without a key".to_owned() ))?;
+		self.entries.push(( key, val.serialize( ValueSerializer )? ));
+		Ok(())
+	}
+	
+	fn end( self ) -> Result< SnapValue, SnapErr > {
+		Ok( SnapValue::Map( self.entries ))
+	}
+}
+
+struct StructSerializer {
+	variant: Option< &'static str >,
+	fields: Vec< ( String, SnapValue ) >,
+}
+
+impl StructSerializer {
+	fn push< T: ?Sized + Serialize >( &mut self, key: &'static str, val: &T ) -> Result< (), SnapErr > {
+		self.fields.push(( key.to_owned(), val.serialize( ValueSerializer )? ));
+		Ok(())
+	}
+	
+	fn finish( self ) -> SnapValue {
+		let value = SnapValue::Struct( self.fields );
+		match self.variant {
+			Some( variant ) => SnapValue::Variant( variant.to_owned(), Box::new( value )),
+			None => value,
+		}
+	}
+}
+
+impl ser::SerializeStruct for StructSerializer {
+	type Ok = SnapValue;
+	type Error = SnapErr;
+	
+	fn serialize_field< T: ?Sized + Serialize >( &mut self, key: &'static str, val: &T ) -> Result< (), SnapErr > {
+		self.push( key, val )
+	}
+	
+	fn end( self ) -> Result< SnapValue, SnapErr > {
+		Ok( self.finish() )
+	}
+}
+
+impl ser::SerializeStructVariant for StructSerializer {
+	type Ok = SnapValue;
+	type Error = SnapErr;
+	
+	fn serialize_field< T: ?Sized + Serialize >( &mut self, key: &'static str, val: &T ) -> Result< (), SnapErr > {
+		self.push( key, val )
+	}
+	
+	fn end( self ) -> Result< SnapValue, SnapErr > {
+		Ok( self.finish() )
+	}
+}
+
+// struct - ValueSerializer
+//------------------------------------------------------------------------------
+// impl Deserializer for SnapValue
+
+impl< 'de > de::Deserializer< 'de > for SnapValue {
+	type Error = SnapErr;
+	
+	#[inline]
+	fn deserialize_any< V: de::Visitor< 'de > >( self, visitor: V ) -> Result< V::Value, SnapErr > {
+		match self {
+			Self::Unit => visitor.visit_unit(),
+			Self::Bool( val ) => visitor.visit_bool( val ),
+			Self::Int( val ) => visitor.visit_i64( val ),
+			Self::UInt( val ) => visitor.visit_u64( val ),
+			Self::Float( val ) => visitor.visit_f64( val ),
+			Self::Char( val ) => visitor.visit_char( val ),
+			Self::Str( val ) => visitor.visit_string( val ),
+			Self::Bytes( val ) => visitor.visit_byte_buf( val ),
+			Self::None => visitor.visit_none(),
+			Self::Some( val ) => visitor.visit_some( *val ),
+			Self::Seq( vec ) => visitor.visit_seq( SeqDeserializer { iter: vec.into_iter() } ),
+			Self::Map( vec ) => visitor.visit_map( MapDeserializer { iter: vec.into_iter(), value: None } ),
+			Self::Struct( vec ) => visitor.visit_map( MapDeserializer {
+				iter: vec.into_iter().map( |( key, val )| ( Self::Str( key ), val )).collect::< Vec<_> >().into_iter(),
+				value: None,
+			}),
+			Self::Variant( name, val ) => visitor.visit_enum( EnumDeserializer { name, value: *val } ),
+		}
+	}
+	
+	#[inline]
+	fn deserialize_option< V: de::Visitor< 'de > >( self, visitor: V ) -> Result< V::Value, SnapErr > {
+		match self {
+			Self::None | Self::Unit => visitor.visit_none(),
+			Self::Some( val ) => visitor.visit_some( *val ),
+			other => visitor.visit_some( other ),
+		}
+	}
+	
+	#[inline]
+	fn deserialize_newtype_struct< V: de::Visitor< 'de > >( self, _name: &'static str, visitor: V ) -> Result< V::Value, SnapErr > {
+		visitor.visit_newtype_struct( self )
+	}
+	
+	#[inline]
+	fn deserialize_enum< V: de::Visitor< 'de > >( self, _name: &'static str, _variants: &'static [&'static str], visitor: V ) -> Result< V::Value, SnapErr > {
+		match self {
+			Self::Variant( name, val ) => visitor.visit_enum( EnumDeserializer { name, value: *val } ),
+			Self::Str( name ) => visitor.visit_enum( EnumDeserializer { name, value: Self::Unit } ),
+			// `{ "Variant": value }`, as written by self-describing formats
+			Self::Map( mut vec ) if vec.len() == 1 => match vec.pop() {
+				Some(( Self::Str( name ), value )) => visitor.visit_enum( EnumDeserializer { name, value } ),
+				_ => Err( SnapErr::Value( "expected enum variant name".to_owned() )),
+			},
+			other => Err( other.unexpected( "enum variant" )),
+		}
+	}
+	
+	#[inline]
+	fn deserialize_unit_struct< V: de::Visitor< 'de > >( self, _name: &'static str, visitor: V ) -> Result< V::Value, SnapErr > {
+		visitor.visit_unit()
+	}
+	
+	serde::forward_to_deserialize_any! {
+		bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+		bytes byte_buf unit seq tuple tuple_struct map struct identifier ignored_any
+	}
+}
+
+impl IntoDeserializer< '_, SnapErr > for SnapValue {
+	type Deserializer = Self;
+	
+	#[inline]
+	fn into_deserializer( self ) -> Self {
+		self
+	}
+}
+
+struct SeqDeserializer {
+	iter: std::vec::IntoIter< SnapValue >,
+}
+
+impl< 'de > de::SeqAccess< 'de > for SeqDeserializer {
+	type Error = SnapErr;
+	
+	fn next_element_seed< T: de::DeserializeSeed< 'de > >( &mut self, seed: T ) -> Result< Option< T::Value >, SnapErr > {
+		self.iter.next().map( |val| seed.deserialize( val )).transpose()
+	}
+	
+	fn size_hint( &self ) -> Option< usize > {
+		Some( self.iter.len() )
+	}
+}
+
+struct MapDeserializer {
+	iter: std::vec::IntoIter< ( SnapValue, SnapValue ) >,
+	value: Option< SnapValue >,
+}
+
+impl< 'de > de::MapAccess< 'de > for MapDeserializer {
+	type Error = SnapErr;
+	
+	fn next_key_seed< K: de::DeserializeSeed< 'de > >( &mut self, seed: K ) -> Result< Option< K::Value >, SnapErr > {
+		match self.iter.next() {
+			Some(( key, val )) => {
+				self.value = Some( val );
+				seed.deserialize( key ).map( Some )
+			}
+			None => Ok( None ),
+		}
+	}
+	
+	fn next_value_seed< T: de::DeserializeSeed< 'de > >( &mut self, seed: T ) -> Result< T::Value, SnapErr > {
+		let val = self.value.take().ok_or_else( || SnapErr::Value( "map key without a value".to_owned() ))?;
+		seed.deserialize( val )
+	}
+	
+	fn size_hint( &self ) -> Option< usize > {
+		Some( self.iter.len() )
+	}
+}
+
+struct EnumDeserializer {
+	name: String,
+	value: SnapValue,
+}
+
+impl< 'de > de::EnumAccess< 'de > for EnumDeserializer {
+	type Error = SnapErr;
+	type Variant = SnapValue;
+	
+	fn variant_seed< T: de::DeserializeSeed< 'de > >( self, seed: T ) -> Result< ( T::Value, SnapValue ), SnapErr > {
+		let variant = seed.deserialize( self.name.into_deserializer() )?;
+		Ok(( variant, self.value ))
+	}
+}
+
+impl< 'de > de::VariantAccess< 'de > for SnapValue {
+	type Error = SnapErr;
+	
+	#[inline]
+	fn unit_variant( self ) -> Result< (), SnapErr > {
+		match self {
+			Self::Unit => Ok(()),
+			other => Err( other.unexpected( "unit variant" )),
+		}
+	}
+	
+	#[inline]
+	fn newtype_variant_seed< T: de::DeserializeSeed< 'de > >( self, seed: T ) -> Result< T::Value, SnapErr > {
+		seed.deserialize( self )
+	}
+	
+	#[inline]
+	fn tuple_variant< V: de::Visitor< 'de > >( self, _len: usize, visitor: V ) -> Result< V::Value, SnapErr > {
+		de::Deserializer::deserialize_seq( self, visitor )
+	}
+	
+	#[inline]
+	fn struct_variant< V: de::Visitor< 'de > >( self, _fields: &'static [&'static str], visitor: V ) -> Result< V::Value, SnapErr > {
+		de::Deserializer::deserialize_map( self, visitor )
+	}
+}
+
+// impl Deserializer for SnapValue
+//------------------------------------------------------------------------------
+// impl Serialize, Deserialize for Prefab
+
+/// Borrowed `SnapValue`, serialized in the form natural for self-describing formats, fe. structs as maps.
+struct NaturalRef< 'a >( &'a SnapValue );
+
+impl Serialize for NaturalRef< '_ > {
+	fn serialize< S: ser::Serializer >( &self, serializer: S ) -> Result< S::Ok, S::Error > {
+		use ser::{ SerializeMap, SerializeSeq };
+		
+		match *self.0 {
+			SnapValue::Unit => serializer.serialize_unit(),
+			SnapValue::Bool( val ) => serializer.serialize_bool( val ),
+			SnapValue::Int( val ) => serializer.serialize_i64( val ),
+			SnapValue::UInt( val ) => serializer.serialize_u64( val ),
+			SnapValue::Float( val ) => serializer.serialize_f64( val ),
+			SnapValue::Char( val ) => serializer.serialize_char( val ),
+			SnapValue::Str( ref val ) => serializer.serialize_str( val ),
+			SnapValue::Bytes( ref val ) => serializer.serialize_bytes( val ),
+			SnapValue::None => serializer.serialize_none(),
+			SnapValue::Some( ref val ) => serializer.serialize_some( &NaturalRef( val )),
+			SnapValue::Seq( ref vec ) => {
+				let mut seq = serializer.serialize_seq( Some( vec.len() ))?;
+				for val in vec {
+					seq.serialize_element( &NaturalRef( val ))?;
+				}
+				seq.end()
+			}
+			SnapValue::Map( ref vec ) => {
+				let mut map = serializer.serialize_map( Some( vec.len() ))?;
+				for entry in vec {
+					map.serialize_entry( &NaturalRef( &entry.0 ), &NaturalRef( &entry.1 ))?;
+				}
+				map.end()
+			}
+			SnapValue::Struct( ref vec ) => {
+				let mut map = serializer.serialize_map( Some( vec.len() ))?;
+				for entry in vec {
+					map.serialize_entry( &entry.0, &NaturalRef( &entry.1 ))?;
+				}
+				map.end()
+			}
+			SnapValue::Variant( ref name, ref val ) => {
+				if **val == SnapValue::Unit {
+					serializer.serialize_str( name )
+				} else {
+					let mut map = serializer.serialize_map( Some( 1 ))?;
+					map.serialize_entry( name, &NaturalRef( val ))?;
+					map.end()
+				}
+			}
+		}
+	}
+}
+
+/// `SnapValue` deserialized from the form natural for self-describing formats.
+struct Natural( SnapValue );
+
+impl< 'de > de::Deserialize< 'de > for Natural {
+	fn deserialize< D: de::Deserializer< 'de > >( deserializer: D ) -> Result< Self, D::Error > {
+		deserializer.deserialize_any( NaturalVisitor ).map( Natural )
+	}
+}
+
+struct NaturalVisitor;
+
+impl< 'de > de::Visitor< 'de > for NaturalVisitor {
+	type Value = SnapValue;
+	
+	fn expecting( &self, formatter: &mut std::fmt::Formatter<'_> ) -> std::fmt::Result {
+		formatter.write_str( "any value" )
+	}
+	
+	fn visit_bool< Er: de::Error >( self, val: bool ) -> Result< SnapValue, Er > {
+		Ok( SnapValue::Bool( val ))
+	}
+	
+	fn visit_i64< Er: de::Error >( self, val: i64 ) -> Result< SnapValue, Er > {
+		Ok( SnapValue::Int( val ))
+	}
+	
+	fn visit_u64< Er: de::Error >( self, val: u64 ) -> Result< SnapValue, Er > {
+		Ok( SnapValue::UInt( val ))
+	}
+	
+	fn visit_f64< Er: de::Error >( self, val: f64 ) -> Result< SnapValue, Er > {
+		Ok( SnapValue::Float( val ))
+	}
+	
+	fn visit_char< Er: de::Error >( self, val: char ) -> Result< SnapValue, Er > {
+		Ok( SnapValue::Char( val ))
+	}
+	
+	fn visit_str< Er: de::Error >( self, val: &str ) -> Result< SnapValue, Er > {
+		Ok( SnapValue::Str( val.to_owned() ))
+	}
+	
+	fn visit_string< Er: de::Error >( self, val: String ) -> Result< SnapValue, Er > {
+		Ok( SnapValue::Str( val ))
+	}
+	
+	fn visit_bytes< Er: de::Error >( self, val: &[u8] ) -> Result< SnapValue, Er > {
+		Ok( SnapValue::Bytes( val.to_vec() ))
+	}
+	
+	fn visit_none< Er: de::Error >( self ) -> Result< SnapValue, Er > {
+		Ok( SnapValue::None )
+	}
+	
+	fn visit_some< D: de::Deserializer< 'de > >( self, deserializer: D ) -> Result< SnapValue, D::Error > {
+		< Natural as de::Deserialize >::deserialize( deserializer ).map( |val| SnapValue::Some( Box::new( val.0 )))
+	}
+	
+	fn visit_unit< Er: de::Error >( self ) -> Result< SnapValue, Er > {
+		Ok( SnapValue::Unit )
+	}
+	
+	fn visit_newtype_struct< D: de::Deserializer< 'de > >( self, deserializer: D ) -> Result< SnapValue, D::Error > {
+		< Natural as de::Deserialize >::deserialize( deserializer ).map( |val| val.0 )
+	}
+	
+	fn visit_seq< A: de::SeqAccess< 'de > >( self, mut seq: A ) -> Result< SnapValue, A::Error > {
+		let mut out = Vec::new();
+		while let Some( Natural( val )) = seq.next_element()? {
+			out.push( val );
+		}
+		Ok( SnapValue::Seq( out ))
+	}
+	
+	fn visit_map< A: de::MapAccess< 'de > >( self, mut map: A ) -> Result< SnapValue, A::Error > {
+		let mut out = Vec::new();
+		while let Some(( Natural( key ), Natural( val ))) = map.next_entry()? {
+			out.push(( key, val ));
+		}
+		Ok( SnapValue::Map( out ))
+	}
+}
+
+/// Prefab in its serialized form, with components being a map of component names to values.
+#[derive( serde::Deserialize )]
+struct PrefabRepr {
+	name: String,
+	components: Natural,
+}
+
+impl Serialize for Prefab {
+	#[inline]
+	fn serialize< S: ser::Serializer >( &self, serializer: S ) -> Result< S::Ok, S::Error > {
+		use ser::{ SerializeMap, SerializeStruct };
+		
+		struct Components< 'a >( &'a [( String, SnapValue )] );
+		
+		impl Serialize for Components< '_ > {
+			fn serialize< S: ser::Serializer >( &self, serializer: S ) -> Result< S::Ok, S::Error > {
+				let mut map = serializer.serialize_map( Some( self.0.len() ))?;
+				for entry in self.0 {
+					map.serialize_entry( &entry.0, &NaturalRef( &entry.1 ))?;
+				}
+				map.end()
+			}
+		}
+		
+		let mut out = serializer.serialize_struct( "Prefab", 2 )?;
+		out.serialize_field( "name", &self.name )?;
+		out.serialize_field( "components", &Components( &self.components ))?;
+		out.end()
+	}
+}
+
+impl< 'de > de::Deserialize< 'de > for Prefab {
+	#[inline]
+	fn deserialize< D: de::Deserializer< 'de > >( deserializer: D ) -> Result< Self, D::Error > {
+		let repr = < PrefabRepr as de::Deserialize >::deserialize( deserializer )?;
+		let SnapValue::Map( entries ) = repr.components.0 else {
+			return Err( de::Error::custom( format!( "prefab: {} expected a map of components", repr.name )))
+		};
+		
+		let mut components = Vec::with_capacity( entries.len() );
+		for ( key, val ) in entries {
+			let SnapValue::Str( name ) = key else {
+				return Err( de::Error::custom( format!( "prefab: {} expected component name, found {}", repr.name, key.kind() )))
+			};
+			components.push(( name, val ));
+		}
+		
+		Ok( Self { name: repr.name, components })
+	}
+}
+
+// impl Serialize, Deserialize for Prefab
+//------------------------------------------------------------------------------
@@ -0,0 +1,388 @@
+use crate::{ BinReader, EntityId, SnapErr, SnapValue };
+
+//------------------------------------------------------------------------------
+// --Traits
+
+/// Spawning of entities out of `Prefab`s, implemented by `minecs!` for ECS declared with `#[snapshot]`.
+pub trait EcsPrefab< E > {
+	/// Spawns a new entity with all components of the prefab.
+	/// 
+	/// Components are named same as in `Snapshot`: field name for named components, type for generic ones.
+	/// All values are converted before the entity is spawned, so on error the ECS is left unchanged.
+	/// 
+	/// # Errors
+	/// 
+	/// SnapErr::UnknownPrefabComponent - when the prefab contains a component not present in the ECS, or a `#[transient]` one
+	/// SnapErr::Value - when a value cannot be converted into its component
+	fn spawn_prefab ( &mut self, prefab: &Prefab ) -> Result< EntityId< E >, SnapErr >;
+}
+
+// --Traits
+//------------------------------------------------------------------------------
+// struct - Prefab
+
+/// Named template of an entity, a set of component values keyed by component names.
+/// 
+/// Prefabs can be written in a simple built-in text format and loaded with `Prefab::parse_all`,
+/// or, with `serde` feature, loaded from any self-describing serde format such as JSON or RON,
+/// as a map of `name` and `components`, the latter being a map of component names to their values.
+/// 
+/// # Built-in format
+/// 
+/// ```text
+/// # comments start with `#`
+/// [goblin]                          # header starts a new prefab
+/// health = 30                       # integers, floats, `true`, `false`
+/// name = "Goblin"                   # strings, chars: 'g'
+/// position = ( 1.5, -2.0 )          # tuples and lists: [ 1, 2 ], unit: ()
+/// armor = { value = 5, weight = 1 } # structs, the type name may precede braces: Armor { .. }
+/// mood = Angry                      # enum variants: Angry, Follow( 3 ), Move( 1, 2 )
+/// target = none                     # options: none, some( 3 )
+/// ```
+#[derive( Debug, Clone, PartialEq )]
+pub struct Prefab {
+	pub name: String,
+	/// Pairs of component name and its value.
+	pub components: Vec< ( String, SnapValue ) >,
+}
+
+impl Prefab {
+	/// Creates an empty prefab.
+	#[inline]
+	pub fn new ( name: &str ) -> Self {
+		Self {
+			name: name.to_owned(),
+			components: Vec::new(),
+		}
+	}
+	
+	/// Adds value of the component with specified name.
+	#[inline]
+	#[must_use]
+	pub fn with ( mut self, component: &str, value: SnapValue ) -> Self {
+		self.components.push(( component.to_owned(), value ));
+		self
+	}
+	
+	/// Returns value of the component with specified name if present.
+	#[inline]
+	pub fn get ( &self, component: &str ) -> Option< &SnapValue > {
+		self.components.iter().find( |entry| entry.0 == component ).map( |entry| &entry.1 )
+	}
+	
+	/// Parses all prefabs written in the built-in text format.
+	/// 
+	/// # Errors
+	/// 
+	/// SnapErr::Parse - when the text is invalid or values are nested deeper than `BinReader::MAX_DEPTH`, along with the line of the error
+	#[inline]
+	pub fn parse_all ( text: &str ) -> Result< Vec< Self >, SnapErr > {
+		let mut parser = Parser::new( text );
+		let mut out: Vec< Self > = Vec::new();
+		
+		while let Some( ch ) = parser.skip_blank() {
+			if ch == '[' {
+				parser.bump();
+				parser.skip_blank();
+				let name = parser.ident()?;
+				parser.expect( ']' )?;
+				out.push( Self::new( &name ));
+				continue
+			}
+			
+			let component = parser.ident()?;
+			parser.expect( '=' )?;
+			let value = parser.value()?;
+			let Some( prefab ) = out.last_mut() else {
+				return Err( parser.error( "component declared before any `[prefab]` header" ))
+			};
+			prefab.components.push(( component, value ));
+		}
+		
+		Ok( out )
+	}
+	
+	/// Parses a single prefab written in the built-in text format.
+	/// 
+	/// # Errors
+	/// 
+	/// SnapErr::Parse - when the text is invalid, or does not contain exactly one prefab
+	#[inline]
+	pub fn parse ( text: &str ) -> Result< Self, SnapErr > {
+		let mut all = Self::parse_all( text )?;
+		match all.len() {
+			1 => all.pop().ok_or_else( || SnapErr::Parse( 0, "expected exactly one prefab".to_owned() )),
+			len => Err( SnapErr::Parse( 0, format!( "expected exactly one prefab, found {len}" ))),
+		}
+	}
+}
+
+// struct - Prefab
+//------------------------------------------------------------------------------
+// struct - Parser
+
+/// Parser of the built-in prefab format, tracking nesting of values so that deeply nested input cannot overflow the stack.
+struct Parser< 'a > {
+	chars: std::iter::Peekable< std::str::Chars< 'a > >,
+	line: usize,
+	depth: usize,
+}
+
+impl< 'a > Parser< 'a > {
+	fn new ( text: &'a str ) -> Self {
+		Self {
+			chars: text.chars().peekable(),
+			line: 1,
+			depth: 0,
+		}
+	}
+	
+	fn error ( &self, msg: &str ) -> SnapErr {
+		SnapErr::Parse( self.line, msg.to_owned() )
+	}
+	
+	fn peek ( &mut self ) -> Option< char > {
+		self.chars.peek().copied()
+	}
+	
+	fn bump ( &mut self ) -> Option< char > {
+		let ch = self.chars.next();
+		if ch == Some( '\n' ) {
+			self.line += 1;
+		}
+		ch
+	}
+	
+	/// Skips whitespace and comments, returns next character.
+	fn skip_blank ( &mut self ) -> Option< char > {
+		while let Some( ch ) = self.peek() {
+			if ch == '#' {
+				while self.peek().is_some_and( |next| next != '\n' ) {
+					self.bump();
+				}
+			} else if ch.is_whitespace() {
+				self.bump();
+			} else {
+				return Some( ch )
+			}
+		}
+		None
+	}
+	
+	fn expect ( &mut self, expected: char ) -> Result< (), SnapErr > {
+		match self.skip_blank() {
+			Some( ch ) if ch == expected => {
+				self.bump();
+				Ok(())
+			}
+			Some( ch ) => Err( self.error( &format!( "expected `{expected}`, found `{ch}`" ))),
+			None => Err( self.error( &format!( "expected `{expected}`, found end of input" ))),
+		}
+	}
+	
+	fn ident ( &mut self ) -> Result< String, SnapErr > {
+		let mut out = String::new();
+		while let Some( ch ) = self.peek().filter( |&next| next.is_alphanumeric() || next == '_' ) {
+			out.push( ch );
+			self.bump();
+		}
+		
+		if out.is_empty() {
+			Err( match self.peek() {
+				Some( ch ) => self.error( &format!( "expected identifier, found `{ch}`" )),
+				None => self.error( "expected identifier, found end of input" ),
+			})
+		} else {
+			Ok( out )
+		}
+	}
+	
+	/// Parses comma separated values until `close`, the opening bracket being already consumed.
+	fn list ( &mut self, close: char ) -> Result< Vec< SnapValue >, SnapErr > {
+		let mut out = Vec::new();
+		loop {
+			if self.skip_blank() == Some( close ) {
+				self.bump();
+				return Ok( out )
+			}
+			out.push( self.value()? );
+			if self.skip_blank() == Some( ',' ) {
+				self.bump();
+			} else {
+				self.expect( close )?;
+				return Ok( out )
+			}
+		}
+	}
+	
+	/// Parses `key = value` pairs until `}`, the opening brace being already consumed.
+	fn fields ( &mut self ) -> Result< SnapValue, SnapErr > {
+		let mut out = Vec::new();
+		loop {
+			if self.skip_blank() == Some( '}' ) {
+				self.bump();
+				return Ok( SnapValue::Struct( out ))
+			}
+			let key = self.ident()?;
+			self.expect( '=' )?;
+			out.push(( key, self.value()? ));
+			if self.skip_blank() == Some( ',' ) {
+				self.bump();
+			} else {
+				self.expect( '}' )?;
+				return Ok( SnapValue::Struct( out ))
+			}
+		}
+	}
+	
+	fn value ( &mut self ) -> Result< SnapValue, SnapErr > {
+		if self.depth >= BinReader::MAX_DEPTH {
+			return Err( self.error( "values nested too deep" ))
+		}
+		self.depth += 1;
+		let out = self.nested_value();
+		self.depth -= 1;
+		out
+	}
+	
+	/// Parses a single value, called only by `Parser::value` which tracks nesting.
+	fn nested_value ( &mut self ) -> Result< SnapValue, SnapErr > {
+		match self.skip_blank() {
+			Some( '"' ) => self.string(),
+			Some( '\'' ) => self.char(),
+			Some( '[' ) => {
+				self.bump();
+				self.list( ']' ).map( SnapValue::Seq )
+			}
+			Some( '(' ) => {
+				self.bump();
+				let list = self.list( ')' )?;
+				Ok( if list.is_empty() { SnapValue::Unit } else { SnapValue::Seq( list ) })
+			}
+			Some( '{' ) => {
+				self.bump();
+				self.fields()
+			}
+			Some( ch ) if ch == '-' || ch.is_ascii_digit() => self.number(),
+			Some( ch ) if ch.is_alphabetic() || ch == '_' => self.named(),
+			Some( ch ) => Err( self.error( &format!( "expected value, found `{ch}`" ))),
+			None => Err( self.error( "expected value, found end of input" )),
+		}
+	}
+	
+	/// Parses keywords, enum variants and named structs.
+	fn named ( &mut self ) -> Result< SnapValue, SnapErr > {
+		let name = self.ident()?;
+		match name.as_str() {
+			"true" => return Ok( SnapValue::Bool( true )),
+			"false" => return Ok( SnapValue::Bool( false )),
+			"none" => return Ok( SnapValue::None ),
+			_ => {}
+		}
+		
+		match self.skip_blank() {
+			Some( '{' ) => {
+				self.bump();
+				self.fields()
+			}
+			Some( '(' ) => {
+				self.bump();
+				let mut list = self.list( ')' )?;
+				let inner = match list.len() {
+					0 => SnapValue::Unit,
+					1 => list.pop().unwrap_or( SnapValue::Unit ),
+					_ => SnapValue::Seq( list ),
+				};
+				Ok( if name == "some" {
+					SnapValue::Some( Box::new( inner ))
+				} else {
+					SnapValue::Variant( name, Box::new( inner ))
+				})
+			}
+			_ => Ok( SnapValue::Variant( name, Box::new( SnapValue::Unit ))),
+		}
+	}
+	
+	fn number ( &mut self ) -> Result< SnapValue, SnapErr > {
+		let mut text = String::new();
+		while let Some( ch ) = self.peek().filter( |&next| next.is_ascii_alphanumeric() || matches!( next, '-' | '+' | '.' | '_' )) {
+			if ch != '_' {
+				text.push( ch );
+			}
+			self.bump();
+		}
+		
+		let result = if text.contains( [ '.', 'e', 'E' ] ) {
+			text.parse().map( SnapValue::Float ).map_err( |err| err.to_string() )
+		} else if text.starts_with( '-' ) {
+			text.parse().map( SnapValue::Int ).map_err( |err| err.to_string() )
+		} else {
+			text.parse().map( SnapValue::UInt ).map_err( |err| err.to_string() )
+		};
+		result.map_err( |err| self.error( &format!( "invalid number `{text}`: {err}" )))
+	}
+	
+	fn escaped ( &mut self ) -> Result< char, SnapErr > {
+		match self.bump() {
+			Some( 'n' ) => Ok( '\n' ),
+			Some( 't' ) => Ok( '\t' ),
+			Some( 'r' ) => Ok( '\r' ),
+			Some( '0' ) => Ok( '\0' ),
+			Some( ch @ ( '\\' | '"' | '\'' )) => Ok( ch ),
+			Some( ch ) => Err( self.error( &format!( "unknown escape `\\{ch}`" ))),
+			None => Err( self.error( "unterminated escape" )),
+		}
+	}
+	
+	fn string ( &mut self ) -> Result< SnapValue, SnapErr > {
+		self.bump();
+		let mut out = String::new();
+		loop {
+			match self.bump() {
+				Some( '"' ) => return Ok( SnapValue::Str( out )),
+				Some( '\\' ) => out.push( self.escaped()? ),
+				Some( ch ) => out.push( ch ),
+				None => return Err( self.error( "unterminated string" )),
+			}
+		}
+	}
+	
+	fn char ( &mut self ) -> Result< SnapValue, SnapErr > {
+		self.bump();
+		let ch = match self.bump() {
+			Some( '\\' ) => self.escaped()?,
+			Some( ch ) => ch,
+			None => return Err( self.error( "unterminated char" )),
+		};
+		if self.bump() == Some( '\'' ) {
+			Ok( SnapValue::Char( ch ))
+		} else {
+			Err( self.error( "expected `'` closing char" ))
+		}
+	}
+}
+
+// struct - Parser
+//------------------------------------------------------------------------------
+
+//------------------------------------------------------------------------------
+// --Tests
+
+#[cfg( test )]
+mod tests {
+	use crate::*;
+	
+	fn nested ( depth: usize ) -> String {
+		format!( "[deep]\nvalue = {}1{}", "[".repeat( depth - 1 ), "]".repeat( depth - 1 ))
+	}
+	
+	#[test]
+	fn nesting_is_limited() {
+		let prefab = Prefab::parse( &nested( BinReader::MAX_DEPTH )).unwrap();
+		assert!( matches!( prefab.get( "value" ), Some( SnapValue::Seq(_) )));
+		
+		let too_deep = Err( SnapErr::Parse( 2, "values nested too deep".to_owned() ));
+		assert_eq!( Prefab::parse( &nested( BinReader::MAX_DEPTH + 1 )), too_deep );
+		assert_eq!( Prefab::parse_all( &format!( "[p]\nx = {}", "[".repeat( 200_000 ))), too_deep.map( |single| vec![ single ] ));
+	}
+}
@@ -0,0 +1,1171 @@
+use std::any::{ Any, TypeId };
+
+use crate::{ CompId, CompName, Component, EcsErr, EcsValidate, EntityId, TagFilter, TagSet, Tagged };
+
+//------------------------------------------------------------------------------
+// --Traits
+
+/// Splitting of the ECS into separately borrowed columns, implemented by `minecs!` for ECS declared with `#[parallel]`.
+/// 
+/// Requires `EcsValidate`, so that `Schedule::run` can check the ECS after systems changed it.
+pub trait EcsColumns< E >: EcsValidate {
+	/// Borrows all entities along with every component type and resource as a separate column.
+	fn columns_mut ( &mut self ) -> Columns< '_, E >;
+}
+
+/// Splitting of the component array into columns, implemented by `minecs!` for ECS declared with `#[parallel]`.
+pub trait CompColumns {
+	/// Mutably borrows components of every type as a separate column.
+	fn columns_mut ( &mut self ) -> Vec< Column< '_ > >;
+}
+
+// --Traits
+//------------------------------------------------------------------------------
+// struct - Access
+
+#[derive( Debug, Clone, Copy, PartialEq, Eq )]
+struct AccessKey {
+	type_id: TypeId,
+	resource: bool,
+}
+
+impl AccessKey {
+	fn component< T: 'static > () -> Self {
+		Self {
+			type_id: TypeId::of::< T >(),
+			resource: false,
+		}
+	}
+	
+	fn resource< R: 'static > () -> Self {
+		Self {
+			type_id: TypeId::of::< R >(),
+			resource: true,
+		}
+	}
+}
+
+/// Component types and resources read and written by a system.
+/// 
+/// Two accesses conflict when one of them writes a component type or resource the other one reads or writes.
+/// Writing subsumes reading, declaring both only keeps the write.
+#[derive( Debug, Clone, Default, PartialEq )]
+pub struct Access {
+	reads: Vec< ( AccessKey, CompName ) >,
+	writes: Vec< ( AccessKey, CompName ) >,
+}
+
+impl Access {
+	#[inline]
+	pub const fn new () -> Self {
+		Self {
+			reads: Vec::new(),
+			writes: Vec::new(),
+		}
+	}
+	
+	/// Declares reading components of type `T`.
+	#[inline]
+	#[must_use]
+	pub fn read< T: 'static > ( mut self ) -> Self {
+		self.add_read( AccessKey::component::< T >(), CompName::of::< T >() );
+		self
+	}
+	
+	/// Declares writing components of type `T`.
+	#[inline]
+	#[must_use]
+	pub fn write< T: 'static > ( mut self ) -> Self {
+		self.add_write( AccessKey::component::< T >(), CompName::of::< T >() );
+		self
+	}
+	
+	/// Declares reading resource of type `R`.
+	#[inline]
+	#[must_use]
+	pub fn read_resource< R: 'static > ( mut self ) -> Self {
+		self.add_read( AccessKey::resource::< R >(), CompName::of::< R >() );
+		self
+	}
+	
+	/// Declares writing resource of type `R`.
+	#[inline]
+	#[must_use]
+	pub fn write_resource< R: 'static > ( mut self ) -> Self {
+		self.add_write( AccessKey::resource::< R >(), CompName::of::< R >() );
+		self
+	}
+	
+	/// Returns the name of the first component type or resource written by one access and read or written by the other,
+	/// or `None` when the accesses do not conflict.
+	#[inline]
+	pub fn conflict ( &self, other: &Self ) -> Option< CompName > {
+		let touches = |access: &Self, key: AccessKey| access.reads.iter().chain( &access.writes ).any( |entry| entry.0 == key );
+		
+		self.writes.iter().find( |entry| touches( other, entry.0 ))
+			.or_else( || other.writes.iter().find( |entry| touches( self, entry.0 )))
+			.map( |entry| entry.1 )
+	}
+	
+	fn add_read ( &mut self, key: AccessKey, name: CompName ) {
+		if self.reads.iter().chain( &self.writes ).all( |entry| entry.0 != key ) {
+			self.reads.push(( key, name ));
+		}
+	}
+	
+	fn add_write ( &mut self, key: AccessKey, name: CompName ) {
+		self.reads.retain( |entry| entry.0 != key );
+		if self.writes.iter().all( |entry| entry.0 != key ) {
+			self.writes.push(( key, name ));
+		}
+	}
+}
+
+// struct - Access
+//------------------------------------------------------------------------------
+// struct - Column
+
+/// Components of one type, or a resource, borrowed separately from the rest of the ECS.
+#[derive( Debug )]
+pub struct Column< 'w > {
+	key: AccessKey,
+	data: &'w mut ( dyn Any + Send + Sync ),
+	recycled: &'w [usize],
+}
+
+impl< 'w > Column< 'w > {
+	/// This function is intended for internal use only, it is called by generated `CompColumns::columns_mut`.
+	/// 
+	/// `recycled` lists indices of components retained for overwrite.
+	#[inline]
+	pub fn component< T, E > ( data: &'w mut Vec< Component< T, E > >, recycled: &'w [usize] ) -> Self
+	where
+		T: Send + Sync + 'static,
+		E: Send + Sync + 'static,
+	{
+		Self {
+			key: AccessKey::component::< T >(),
+			data,
+			recycled,
+		}
+	}
+	
+	/// This function is intended for internal use only, it is called by generated `EcsColumns::columns_mut`.
+	#[inline]
+	pub fn resource< R: Send + Sync + 'static > ( data: &'w mut R ) -> Self {
+		Self {
+			key: AccessKey::resource::< R >(),
+			data,
+			recycled: &[],
+		}
+	}
+	
+	fn into_shared ( self ) -> SharedColumn< 'w > {
+		SharedColumn {
+			key: self.key,
+			data: self.data,
+			recycled: self.recycled,
+		}
+	}
+}
+
+#[derive( Debug, Clone, Copy )]
+struct SharedColumn< 'w > {
+	key: AccessKey,
+	data: &'w ( dyn Any + Send + Sync ),
+	recycled: &'w [usize],
+}
+
+/// Entities along with separately borrowed columns of the ECS, created by `EcsColumns::columns_mut`.
+#[derive( Debug )]
+pub struct Columns< 'w, E > {
+	entities: &'w [E],
+	despawned: &'w [usize],
+	tags: &'w [TagSet],
+	components: Vec< Column< 'w > >,
+	resources: Vec< Column< 'w > >,
+}
+
+impl< 'w, E > Columns< 'w, E > {
+	/// This function is intended for internal use only, it is called by generated `EcsColumns::columns_mut`.
+	#[inline]
+	pub const fn new ( entities: &'w [E], components: Vec< Column< 'w > >, resources: Vec< Column< 'w > > ) -> Self {
+		Self {
+			entities,
+			despawned: &[],
+			tags: &[],
+			components,
+			resources,
+		}
+	}
+	
+	/// This function is intended for internal use only, it is called by generated `EcsColumns::columns_mut`.
+	#[inline]
+	#[must_use]
+	pub const fn with_tags ( mut self, tags: &'w [TagSet] ) -> Self {
+		self.tags = tags;
+		self
+	}
+	
+	/// This function is intended for internal use only, it is called by generated `EcsColumns::columns_mut`.
+	#[inline]
+	#[must_use]
+	pub const fn with_despawned ( mut self, despawned: &'w [usize] ) -> Self {
+		self.despawned = despawned;
+		self
+	}
+	
+	/// Mutably borrows resource of type `R`, fe. to update it between runs of a schedule.
+	/// On success returns `Some( &mut R )` or `None` when the ECS has no such resource.
+	#[inline]
+	pub fn into_resource< R: 'static > ( self ) -> Option< &'w mut R > {
+		let key = AccessKey::resource::< R >();
+		self.resources.into_iter()
+			.find( |column| column.key == key )
+			.and_then( |column| column.data.downcast_mut::< R >() )
+	}
+}
+
+// struct - Column
+//------------------------------------------------------------------------------
+// struct - SystemData
+
+/// Columns borrowed by a system for a single run, limited to its declared `Access`.
+#[derive( Debug )]
+pub struct SystemData< 'w, E > {
+	entities: &'w [E],
+	despawned: &'w [usize],
+	tags: &'w [TagSet],
+	reads: Vec< SharedColumn< 'w > >,
+	writes: Vec< ( AccessKey, Option< Column< 'w > > ) >,
+}
+
+impl< 'w, E: 'static > SystemData< 'w, E > {
+	/// Borrows all entities of the ECS, including despawned ones, which have no components.
+	#[inline]
+	pub const fn entities ( &self ) -> &'w [E] {
+		self.entities
+	}
+	
+	/// Attempts to borrow entity with specified id.
+	/// On success returns `Some( &Entity )` or `None` when it does not exist or was despawned.
+	#[inline]
+	pub fn entity ( &self, id: EntityId< E > ) -> Option< &'w E > {
+		let idx = usize::from( id );
+		if self.despawned.binary_search( &idx ).is_ok() { None } else { self.entities.get( idx ) }
+	}
+	
+	/// Returns all tags of the entity.
+	#[inline]
+	pub fn tag_set ( &self, id: EntityId< E > ) -> TagSet {
+		self.tags.get( usize::from( id )).copied().unwrap_or_default()
+	}
+	
+	/// Returns an iterator over ids of entities matching the filter, in order of their ids, skipping despawned entities.
+	#[inline]
+	pub const fn tagged< W > ( &self, filter: &TagFilter< W > ) -> Tagged< 'w, E > {
+		Tagged::new( self.tags, self.entities.len(), filter ).with_despawned( self.despawned )
+	}
+	
+	/// Borrows components of type `T`, declared via `Access::read`.
+	/// 
+	/// Components of a type declared via `Access::write` as well are borrowed via `SystemData::write` or `SystemData::with_write`.
+	/// 
+	/// # Errors
+	/// 
+	/// EcsErr::UndeclaredAccess - when reading `T` was not declared, or writing it was
+	#[inline]
+	pub fn read< T: 'static > ( &self ) -> Result< CompRead< 'w, T, E >, EcsErr > {
+		let key = AccessKey::component::< T >();
+		self.reads.iter()
+			.find( |column| column.key == key )
+			.and_then( |column| column.data.downcast_ref::< Vec< Component< T, E > >>().map( |data| CompRead { data, recycled: column.recycled }))
+			.ok_or( EcsErr::UndeclaredAccess( CompName::of::< T >() ))
+	}
+	
+	/// Mutably borrows components of type `T`, declared via `Access::write`, for the rest of the run.
+	/// 
+	/// # Errors
+	/// 
+	/// EcsErr::BorrowConflict - when `T` was already borrowed during this run
+	/// EcsErr::UndeclaredAccess - when writing `T` was not declared
+	#[inline]
+	pub fn write< T: 'static > ( &mut self ) -> Result< CompWrite< 'w, T, E >, EcsErr > {
+		let column = self.take( AccessKey::component::< T >(), CompName::of::< T >() )?;
+		let recycled = column.recycled;
+		column.data.downcast_mut::< Vec< Component< T, E > >>()
+			.map( |data| CompWrite { data, recycled })
+			.ok_or( EcsErr::UndeclaredAccess( CompName::of::< T >() ))
+	}
+	
+	/// Mutably borrows components of type `T`, declared via `Access::write`, for the duration of a provided function or closure,
+	/// so that they can be borrowed again later in the run, fe. by each hook of a `StateMachine`. Returns the result of the function.
+	/// 
+	/// # Errors
+	/// 
+	/// Same as `SystemData::write`.
+	#[inline]
+	pub fn with_write< T: 'static, F, U > ( &mut self, fcn: F ) -> Result< U, EcsErr >
+	where
+		F: FnOnce( CompWrite< '_, T, E > ) -> U,
+	{
+		let key = AccessKey::component::< T >();
+		let column = self.take( key, CompName::of::< T >() )?;
+		let recycled = column.recycled;
+		let out = column.data.downcast_mut::< Vec< Component< T, E > >>()
+			.map( |data| fcn( CompWrite { data, recycled }))
+			.ok_or( EcsErr::UndeclaredAccess( CompName::of::< T >() ));
+		if let Some( slot ) = self.writes.iter_mut().find( |entry| entry.0 == key ) {
+			slot.1 = Some( column );
+		}
+		out
+	}
+	
+	/// Borrows resource of type `R`, declared via `Access::read_resource`.
+	/// 
+	/// A resource declared via `Access::write_resource` as well is borrowed via `SystemData::resource_mut`.
+	/// 
+	/// # Errors
+	/// 
+	/// EcsErr::UndeclaredAccess - when reading `R` was not declared, or writing it was
+	#[inline]
+	pub fn resource< R: 'static > ( &self ) -> Result< &'w R, EcsErr > {
+		let key = AccessKey::resource::< R >();
+		self.reads.iter()
+			.find( |column| column.key == key )
+			.and_then( |column| column.data.downcast_ref::< R >() )
+			.ok_or( EcsErr::UndeclaredAccess( CompName::of::< R >() ))
+	}
+	
+	/// Mutably borrows resource of type `R`, declared via `Access::write_resource`, for the rest of the run.
+	/// 
+	/// # Errors
+	/// 
+	/// EcsErr::BorrowConflict - when `R` was already borrowed during this run
+	/// EcsErr::UndeclaredAccess - when writing `R` was not declared
+	#[inline]
+	pub fn resource_mut< R: 'static > ( &mut self ) -> Result< &'w mut R, EcsErr > {
+		self.take( AccessKey::resource::< R >(), CompName::of::< R >() )?
+			.data.downcast_mut::< R >()
+			.ok_or( EcsErr::UndeclaredAccess( CompName::of::< R >() ))
+	}
+	
+	fn take ( &mut self, key: AccessKey, name: CompName ) -> Result< Column< 'w >, EcsErr > {
+		let slot = self.writes.iter_mut()
+			.find( |entry| entry.0 == key )
+			.ok_or( EcsErr::UndeclaredAccess( name ))?;
+		slot.1.take().ok_or( EcsErr::BorrowConflict( name, name ))
+	}
+}
+
+// struct - SystemData
+//------------------------------------------------------------------------------
+// struct - CompRead
+
+/// Components of type `T` borrowed by a system, created by `SystemData::read`.
+#[derive( Debug )]
+pub struct CompRead< 'w, T, E > {
+	data: &'w [Component< T, E >],
+	recycled: &'w [usize],
+}
+
+impl< 'w, T: Clone, E: Clone > CompRead< 'w, T, E > {
+	/// Attempts to borrow component with specified id.
+	/// On success returns `Some( &T )` or `None` otherwise.
+	#[inline]
+	pub fn get ( &self, id: CompId< T, E > ) -> Option< &'w T > {
+		let idx = usize::from( id );
+		if self.recycled.contains( &idx ) {
+			return None
+		}
+		self.data.get( idx ).map( Component::inner )
+	}
+	
+	/// Returns an iterator over components along with ids of their entities, skipping components retained for overwrite.
+	#[inline]
+	pub fn iter ( &self ) -> ColumnIter< 'w, T, E > {
+		ColumnIter {
+			data: self.data.iter().enumerate(),
+			recycled: self.recycled,
+		}
+	}
+}
+
+// struct - CompRead
+//------------------------------------------------------------------------------
+// struct - CompWrite
+
+/// Mutably borrowed components of type `T`, created by `SystemData::write`.
+#[derive( Debug )]
+pub struct CompWrite< 'w, T, E > {
+	data: &'w mut [Component< T, E >],
+	recycled: &'w [usize],
+}
+
+impl< 'w, T: Clone, E: Clone > CompWrite< 'w, T, E > {
+	/// Attempts to borrow component with specified id.
+	/// On success returns `Some( &T )` or `None` otherwise.
+	#[inline]
+	pub fn get ( &self, id: CompId< T, E > ) -> Option< &T > {
+		let idx = usize::from( id );
+		if self.recycled.contains( &idx ) {
+			return None
+		}
+		self.data.get( idx ).map( Component::inner )
+	}
+	
+	/// Attempts to mutably borrow component with specified id.
+	/// On success returns `Some( &mut T )` or `None` otherwise.
+	#[inline]
+	pub fn get_mut ( &mut self, id: CompId< T, E > ) -> Option< &mut T > {
+		let idx = usize::from( id );
+		if self.recycled.contains( &idx ) {
+			return None
+		}
+		self.data.get_mut( idx ).map( Component::inner_mut )
+	}
+	
+	/// Returns an iterator over components along with ids of their entities, skipping components retained for overwrite.
+	#[inline]
+	pub fn iter ( &self ) -> ColumnIter< '_, T, E > {
+		ColumnIter {
+			data: self.data.iter().enumerate(),
+			recycled: self.recycled,
+		}
+	}
+	
+	/// Returns an iterator over components along with ids of their entities, that allows modifying each value.
+	#[inline]
+	pub fn iter_mut ( &mut self ) -> ColumnIterMut< '_, T, E > {
+		ColumnIterMut {
+			data: self.data.iter_mut().enumerate(),
+			recycled: self.recycled,
+		}
+	}
+}
+
+// struct - CompWrite
+//------------------------------------------------------------------------------
+// struct - ColumnIter
+
+/// Iterator over live components of a column, created by `CompRead::iter` and `CompWrite::iter`.
+#[derive( Debug )]
+pub struct ColumnIter< 'a, T, E > {
+	data: core::iter::Enumerate< core::slice::Iter< 'a, Component< T, E > > >,
+	recycled: &'a [usize],
+}
+
+impl< 'a, T: Clone, E: Clone > Iterator for ColumnIter< 'a, T, E > {
+	type Item = ( EntityId< E >, &'a T );
+	
+	#[inline]
+	fn next( &mut self ) -> Option< Self::Item > {
+		let recycled = self.recycled;
+		self.data.by_ref()
+			.find( |entry| !recycled.contains( &entry.0 ))
+			.map( |( _, comp )| ( comp.id(), comp.inner() ))
+	}
+}
+
+/// Iterator over live components of a column, that allows modifying each value, created by `CompWrite::iter_mut`.
+#[derive( Debug )]
+pub struct ColumnIterMut< 'a, T, E > {
+	data: core::iter::Enumerate< core::slice::IterMut< 'a, Component< T, E > > >,
+	recycled: &'a [usize],
+}
+
+impl< 'a, T: Clone, E: Clone > Iterator for ColumnIterMut< 'a, T, E > {
+	type Item = ( EntityId< E >, &'a mut T );
+	
+	#[inline]
+	fn next( &mut self ) -> Option< Self::Item > {
+		let recycled = self.recycled;
+		self.data.by_ref()
+			.find( |entry| !recycled.contains( &entry.0 ))
+			.map( |( _, comp )| ( comp.id(), comp.inner_mut() ))
+	}
+}
+
+// struct - ColumnIter
+//------------------------------------------------------------------------------
+// struct - System
+
+type SystemFn< E > = Box< dyn FnMut( &mut SystemData< '_, E > ) -> Result< (), EcsErr > + Send >;
+
+/// Function run by `Schedule`, along with its `Access` and explicit ordering relative to other systems.
+pub struct System< E > {
+	name: String,
+	access: Access,
+	after: Vec< String >,
+	before: Vec< String >,
+	run: SystemFn< E >,
+}
+
+impl< E > System< E > {
+	/// Creates a system without any access, extended via `reads`, `writes` and their resource counterparts.
+	#[inline]
+	pub fn new< F > ( name: &str, run: F ) -> Self
+	where
+		F: FnMut( &mut SystemData< '_, E > ) -> Result< (), EcsErr > + Send + 'static,
+	{
+		Self {
+			name: name.to_owned(),
+			access: Access::new(),
+			after: Vec::new(),
+			before: Vec::new(),
+			run: Box::new( run ),
+		}
+	}
+	
+	/// Declares reading components of type `T`.
+	#[inline]
+	#[must_use]
+	pub fn reads< T: 'static > ( mut self ) -> Self {
+		self.access = self.access.read::< T >();
+		self
+	}
+	
+	/// Declares writing components of type `T`.
+	#[inline]
+	#[must_use]
+	pub fn writes< T: 'static > ( mut self ) -> Self {
+		self.access = self.access.write::< T >();
+		self
+	}
+	
+	/// Declares reading resource of type `R`.
+	#[inline]
+	#[must_use]
+	pub fn reads_resource< R: 'static > ( mut self ) -> Self {
+		self.access = self.access.read_resource::< R >();
+		self
+	}
+	
+	/// Declares writing resource of type `R`.
+	#[inline]
+	#[must_use]
+	pub fn writes_resource< R: 'static > ( mut self ) -> Self {
+		self.access = self.access.write_resource::< R >();
+		self
+	}
+	
+	/// Runs the system after the system named `name`.
+	#[inline]
+	#[must_use]
+	pub fn after ( mut self, name: &str ) -> Self {
+		self.after.push( name.to_owned() );
+		self
+	}
+	
+	/// Runs the system before the system named `name`.
+	#[inline]
+	#[must_use]
+	pub fn before ( mut self, name: &str ) -> Self {
+		self.before.push( name.to_owned() );
+		self
+	}
+	
+	#[inline]
+	pub fn name ( &self ) -> &str {
+		&self.name
+	}
+	
+	#[inline]
+	pub const fn access ( &self ) -> &Access {
+		&self.access
+	}
+	
+	fn call ( &mut self, data: &mut SystemData< '_, E > ) -> Result< (), ScheduleErr > {
+		( self.run )( data ).map_err( |err| ScheduleErr::System( self.name.clone(), err ))
+	}
+}
+
+#[allow( clippy::min_ident_chars )]
+impl< E > std::fmt::Debug for System< E > {
+	#[inline]
+	fn fmt( &self, f: &mut std::fmt::Formatter<'_> ) -> std::fmt::Result {
+		f.debug_struct( "System" )
+			.field( "name", &self.name )
+			.field( "access", &self.access )
+			.field( "after", &self.after )
+			.field( "before", &self.before )
+			.finish_non_exhaustive()
+	}
+}
+
+// struct - System
+//------------------------------------------------------------------------------
+// struct - Schedule
+
+/// Systems run in stages, each stage running non-conflicting systems at the same time.
+/// 
+/// Systems are ordered by `System::after` and `System::before`, and conflicting systems without explicit order
+/// run in the order they were added; such pairs are listed by `Schedule::ambiguities`.
+/// In debug builds they are also reported whenever `Schedule::run` builds the stages, that is on the first run and the first one after adding a system,
+/// by printing to stderr unless replaced via `Schedule::with_ambiguity_reporter`.
+/// 
+/// Without the `rayon` feature every stage spawns a scoped thread per system except one, run on the calling thread,
+/// so each run creates new threads; enable `rayon` to run systems on its reused thread pool instead.
+#[derive( Debug )]
+pub struct Schedule< E > {
+	systems: Vec< System< E > >,
+	stages: Option< Vec< Vec< usize > > >,
+	reporter: fn( &Ambiguity ),
+}
+
+impl< E > Schedule< E > {
+	#[inline]
+	pub const fn new () -> Self {
+		Self {
+			systems: Vec::new(),
+			stages: None,
+			reporter: Ambiguity::eprint,
+		}
+	}
+	
+	/// Sets the function called in debug builds with every ambiguity found when `Schedule::run` builds the stages, `Ambiguity::eprint` by default.
+	#[inline]
+	#[must_use]
+	pub const fn with_ambiguity_reporter ( mut self, reporter: fn( &Ambiguity ) ) -> Self {
+		self.reporter = reporter;
+		self
+	}
+	
+	/// Adds a system, to be run on the next `Schedule::run`.
+	#[inline]
+	pub fn add_system ( &mut self, system: System< E > ) -> &mut Self {
+		self.systems.push( system );
+		self.stages = None;
+		self
+	}
+	
+	/// Returns names of systems in each stage, in the order the stages are run.
+	/// 
+	/// # Errors
+	/// 
+	/// Same as `Schedule::ambiguities`.
+	#[inline]
+	pub fn stages ( &self ) -> Result< Vec< Vec< &str > >, ScheduleErr > {
+		let ( stages, _ ) = self.build()?;
+		Ok( stages.iter()
+			.map( |stage| stage.iter().filter_map( |&idx| self.systems.get( idx )).map( System::name ).collect() )
+			.collect() )
+	}
+	
+	/// Returns pairs of conflicting systems without explicit order.
+	/// 
+	/// # Errors
+	/// 
+	/// ScheduleErr::DuplicateSystem - when two systems have the same name
+	/// ScheduleErr::OrderCycle - when explicit ordering of systems forms a cycle
+	/// ScheduleErr::UnknownSystem - when a system is ordered relative to a system not present in the schedule
+	#[inline]
+	pub fn ambiguities ( &self ) -> Result< Vec< Ambiguity >, ScheduleErr > {
+		self.build().map( |( _, ambiguities )| ambiguities )
+	}
+	
+	/// Runs all systems once, stage by stage, systems of one stage running on separate threads,
+	/// spawned for this run only unless the `rayon` feature is enabled.
+	/// When the stages are built, on the first run or the first one after adding a system, ambiguities are passed to the reporter in debug builds.
+	/// 
+	/// # Errors
+	/// 
+	/// ScheduleErr::MissingColumn - when a system accesses a component type or resource not present in the ECS
+	/// ScheduleErr::System - when a system returns an error, the remaining stages are not run
+	/// 
+	/// Same as `Schedule::ambiguities`.
+	/// 
+	/// # Panics
+	/// 
+	/// In debug builds, when the ECS fails `EcsValidate::validate` after the run, see `EcsValidate::debug_validate`.
+	#[inline]
+	pub fn run< W: EcsColumns< E > > ( &mut self, ecs: &mut W ) -> Result< (), ScheduleErr >
+	where
+		E: Sync + 'static,
+	{
+		let stages = if let Some( stages ) = self.stages.take() {
+			stages
+		} else {
+			let ( stages, ambiguities ) = self.build()?;
+			if cfg!( debug_assertions ) {
+				ambiguities.iter().for_each( self.reporter );
+			}
+			stages
+		};
+		
+		let out = stages.iter().try_for_each( |stage| {
+			let jobs = distribute( &mut self.systems, stage, ecs.columns_mut() )?;
+			run_jobs( jobs )
+		});
+		self.stages = Some( stages );
+		ecs.debug_validate();
+		out
+	}
+	
+	fn build ( &self ) -> Result< ( Vec< Vec< usize > >, Vec< Ambiguity > ), ScheduleErr > {
+		let count = self.systems.len();
+		let mut order = Order::new( count );
+		for ( idx, system ) in self.systems.iter().enumerate() {
+			if self.systems.iter().take( idx ).any( |other| other.name == system.name ) {
+				return Err( ScheduleErr::DuplicateSystem( system.name.clone() ))
+			}
+			for name in &system.after {
+				order.link( self.position( system, name )?, idx );
+			}
+			for name in &system.before {
+				order.link( idx, self.position( system, name )? );
+			}
+		}
+		
+		let cycle: Vec< String > = self.systems.iter().enumerate()
+			.filter( |&( idx, _ )| order.reaches( idx, idx ))
+			.map( |( _, system )| system.name.clone() )
+			.collect();
+		if !cycle.is_empty() {
+			return Err( ScheduleErr::OrderCycle( cycle ))
+		}
+		
+		let explicit = order.clone();
+		let mut ambiguities = Vec::new();
+		for ( idx_b, second ) in self.systems.iter().enumerate() {
+			for ( idx_a, first ) in self.systems.iter().enumerate().take( idx_b ) {
+				let Some( conflict ) = first.access.conflict( &second.access ) else {
+					continue
+				};
+				if !explicit.reaches( idx_a, idx_b ) && !explicit.reaches( idx_b, idx_a ) {
+					ambiguities.push( Ambiguity {
+						first: first.name.clone(),
+						second: second.name.clone(),
+						conflict,
+					});
+				}
+				if !order.reaches( idx_a, idx_b ) && !order.reaches( idx_b, idx_a ) {
+					order.link( idx_a, idx_b );
+				}
+			}
+		}
+		
+		Ok(( order.stages(), ambiguities ))
+	}
+	
+	fn position ( &self, system: &System< E >, name: &str ) -> Result< usize, ScheduleErr > {
+		self.systems.iter()
+			.position( |other| other.name == name )
+			.ok_or_else( || ScheduleErr::UnknownSystem( system.name.clone(), name.to_owned() ))
+	}
+}
+
+impl< E > Default for Schedule< E > {
+	#[inline]
+	fn default () -> Self {
+		Self::new()
+	}
+}
+
+type Job< 's, 'w, E > = ( &'s mut System< E >, SystemData< 'w, E > );
+
+/// Hands each system of the stage the columns declared in its access.
+fn distribute< 's, 'w, E > ( systems: &'s mut [System< E >], stage: &[usize], columns: Columns< 'w, E > ) -> Result< Vec< Job< 's, 'w, E > >, ScheduleErr > {
+	let Columns { entities, despawned, tags, components, resources } = columns;
+	let mut free: Vec< Option< Column< 'w > > > = components.into_iter().chain( resources ).map( Some ).collect();
+	
+	let members: Vec< &mut System< E > > = systems.iter_mut().enumerate()
+		.filter( |&( idx, _ )| stage.contains( &idx ))
+		.map( |( _, system )| system )
+		.collect();
+	
+	// conflicting systems are never in the same stage, so each written column is taken by one system only
+	let mut taken = Vec::new();
+	for system in &members {
+		let mut list = Vec::new();
+		for &( key, name ) in &system.access.writes {
+			let column = free.iter_mut()
+				.find( |slot| slot.as_ref().is_some_and( |column| column.key == key ))
+				.and_then( Option::take )
+				.ok_or_else( || ScheduleErr::MissingColumn( system.name.clone(), name ))?;
+			list.push(( key, Some( column )));
+		}
+		taken.push( list );
+	}
+	
+	let shared: Vec< SharedColumn< 'w > > = free.into_iter().flatten().map( Column::into_shared ).collect();
+	members.into_iter().zip( taken ).map( |( system, writes )| {
+		let reads = system.access.reads.iter()
+			.map( |&( key, name )| shared.iter().find( |column| column.key == key ).copied().ok_or_else( || ScheduleErr::MissingColumn( system.name.clone(), name )))
+			.collect::< Result< Vec< _ >, _ >>()?;
+		Ok(( system, SystemData { entities, despawned, tags, reads, writes }))
+	}).collect()
+}
+
+/// Runs systems of one stage, each but the last on a separate thread of a scope, spawned for this stage only.
+#[cfg( not( feature = "rayon" ))]
+fn run_jobs< E: Sync > ( mut jobs: Vec< Job< '_, '_, E > > ) -> Result< (), ScheduleErr > {
+	let Some(( last, mut last_data )) = jobs.pop() else {
+		return Ok(())
+	};
+	
+	std::thread::scope( |scope| {
+		let handles: Vec< _ > = jobs.into_iter()
+			.map( |( system, mut data )| scope.spawn( move || system.call( &mut data )))
+			.collect();
+		let out = last.call( &mut last_data );
+		
+		handles.into_iter()
+			.map( |handle| handle.join().unwrap_or_else( |payload| std::panic::resume_unwind( payload )))
+			.fold( out, Result::and )
+	})
+}
+
+/// Runs systems of one stage, each as a separate task of the rayon thread pool.
+#[cfg( feature = "rayon" )]
+fn run_jobs< E: Sync > ( jobs: Vec< Job< '_, '_, E > > ) -> Result< (), ScheduleErr > {
+	let mut results: Vec< Result< (), ScheduleErr > > = jobs.iter().map( |_| Ok(()) ).collect();
+	rayon::scope( |scope| {
+		for ( ( system, mut data ), result ) in jobs.into_iter().zip( results.iter_mut() ) {
+			scope.spawn( move |_| *result = system.call( &mut data ));
+		}
+	});
+	results.into_iter().collect()
+}
+
+// struct - Schedule
+//------------------------------------------------------------------------------
+// struct - Order
+
+/// Transitive closure of the order between systems.
+#[derive( Debug, Clone )]
+struct Order {
+	reach: Vec< Vec< bool > >,
+}
+
+impl Order {
+	fn new ( count: usize ) -> Self {
+		Self {
+			reach: vec![ vec![ false; count ]; count ],
+		}
+	}
+	
+	fn reaches ( &self, from: usize, to: usize ) -> bool {
+		self.reach.get( from ).and_then( |row| row.get( to )).copied().unwrap_or( false )
+	}
+	
+	/// Orders `from` before `to`, along with everything before `from` and after `to`.
+	fn link ( &mut self, from: usize, to: usize ) {
+		let count = self.reach.len();
+		let sources: Vec< usize > = ( 0 .. count ).filter( |&idx| idx == from || self.reaches( idx, from )).collect();
+		let targets: Vec< usize > = ( 0 .. count ).filter( |&idx| idx == to || self.reaches( to, idx )).collect();
+		
+		for source in sources {
+			if let Some( row ) = self.reach.get_mut( source ) {
+				for &target in &targets {
+					if let Some( cell ) = row.get_mut( target ) {
+						*cell = true;
+					}
+				}
+			}
+		}
+	}
+	
+	/// Groups systems by the length of the longest chain of systems ordered before them.
+	fn stages ( &self ) -> Vec< Vec< usize > > {
+		let count = self.reach.len();
+		let ancestors = |idx: usize| ( 0 .. count ).filter( move |&other| self.reaches( other, idx ));
+		
+		let mut sorted: Vec< usize > = ( 0 .. count ).collect();
+		sorted.sort_by_key( |&idx| ancestors( idx ).count() );
+		
+		let mut levels = vec![ 0_usize; count ];
+		for idx in sorted {
+			let level = ancestors( idx ).filter_map( |other| levels.get( other )).map( |level| level + 1 ).max().unwrap_or( 0 );
+			if let Some( slot ) = levels.get_mut( idx ) {
+				*slot = level;
+			}
+		}
+		
+		let mut stages: Vec< Vec< usize > > = Vec::new();
+		for ( idx, level ) in levels.into_iter().enumerate() {
+			if stages.len() <= level {
+				stages.resize_with( level + 1, Vec::new );
+			}
+			if let Some( stage ) = stages.get_mut( level ) {
+				stage.push( idx );
+			}
+		}
+		stages
+	}
+}
+
+// struct - Order
+//------------------------------------------------------------------------------
+// struct - Ambiguity
+
+/// Pair of conflicting systems without explicit order, run in the order they were added.
+#[derive( Debug, Clone, PartialEq )]
+pub struct Ambiguity {
+	pub first: String,
+	pub second: String,
+	pub conflict: CompName,
+}
+
+impl Ambiguity {
+	/// Prints the ambiguity to stderr, the default reporter of `Schedule::run`.
+	#[inline]
+	pub fn eprint ( &self ) {
+		eprintln!( "min_ecs: {self}" );
+	}
+}
+
+#[allow( clippy::min_ident_chars )]
+impl std::fmt::Display for Ambiguity {
+	#[inline]
+	fn fmt( &self, f: &mut std::fmt::Formatter<'_> ) -> std::fmt::Result {
+		format!( "systems `{}` and `{}` both access {} without explicit order, running in the order they were added", self.first, self.second, self.conflict ).fmt(f)
+	}
+}
+
+// struct - Ambiguity
+//------------------------------------------------------------------------------
+// enum - ScheduleErr
+
+#[derive( Debug, Clone, PartialEq )]
+pub enum ScheduleErr {
+	DuplicateSystem( String ), // two systems have the same name
+	MissingColumn( String, CompName ), // system accesses component type or resource not present in the ECS
+	MissingResource( CompName ), // resource required by the runner is not present in the ECS
+	OrderCycle( Vec< String > ), // explicit ordering of specified systems forms a cycle
+	System( String, EcsErr ), // system returned an error
+	UnknownSystem( String, String ), // system is ordered relative to a system not present in the schedule
+}
+
+#[allow( clippy::min_ident_chars )]
+impl std::fmt::Display for ScheduleErr {
+	#[inline]
+	fn fmt( &self, f: &mut std::fmt::Formatter<'_> ) -> std::fmt::Result {
+		match *self {
+			Self::DuplicateSystem( ref name ) => format!( "system `{name}` was added more than once" ),
+			Self::MissingColumn( ref name, comp ) => format!( "system `{name}` accesses {comp}, which is not present in the ECS" ),
+			Self::MissingResource( name ) => format!( "{name} is required, but not present in the ECS" ),
+			Self::OrderCycle( ref names ) => format!( "ordering of systems: {} forms a cycle", names.join( ", " )),
+			Self::System( ref name, ref err ) => format!( "system `{name}` failed: {err}" ),
+			Self::UnknownSystem( ref name, ref other ) => format!( "system `{name}` is ordered relative to unknown system `{other}`" ),
+		}.fmt(f)
+	}
+}
+impl std::error::Error for ScheduleErr {}
+
+// enum - ScheduleErr
+//------------------------------------------------------------------------------
+// --Tests
+
+#[cfg( test )]
+#[allow( dead_code )]// not every generated method is used by tests
+mod tests {
+	use crate::*;
+	use minecs_macro::minecs;
+	use std::sync::{ Arc, Condvar, Mutex, PoisonError };
+	use std::sync::atomic::{ AtomicUsize, Ordering };
+	use std::time::{ Duration, Instant };
+	
+	minecs!(
+		#[parallel]
+		ecs TestEcs< TestArray, TestEntity > {
+			types [ f64, f32, u32, u64 ]
+		}
+	);
+	
+	/// Barrier with a timeout, recording which systems were running at the same time.
+	#[derive( Default )]
+	struct Probe {
+		state: Mutex< ProbeState >,
+		arrived: Condvar,
+	}
+	
+	#[derive( Default )]
+	struct ProbeState {
+		running: Vec< &'static str >,
+		overlaps: Vec< ( &'static str, &'static str ) >,
+		timeouts: Vec< &'static str >,
+		waiting: usize,
+		generation: usize,
+	}
+	
+	impl Probe {
+		/// Waits until `peers` systems, including this one, have arrived, or the timeout passes.
+		fn meet ( &self, name: &'static str, peers: usize ) {
+			let mut guard = self.state.lock().unwrap_or_else( PoisonError::into_inner );
+			let state = &mut *guard;
+			state.overlaps.extend( state.running.iter().map( |&other| if other < name { ( other, name ) } else { ( name, other ) } ));
+			state.running.push( name );
+			state.waiting += 1;
+			
+			let generation = state.generation;
+			if state.waiting == peers {
+				state.waiting = 0;
+				state.generation += 1;
+				self.arrived.notify_all();
+			}
+			
+			let deadline = Instant::now() + if can_overlap( peers ) { Duration::from_secs( 5 ) } else { Duration::ZERO };
+			while guard.generation == generation {
+				let Some( left ) = deadline.checked_duration_since( Instant::now() ) else {
+					guard.waiting -= 1;
+					guard.timeouts.push( name );
+					break
+				};
+				guard = self.arrived.wait_timeout( guard, left ).unwrap_or_else( PoisonError::into_inner ).0;
+			}
+			guard.running.retain( |&running| running != name );
+		}
+		
+		/// Returns pairs of systems running at the same time, and systems which did not meet all their peers.
+		fn report ( &self ) -> ( Vec< ( &'static str, &'static str ) >, Vec< &'static str > ) {
+			let state = self.state.lock().unwrap_or_else( PoisonError::into_inner );
+			let mut overlaps = state.overlaps.clone();
+			overlaps.sort_unstable();
+			( overlaps, state.timeouts.clone() )
+		}
+	}
+	
+	#[cfg( not( feature = "rayon" ))]
+	const fn can_overlap ( _peers: usize ) -> bool {
+		true
+	}
+	
+	#[cfg( feature = "rayon" )]
+	fn can_overlap ( peers: usize ) -> bool {
+		rayon::current_num_threads() >= peers
+	}
+	
+	/// Adds system `name` meeting `peers` systems of its stage, with access declared by `access`.
+	fn add ( schedule: &mut Schedule< TestEntity >, probe: &Arc< Probe >, name: &'static str, peers: usize, access: fn( System< TestEntity > ) -> System< TestEntity > ) {
+		let shared = Arc::clone( probe );
+		schedule.add_system( access( System::new( name, move |_| {
+			shared.meet( name, peers );
+			Ok(())
+		})));
+	}
+	
+	fn run ( schedule: &mut Schedule< TestEntity >, probe: &Probe, expected: &[( &str, &str )] ) {
+		schedule.run( &mut TestEcs::new() ).unwrap();
+		let ( overlaps, timeouts ) = probe.report();
+		if can_overlap( 3 ) {
+			assert_eq!( overlaps, expected );
+			assert_eq!( timeouts, Vec::< &str >::new() );
+		} else {
+			assert!( overlaps.iter().all( |pair| expected.contains( pair )));
+		}
+	}
+	
+	#[test]
+	fn independent_systems_share_a_stage() {
+		let probe = Arc::default();
+		let mut schedule = Schedule::new();
+		add( &mut schedule, &probe, "a", 3, System::writes::< f64 > );
+		add( &mut schedule, &probe, "b", 3, System::writes::< f32 > );
+		add( &mut schedule, &probe, "c", 3, System::reads::< u32 > );
+		
+		assert_eq!( schedule.stages(), Ok( vec![ vec![ "a", "b", "c" ]] ));
+		run( &mut schedule, &probe, &[( "a", "b" ), ( "a", "c" ), ( "b", "c" )] );
+	}
+	
+	#[test]
+	fn conflicting_systems_form_a_chain() {
+		let probe = Arc::default();
+		let mut schedule = Schedule::new();
+		add( &mut schedule, &probe, "a", 1, System::writes::< f64 > );
+		add( &mut schedule, &probe, "b", 1, System::reads::< f64 > );
+		add( &mut schedule, &probe, "c", 1, System::writes::< f64 > );
+		
+		assert_eq!( schedule.stages(), Ok( vec![ vec![ "a" ], vec![ "b" ], vec![ "c" ]] ));
+		assert_eq!( schedule.ambiguities().map( |list| list.len() ), Ok( 3 ));
+		run( &mut schedule, &probe, &[] );
+	}
+	
+	#[test]
+	fn diamond_overlaps_in_the_middle() {
+		let probe = Arc::default();
+		let mut schedule = Schedule::new();
+		add( &mut schedule, &probe, "a", 1, System::writes::< f64 > );
+		add( &mut schedule, &probe, "b", 2, |system| system.reads::< f64 >().writes::< f32 >() );
+		add( &mut schedule, &probe, "c", 2, |system| system.reads::< f64 >().writes::< u32 >() );
+		add( &mut schedule, &probe, "d", 1, |system| system.reads::< f32 >().reads::< u32 >() );
+		
+		assert_eq!( schedule.stages(), Ok( vec![ vec![ "a" ], vec![ "b", "c" ], vec![ "d" ]] ));
+		run( &mut schedule, &probe, &[( "b", "c" )] );
+	}
+	
+	#[test]
+	fn explicit_order_separates_independent_systems() {
+		let probe = Arc::default();
+		let mut schedule = Schedule::new();
+		add( &mut schedule, &probe, "a", 1, |system| system.writes::< f64 >().after( "c" ));
+		add( &mut schedule, &probe, "b", 2, System::writes::< f32 > );
+		add( &mut schedule, &probe, "c", 2, System::writes::< u32 > );
+		
+		assert_eq!( schedule.stages(), Ok( vec![ vec![ "b", "c" ], vec![ "a" ]] ));
+		assert_eq!( schedule.ambiguities(), Ok( vec![] ));
+		run( &mut schedule, &probe, &[( "b", "c" )] );
+	}
+	
+	#[test]
+	fn write_subsumes_read() {
+		let access = Access::new().read::< f64 >().write::< f64 >();
+		assert_eq!( access, Access::new().write::< f64 >().read::< f64 >() );
+		assert_eq!( access, Access::new().write::< f64 >() );
+		
+		let mut ecs = TestEcs::new();
+		let id = ecs.new_entity();
+		_ = ecs.insert( id, 1.0_f64 );
+		
+		let mut schedule = Schedule::new();
+		schedule.add_system( System::new( "double", |data| {
+			data.write::< f64 >()?.iter_mut().for_each( |( _, val )| *val *= 2.0 );
+			Ok(())
+		}).reads::< f64 >().writes::< f64 >() );
+		schedule.run( &mut ecs ).unwrap();
+		assert_eq!( ecs.iter::< f64 >().copied().collect::< Vec<_> >(), [ 2.0 ] );
+	}
+	
+	#[test]
+	#[cfg( debug_assertions )]
+	#[should_panic( expected = "ECS integrity check failed" )]
+	fn corrupted_ecs_is_reported_after_run() {
+		let mut ecs = TestEcs::new();
+		let [ first, second ] = [ ecs.new_entity(), ecs.new_entity() ];
+		let cid = ecs.insert( first, 1.0_f64 ).unwrap();
+		_ = EntityFn::< f64 >::remove( ecs.entity_mut( first ).unwrap() );
+		_ = ecs.entity_mut( second ).unwrap().set( cid );
+		
+		let mut schedule = Schedule::new();
+		schedule.add_system( System::new( "idle", |_| Ok(()) ));
+		_ = schedule.run( &mut ecs );
+	}
+	
+	static REPORTED: AtomicUsize = AtomicUsize::new( 0 );
+	
+	fn count_ambiguity ( _ambiguity: &Ambiguity ) {
+		REPORTED.fetch_add( 1, Ordering::Relaxed );
+	}
+	
+	#[test]
+	#[cfg( debug_assertions )]
+	fn ambiguities_are_reported_when_stages_are_built() {
+		let mut ecs = TestEcs::new();
+		let mut schedule = Schedule::new().with_ambiguity_reporter( count_ambiguity );
+		schedule.add_system( System::new( "a", |_| Ok(()) ).writes::< f64 >() );
+		schedule.add_system( System::new( "b", |_| Ok(()) ).reads::< f64 >() );
+		
+		// listing ambiguities does not report them
+		assert_eq!( schedule.ambiguities().map( |ambiguities| ambiguities.len() ), Ok( 1 ));
+		assert_eq!( REPORTED.load( Ordering::Relaxed ), 0 );
+		
+		schedule.run( &mut ecs ).unwrap();
+		schedule.run( &mut ecs ).unwrap();
+		assert_eq!( REPORTED.load( Ordering::Relaxed ), 1 );
+		
+		// adding a system rebuilds the stages, reporting `a` and `b` again along with `a` and `c`
+		schedule.add_system( System::new( "c", |_| Ok(()) ).writes::< f64 >().after( "b" ));
+		schedule.run( &mut ecs ).unwrap();
+		assert_eq!( REPORTED.load( Ordering::Relaxed ), 3 );
+	}
+}
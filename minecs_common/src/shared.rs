@@ -0,0 +1,107 @@
+use std::sync::{ Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard, TryLockError };
+
+//------------------------------------------------------------------------------
+// struct - SharedEcs
+
+/// ECS shared between threads, fe. a loader thread inserting components while the main thread reads them.
+/// 
+/// Cloning creates another handle to the same ECS. Any number of threads may read the ECS at once, while writing is exclusive.
+/// An ECS generated by `minecs!` is `Send` and `Sync` when all its components, resources and relation data are, which is required to share it.
+/// 
+/// A lock poisoned by a thread that panicked while writing is recovered, `EcsValidate::validate` can be used to check the ECS afterwards.
+#[derive( Debug, Default )]
+pub struct SharedEcs< W > {
+	inner: Arc< RwLock< W > >,
+}
+
+impl< W > SharedEcs< W > {
+	#[inline]
+	pub fn new ( ecs: W ) -> Self {
+		Self {
+			inner: Arc::new( RwLock::new( ecs )),
+		}
+	}
+	
+	/// Locks the ECS for reading, blocking the current thread until no other thread writes it.
+	#[inline]
+	pub fn read ( &self ) -> RwLockReadGuard< '_, W > {
+		self.inner.read().unwrap_or_else( PoisonError::into_inner )
+	}
+	
+	/// Locks the ECS for writing, blocking the current thread until no other thread reads or writes it.
+	#[inline]
+	pub fn write ( &self ) -> RwLockWriteGuard< '_, W > {
+		self.inner.write().unwrap_or_else( PoisonError::into_inner )
+	}
+	
+	/// Attempts to lock the ECS for reading without blocking.
+	/// Returns `None` when another thread writes it.
+	#[inline]
+	pub fn try_read ( &self ) -> Option< RwLockReadGuard< '_, W >> {
+		match self.inner.try_read() {
+			Ok( guard ) => Some( guard ),
+			Err( TryLockError::Poisoned( err )) => Some( err.into_inner() ),
+			Err( TryLockError::WouldBlock ) => None,
+		}
+	}
+	
+	/// Attempts to lock the ECS for writing without blocking.
+	/// Returns `None` when another thread reads or writes it.
+	#[inline]
+	pub fn try_write ( &self ) -> Option< RwLockWriteGuard< '_, W >> {
+		match self.inner.try_write() {
+			Ok( guard ) => Some( guard ),
+			Err( TryLockError::Poisoned( err )) => Some( err.into_inner() ),
+			Err( TryLockError::WouldBlock ) => None,
+		}
+	}
+	
+	/// Calls a provided function or closure with the ECS locked for reading, returns its result.
+	#[inline]
+	pub fn with< F, U > ( &self, fcn: F ) -> U
+	where
+		F: FnOnce( &W ) -> U,
+	{
+		fcn( &self.read() )
+	}
+	
+	/// Calls a provided function or closure with the ECS locked for writing, returns its result.
+	#[inline]
+	pub fn with_mut< F, U > ( &self, fcn: F ) -> U
+	where
+		F: FnOnce( &mut W ) -> U,
+	{
+		fcn( &mut self.write() )
+	}
+	
+	/// Returns the ECS if this is the last handle to it, or `Err( self )` otherwise.
+	/// 
+	/// # Errors
+	/// 
+	/// `Self` - when other handles to the ECS exist
+	#[inline]
+	pub fn into_inner ( self ) -> Result< W, Self > {
+		Arc::try_unwrap( self.inner )
+			.map( |lock| lock.into_inner().unwrap_or_else( PoisonError::into_inner ))
+			.map_err( |inner| Self { inner })
+	}
+}
+
+impl< W > Clone for SharedEcs< W > {
+	#[inline]
+	fn clone ( &self ) -> Self {
+		Self {
+			inner: Arc::clone( &self.inner ),
+		}
+	}
+}
+
+impl< W > From< W > for SharedEcs< W > {
+	#[inline]
+	fn from( value: W ) -> Self {
+		Self::new( value )
+	}
+}
+
+// struct - SharedEcs
+//------------------------------------------------------------------------------
@@ -0,0 +1,325 @@
+use std::collections::HashMap;
+
+use crate::{ EcsErr, EntityId, Events, System, SystemData };
+
+//------------------------------------------------------------------------------
+// struct - State
+
+/// Component holding the current state of an entity's state machine.
+/// 
+/// The state is changed by requesting it via `NextState`, the system created by `StateMachine::system` applies the request.
+#[cfg_attr( feature = "serde", derive( serde::Serialize, serde::Deserialize ) )]
+#[derive( Debug, Clone, PartialEq )]
+pub struct State< S > {
+	current: S,
+}
+
+impl< S > State< S > {
+	#[inline]
+	pub const fn new ( state: S ) -> Self {
+		Self {
+			current: state,
+		}
+	}
+	
+	#[inline]
+	pub const fn get ( &self ) -> &S {
+		&self.current
+	}
+}
+
+// struct - State
+//------------------------------------------------------------------------------
+// struct - NextState
+
+/// Component requesting a transition of the entity's `State`, applied and cleared by the system created by `StateMachine::system`.
+#[cfg_attr( feature = "serde", derive( serde::Serialize, serde::Deserialize ) )]
+#[derive( Debug, Clone, PartialEq )]
+pub struct NextState< S > {
+	next: Option< S >,
+}
+
+impl< S > NextState< S > {
+	#[inline]
+	pub const fn new () -> Self {
+		Self {
+			next: None,
+		}
+	}
+	
+	/// Requests a transition to `state`, replacing a pending request.
+	#[inline]
+	pub fn set ( &mut self, state: S ) {
+		self.next = Some( state );
+	}
+	
+	/// Returns the pending request.
+	#[inline]
+	pub const fn get ( &self ) -> Option< &S > {
+		self.next.as_ref()
+	}
+	
+	/// Cancels the pending request.
+	#[inline]
+	pub fn clear ( &mut self ) {
+		self.next = None;
+	}
+}
+
+impl< S > Default for NextState< S > {
+	#[inline]
+	fn default () -> Self {
+		Self::new()
+	}
+}
+
+// struct - NextState
+//------------------------------------------------------------------------------
+// struct - StateTransition
+
+/// Event sent by the system created by `StateMachine::system` for each applied transition.
+#[derive( Debug, Clone, PartialEq )]
+pub struct StateTransition< S, E > {
+	pub entity: EntityId< E >,
+	pub from: S,
+	pub to: S,
+}
+
+// struct - StateTransition
+//------------------------------------------------------------------------------
+// struct - StateMachine
+
+type Hook< S, E > = ( S, Box< dyn FnMut( EntityId< E >, &mut SystemData< '_, E > ) -> Result< (), EcsErr > + Send > );
+
+/// Hooks run on entering and exiting states of type `S`, turned into a system by `StateMachine::system`.
+pub struct StateMachine< S, E > {
+	enter: Vec< Hook< S, E > >,
+	exit: Vec< Hook< S, E > >,
+}
+
+impl< S, E > StateMachine< S, E >
+where
+	S: Clone + PartialEq + Send + Sync + 'static,
+	E: Clone + Send + Sync + 'static,
+{
+	#[inline]
+	pub const fn new () -> Self {
+		Self {
+			enter: Vec::new(),
+			exit: Vec::new(),
+		}
+	}
+	
+	/// Adds a hook run with the entity entering `state`.
+	#[inline]
+	#[must_use]
+	pub fn on_enter< F > ( mut self, state: S, hook: F ) -> Self
+	where
+		F: FnMut( EntityId< E >, &mut SystemData< '_, E > ) -> Result< (), EcsErr > + Send + 'static,
+	{
+		self.enter.push(( state, Box::new( hook )));
+		self
+	}
+	
+	/// Adds a hook run with the entity exiting `state`.
+	#[inline]
+	#[must_use]
+	pub fn on_exit< F > ( mut self, state: S, hook: F ) -> Self
+	where
+		F: FnMut( EntityId< E >, &mut SystemData< '_, E > ) -> Result< (), EcsErr > + Send + 'static,
+	{
+		self.exit.push(( state, Box::new( hook )));
+		self
+	}
+	
+	/// Creates a system applying `NextState` requests of entities having a `State`, requests of other entities are kept.
+	/// For each transition it runs the exit hooks of the previous state, then the enter hooks of the new one,
+	/// and sends `StateTransition` to the `Events< StateTransition< S, E > >` resource. Requests of the current state are dropped.
+	/// 
+	/// Hooks run after all requests were applied; components and resources they access have to be declared on the returned system,
+	/// hooks writing components should borrow them via `SystemData::with_write`, so that other hooks of the same run can borrow them again.
+	#[inline]
+	pub fn system ( mut self, name: &str ) -> System< E > {
+		System::new( name, move |data| {
+			let mut states = data.write::< State< S > >()?;
+			let mut requests = data.write::< NextState< S > >()?;
+			let mut current: HashMap< usize, &mut State< S > > = states.iter_mut()
+				.map( |( entity, state )| ( usize::from( entity ), state ))
+				.collect();
+			
+			let mut transitions = Vec::new();
+			for ( entity, request ) in requests.iter_mut() {
+				let Some( state ) = current.get_mut( &usize::from( entity )) else {
+					continue
+				};
+				let Some( to ) = request.next.take() else {
+					continue
+				};
+				if to != state.current {
+					let from = std::mem::replace( &mut state.current, to.clone() );
+					transitions.push( StateTransition { entity, from, to });
+				}
+			}
+			
+			for transition in &transitions {
+				Self::run_hooks( &mut self.exit, &transition.from, transition.entity, data )?;
+				Self::run_hooks( &mut self.enter, &transition.to, transition.entity, data )?;
+			}
+			data.resource_mut::< Events< StateTransition< S, E > > >()?.extend( transitions );
+			Ok(())
+		})
+			.writes::< State< S > >()
+			.writes::< NextState< S > >()
+			.writes_resource::< Events< StateTransition< S, E > > >()
+	}
+	
+	fn run_hooks ( hooks: &mut [Hook< S, E >], state: &S, entity: EntityId< E >, data: &mut SystemData< '_, E > ) -> Result< (), EcsErr > {
+		hooks.iter_mut()
+			.filter( |hook| hook.0 == *state )
+			.try_for_each( |hook| ( hook.1 )( entity, data ))
+	}
+}
+
+impl< S, E > Default for StateMachine< S, E >
+where
+	S: Clone + PartialEq + Send + Sync + 'static,
+	E: Clone + Send + Sync + 'static,
+{
+	#[inline]
+	fn default () -> Self {
+		Self::new()
+	}
+}
+
+impl< S: std::fmt::Debug, E > std::fmt::Debug for StateMachine< S, E > {
+	#[inline]
+	fn fmt( &self, f: &mut std::fmt::Formatter<'_> ) -> std::fmt::Result {
+		f.debug_struct( "StateMachine" )
+			.field( "enter", &self.enter.iter().map( |hook| &hook.0 ).collect::< Vec<_> >() )
+			.field( "exit", &self.exit.iter().map( |hook| &hook.0 ).collect::< Vec<_> >() )
+			.finish()
+	}
+}
+
+// struct - StateMachine
+//------------------------------------------------------------------------------
+// --Tests
+
+#[cfg( test )]
+#[allow( dead_code )]// not every generated method is used by tests
+mod tests {
+	use crate::*;
+	use minecs_macro::ecs;
+	use std::sync::{ Arc, Mutex, PoisonError };
+	
+	#[derive( Debug, Clone, Copy, PartialEq )]
+	pub enum Door { Closed, Open, Locked }
+	
+	#[ecs( TestArray )]
+	#[parallel]
+	pub struct World {
+		#[entities]
+		entities: Vec< TestEntity >,
+		#[component]
+		door: State< Door >,
+		#[component]
+		door_next: NextState< Door >,
+		#[resource]
+		transitions: Events< StateTransition< Door, TestEntity > >,
+	}
+	
+	type Log = Arc< Mutex< Vec< String > > >;
+	
+	fn logging ( log: &Log, text: &'static str ) -> impl FnMut( EntityId< TestEntity >, &mut SystemData< '_, TestEntity > ) -> Result< (), EcsErr > + Send + 'static {
+		let shared = Arc::clone( log );
+		move |entity, _| {
+			shared.lock().unwrap_or_else( PoisonError::into_inner ).push( format!( "{text} {}", usize::from( entity )));
+			Ok(())
+		}
+	}
+	
+	fn request ( world: &mut World, entity: EntityId< TestEntity >, state: Door ) {
+		if let Some( next ) = world.door_next_mut( entity ) {
+			next.set( state );
+		}
+	}
+	
+	#[test]
+	fn transitions_run_exit_then_enter_hooks() {
+		let log = Log::default();
+		let mut world = World::new();
+		let door = world.new_entity();
+		world.insert_door( door, State::new( Door::Closed ));
+		world.insert_door_next( door, NextState::new() );
+		
+		let machine = StateMachine::new()
+			.on_exit( Door::Closed, logging( &log, "exit closed" ))
+			.on_enter( Door::Open, logging( &log, "enter open" ))
+			.on_enter( Door::Locked, logging( &log, "enter locked" ));
+		let mut schedule = Schedule::new();
+		schedule.add_system( machine.system( "doors" ));
+		
+		request( &mut world, door, Door::Open );
+		schedule.run( &mut world ).unwrap();
+		assert_eq!( world.door( door ).map( State::get ), Some( &Door::Open ));
+		assert_eq!( world.door_next( door ).and_then( NextState::get ), None );
+		assert_eq!( *log.lock().unwrap_or_else( PoisonError::into_inner ), [ "exit closed 0", "enter open 0" ] );
+		assert_eq!( world.transitions_mut().drain().collect::< Vec<_> >(), [ StateTransition { entity: door, from: Door::Closed, to: Door::Open } ] );
+		
+		// nothing happens without a request
+		schedule.run( &mut world ).unwrap();
+		assert!( world.transitions().is_empty() );
+		assert_eq!( log.lock().unwrap_or_else( PoisonError::into_inner ).len(), 2 );
+	}
+	
+	#[test]
+	fn requests_of_the_current_state_are_dropped() {
+		let log = Log::default();
+		let mut world = World::new();
+		let door = world.new_entity();
+		world.insert_door( door, State::new( Door::Open ));
+		world.insert_door_next( door, NextState::new() );
+		
+		let mut schedule = Schedule::new();
+		schedule.add_system( StateMachine::new().on_enter( Door::Open, logging( &log, "enter open" )).system( "doors" ));
+		
+		request( &mut world, door, Door::Open );
+		schedule.run( &mut world ).unwrap();
+		assert_eq!( world.door_next( door ).and_then( NextState::get ), None );
+		assert!( world.transitions().is_empty() );
+		assert!( log.lock().unwrap_or_else( PoisonError::into_inner ).is_empty() );
+	}
+	
+	#[test]
+	fn requests_without_state_are_kept() {
+		let mut world = World::new();
+		let ghost = world.new_entity();
+		world.insert_door_next( ghost, NextState::new() );
+		
+		let mut schedule = Schedule::new();
+		schedule.add_system( StateMachine::< Door, TestEntity >::new().system( "doors" ));
+		
+		request( &mut world, ghost, Door::Locked );
+		schedule.run( &mut world ).unwrap();
+		assert_eq!( world.door_next( ghost ).and_then( NextState::get ), Some( &Door::Locked ));
+		assert!( world.transitions().is_empty() );
+	}
+	
+	#[test]
+	fn failing_hook_fails_the_system() {
+		let mut world = World::new();
+		let door = world.new_entity();
+		world.insert_door( door, State::new( Door::Closed ));
+		world.insert_door_next( door, NextState::new() );
+		
+		let machine = StateMachine::new().on_enter( Door::Locked, |_, data| data.write::< f64 >().map( |_| () ));
+		let mut schedule = Schedule::new();
+		schedule.add_system( machine.system( "doors" ));
+		
+		request( &mut world, door, Door::Locked );
+		assert_eq!(
+			schedule.run( &mut world ),
+			Err( ScheduleErr::System( "doors".to_owned(), EcsErr::UndeclaredAccess( CompName::of::< f64 >() )))
+		);
+	}
+}
@@ -0,0 +1,466 @@
+use std::hash::{ Hash, Hasher };
+
+use crate::{ EcsErr, EcsMain, EntityId, EntityMap, SnapErr, SnapSection, SnapValue };
+
+//------------------------------------------------------------------------------
+// --Traits
+
+/// Tag declared in `tags [...]` section of `minecs!`, implemented by the ECS for each tag type.
+pub trait EcsTagFn< T > {
+	/// Bit of the tag in `TagSet`.
+	const BIT: u32;
+}
+
+/// Tags of entities, implemented by `minecs!` for every ECS.
+/// 
+/// Tags are marker types stored as bits of a per-entity `TagSet`, without a component or a `CompId` in the entity.
+pub trait EcsTag< E: Clone >: EcsMain< E > + Sized {
+	/// Borrows tags of all entities.
+	fn tags ( &self ) -> &Tags;
+	
+	/// Mutably borrows tags of all entities.
+	fn tags_mut ( &mut self ) -> &mut Tags;
+	
+	/// Adds tag `T` to the entity.
+	/// Returns `Ok( true )` if the tag was added, or `Ok( false )` if the entity already had it.
+	/// 
+	/// # Errors
+	/// 
+	/// EcsErr::NoSuchEntityId - when entity with specified id does not exist or was despawned
+	#[inline]
+	fn add_tag< T > ( &mut self, id: EntityId< E > ) -> Result< bool, EcsErr >
+	where
+		Self: EcsTagFn< T >,
+	{
+		if self.entity( id ).is_none() {
+			return Err( EcsErr::NoSuchEntityId( usize::from( id )))
+		}
+		Ok( self.tags_mut().insert( usize::from( id ), < Self as EcsTagFn< T >>::BIT ))
+	}
+	
+	/// Removes tag `T` from the entity, returns `true` if the entity had it.
+	#[inline]
+	fn remove_tag< T > ( &mut self, id: EntityId< E > ) -> bool
+	where
+		Self: EcsTagFn< T >,
+	{
+		self.tags_mut().remove( usize::from( id ), < Self as EcsTagFn< T >>::BIT )
+	}
+	
+	/// Returns `true` if the entity has tag `T`.
+	#[inline]
+	fn has_tag< T > ( &self, id: EntityId< E > ) -> bool
+	where
+		Self: EcsTagFn< T >,
+	{
+		self.tags().get( usize::from( id )).contains( < Self as EcsTagFn< T >>::BIT )
+	}
+	
+	/// Returns all tags of the entity.
+	#[inline]
+	fn tag_set ( &self, id: EntityId< E > ) -> TagSet {
+		self.tags().get( usize::from( id ))
+	}
+	
+	/// Creates an empty filter of tags of this ECS.
+	#[inline]
+	fn tag_filter ( &self ) -> TagFilter< Self > {
+		TagFilter::new()
+	}
+	
+	/// Returns an iterator over ids of entities matching the filter, in order of their ids, skipping despawned entities.
+	fn tagged ( &self, filter: &TagFilter< Self > ) -> Tagged< '_, E >;
+}
+
+// --Traits
+//------------------------------------------------------------------------------
+// struct - TagSet
+
+/// Set of tags of an entity, bit `n` standing for the tag declared `n`-th in `tags [...]`.
+#[cfg_attr( feature = "serde", derive( serde::Serialize, serde::Deserialize ) )]
+#[derive( Debug, Clone, Copy, Default, PartialEq, Eq, Hash )]
+pub struct TagSet( u64 );
+
+impl TagSet {
+	/// Maximum amount of tags declared in a single ECS.
+	pub const CAPACITY: u32 = u64::BITS;
+	
+	#[inline]
+	pub const fn new () -> Self {
+		Self( 0 )
+	}
+	
+	#[inline]
+	pub const fn contains ( self, bit: u32 ) -> bool {
+		bit < Self::CAPACITY && self.0 & ( 1 << bit ) != 0
+	}
+	
+	/// Adds tag, returns `true` if it was not present.
+	#[inline]
+	pub fn insert ( &mut self, bit: u32 ) -> bool {
+		if bit >= Self::CAPACITY || self.contains( bit ) {
+			return false
+		}
+		self.0 |= 1 << bit;
+		true
+	}
+	
+	/// Removes tag, returns `true` if it was present.
+	#[inline]
+	pub fn remove ( &mut self, bit: u32 ) -> bool {
+		let present = self.contains( bit );
+		if present {
+			self.0 &= !( 1 << bit );
+		}
+		present
+	}
+	
+	#[inline]
+	pub const fn is_empty ( self ) -> bool {
+		self.0 == 0
+	}
+	
+	/// Returns the amount of tags in the set.
+	#[inline]
+	pub const fn len ( self ) -> u32 {
+		self.0.count_ones()
+	}
+	
+	/// Returns the bits of all tags in the set.
+	#[inline]
+	pub const fn bits ( self ) -> u64 {
+		self.0
+	}
+}
+
+// struct - TagSet
+//------------------------------------------------------------------------------
+// struct - Tags
+
+/// Tags of all entities, stored in every ECS generated by `minecs!`.
+/// 
+/// Equality and hash ignore entities without tags past the last tagged one, fe. after `remove_tag`
+/// the tags equal ones where the tag was never added.
+#[cfg_attr( feature = "serde", derive( serde::Serialize, serde::Deserialize ) )]
+#[derive( Debug, Clone, Default )]
+pub struct Tags {
+	sets: Vec< TagSet >,
+}
+
+impl Tags {
+	#[inline]
+	pub const fn new () -> Self {
+		Self {
+			sets: Vec::new(),
+		}
+	}
+	
+	/// Returns tags of the entity with specified index.
+	#[inline]
+	pub fn get ( &self, entity: usize ) -> TagSet {
+		self.sets.get( entity ).copied().unwrap_or_default()
+	}
+	
+	/// Replaces tags of the entity with specified index.
+	#[inline]
+	pub fn set ( &mut self, entity: usize, tags: TagSet ) {
+		if let Some( set ) = self.slot( entity, !tags.is_empty() ) {
+			*set = tags;
+		}
+	}
+	
+	/// Adds tag to the entity with specified index, returns `true` if it was not present.
+	#[inline]
+	pub fn insert ( &mut self, entity: usize, bit: u32 ) -> bool {
+		self.slot( entity, true ).is_some_and( |set| set.insert( bit ))
+	}
+	
+	/// Removes tag from the entity with specified index, returns `true` if it was present.
+	#[inline]
+	pub fn remove ( &mut self, entity: usize, bit: u32 ) -> bool {
+		self.slot( entity, false ).is_some_and( |set| set.remove( bit ))
+	}
+	
+	/// Removes all tags of the entity.
+	#[inline]
+	pub fn detach< E > ( &mut self, id: EntityId< E > ) {
+		self.set( usize::from( id ), TagSet::new() );
+	}
+	
+	/// Borrows tags of entities, indexed by entity; entities past the end have no tags.
+	#[inline]
+	pub fn as_slice ( &self ) -> &[TagSet] {
+		&self.sets
+	}
+	
+	/// Returns tags of entities up to the last tagged one.
+	fn trimmed ( &self ) -> &[TagSet] {
+		let len = self.sets.iter().rposition( |set| !set.is_empty() ).map_or( 0, |idx| idx + 1 );
+		self.sets.get( ..len ).unwrap_or_default()
+	}
+	
+	fn slot ( &mut self, entity: usize, grow: bool ) -> Option< &mut TagSet > {
+		if grow && self.sets.len() <= entity {
+			self.sets.resize( entity + 1, TagSet::new() );
+		}
+		self.sets.get_mut( entity )
+	}
+}
+
+impl PartialEq for Tags {
+	#[inline]
+	fn eq ( &self, other: &Self ) -> bool {
+		self.trimmed() == other.trimmed()
+	}
+}
+
+impl Tags {
+	/// This function is intended for internal use only, it is called by generated `PartialEq`.
+	/// 
+	/// Compares tags of entities of `map` with tags of entities of `other_map`, in order of their new ids.
+	#[inline]
+	pub fn eq_mapped ( &self, map: &EntityMap, other: &Self, other_map: &EntityMap ) -> bool {
+		map.iter().map( |pair| self.get( pair.0 )).eq( other_map.iter().map( |pair| other.get( pair.0 )))
+	}
+}
+
+impl Eq for Tags {}
+
+impl Hash for Tags {
+	#[inline]
+	fn hash< H: Hasher > ( &self, state: &mut H ) {
+		self.trimmed().hash( state );
+	}
+}
+
+// struct - Tags
+//------------------------------------------------------------------------------
+// --Impl Snapshot
+
+impl Tags {
+	/// This function is intended for internal use only, it is called by generated `EcsSnapshot::snapshot`.
+	/// 
+	/// Saves entities of `map` having the tag under their new ids, or returns `None` when there are none.
+	#[inline]
+	pub fn to_section ( &self, key: &str, bit: u32, map: &EntityMap ) -> Option< SnapSection > {
+		let mut section = SnapSection::new( key );
+		for ( _, new_idx ) in map.iter().filter( |pair| self.get( pair.0 ).contains( bit )) {
+			section.push( new_idx, SnapValue::Unit );
+		}
+		( !section.entries.is_empty() ).then_some( section )
+	}
+	
+	/// This function is intended for internal use only, it is called by generated `EcsSnapshot::from_snapshot`.
+	/// 
+	/// Restores the tag of entities saved by `Tags::to_section`.
+	/// 
+	/// # Errors
+	/// 
+	/// SnapErr::NoSuchEntityId - when an id is not lower than `entities`
+	#[inline]
+	pub fn load_section ( &mut self, section: SnapSection, bit: u32, entities: usize ) -> Result< (), SnapErr > {
+		for ( idx, _ ) in section.entries {
+			if idx >= entities {
+				return Err( SnapErr::NoSuchEntityId( idx ))
+			}
+			_ = self.insert( idx, bit );
+		}
+		Ok(())
+	}
+}
+
+// --Impl Snapshot
+//------------------------------------------------------------------------------
+// struct - TagFilter
+
+/// Filter of entities by tags of ECS `W`, fe. `ecs.tag_filter().with::< Player >().without::< Dead >()`.
+pub struct TagFilter< W > {
+	with: u64,
+	without: u64,
+	marker: std::marker::PhantomData< fn() -> W >,
+}
+
+impl< W > TagFilter< W > {
+	/// Creates a filter matching all entities.
+	#[inline]
+	pub const fn new () -> Self {
+		Self {
+			with: 0,
+			without: 0,
+			marker: std::marker::PhantomData,
+		}
+	}
+	
+	/// Requires entities to have tag `T`.
+	#[inline]
+	#[must_use]
+	pub const fn with< T > ( mut self ) -> Self
+	where
+		W: EcsTagFn< T >,
+	{
+		self.with |= 1 << < W as EcsTagFn< T >>::BIT;
+		self
+	}
+	
+	/// Requires entities not to have tag `T`.
+	#[inline]
+	#[must_use]
+	pub const fn without< T > ( mut self ) -> Self
+	where
+		W: EcsTagFn< T >,
+	{
+		self.without |= 1 << < W as EcsTagFn< T >>::BIT;
+		self
+	}
+	
+	/// Returns `true` if tags of an entity match the filter.
+	#[inline]
+	pub const fn matches ( &self, tags: TagSet ) -> bool {
+		tags.0 & self.with == self.with && tags.0 & self.without == 0
+	}
+}
+
+impl< W > Default for TagFilter< W > {
+	#[inline]
+	fn default () -> Self {
+		Self::new()
+	}
+}
+
+impl< W > Clone for TagFilter< W > {
+	#[inline]
+	fn clone ( &self ) -> Self {
+		*self
+	}
+}
+
+impl< W > Copy for TagFilter< W > {}
+
+impl< W > std::fmt::Debug for TagFilter< W > {
+	#[inline]
+	fn fmt( &self, f: &mut std::fmt::Formatter<'_> ) -> std::fmt::Result {
+		f.debug_struct( "TagFilter" )
+			.field( "with", &TagSet( self.with ))
+			.field( "without", &TagSet( self.without ))
+			.finish()
+	}
+}
+
+// struct - TagFilter
+//------------------------------------------------------------------------------
+// struct - Tagged
+
+/// Iterator over ids of entities matching a `TagFilter`, created by `EcsTag::tagged` and `SystemData::tagged`.
+#[derive( Debug, Clone )]
+pub struct Tagged< 'a, E > {
+	sets: &'a [TagSet],
+	despawned: &'a [usize],
+	count: usize,
+	idx: usize,
+	with: u64,
+	without: u64,
+	marker: std::marker::PhantomData< fn() -> E >,
+}
+
+impl< 'a, E > Tagged< 'a, E > {
+	/// This function is intended for internal use only, it is called by generated `EcsTag::tagged` and `SystemData::tagged`.
+	/// 
+	/// `count` is the amount of entities, `sets` may be shorter.
+	#[inline]
+	pub const fn new< W > ( sets: &'a [TagSet], count: usize, filter: &TagFilter< W > ) -> Self {
+		Self {
+			sets,
+			despawned: &[],
+			count,
+			idx: 0,
+			with: filter.with,
+			without: filter.without,
+			marker: std::marker::PhantomData,
+		}
+	}
+	
+	/// This function is intended for internal use only, it is called by generated `EcsTag::tagged` and `SystemData::tagged`.
+	/// 
+	/// Skips entities with ids in `despawned`, which must be sorted.
+	#[inline]
+	#[must_use]
+	pub const fn with_despawned ( mut self, despawned: &'a [usize] ) -> Self {
+		self.despawned = despawned;
+		self
+	}
+}
+
+impl< 'a, E > Iterator for Tagged< 'a, E > {
+	type Item = EntityId< E >;
+	
+	#[inline]
+	fn next( &mut self ) -> Option< Self::Item > {
+		while self.idx < self.count {
+			let idx = self.idx;
+			self.idx += 1;
+			let tags = self.sets.get( idx ).copied().unwrap_or_default();
+			if tags.0 & self.with == self.with && tags.0 & self.without == 0 && self.despawned.binary_search( &idx ).is_err() {
+				return Some( EntityId::new( idx ))
+			}
+		}
+		None
+	}
+}
+
+// struct - Tagged
+//------------------------------------------------------------------------------
+// --Tests
+
+#[cfg( test )]
+#[allow( dead_code )]// not every generated method is used by tests
+mod tests {
+	use crate::*;
+	use minecs_macro::minecs;
+	
+	minecs!(
+		#[snapshot]
+		ecs TestEcs< TestArray, TestEntity > {
+			types [ f64 ]
+			tags [ Player, Dead ]
+		}
+	);
+	
+	#[test]
+	fn untagging_restores_equality() {
+		let mut ecs = TestEcs::new();
+		let [ hero, orc ] = [ ecs.new_entity(), ecs.new_entity() ];
+		let other = ecs.clone();
+		
+		assert_eq!( ecs.add_tag::< Dead >( orc ), Ok( true ));
+		assert_ne!( ecs, other );
+		assert!( ecs.remove_tag::< Dead >( orc ));
+		assert_eq!( ecs, other );
+		
+		_ = ecs.add_tag::< Player >( hero );
+		let loaded = TestEcs::from_snapshot( ecs.snapshot().unwrap() ).unwrap();
+		assert_eq!( loaded, ecs );
+	}
+	
+	#[test]
+	fn tagged_lists_entities_past_the_last_tagged_one() {
+		let mut ecs = TestEcs::new();
+		let [ hero, orc, ghost ] = [ ecs.new_entity(), ecs.new_entity(), ecs.new_entity() ];
+		_ = ecs.add_tag::< Player >( hero );
+		
+		let filter = ecs.tag_filter().without::< Player >();
+		assert_eq!( ecs.tagged( &filter ).collect::< Vec<_> >(), [ orc, ghost ] );
+		assert_eq!( ecs.tagged( &TagFilter::new() ).count(), 3 );
+	}
+	
+	#[test]
+	fn despawned_entities_are_not_tagged() {
+		let mut ecs = TestEcs::new();
+		let [ hero, orc, ghost ] = [ ecs.new_entity(), ecs.new_entity(), ecs.new_entity() ];
+		_ = ecs.add_tag::< Dead >( orc );
+		
+		assert_eq!( ecs.despawn( orc ), Ok(()));
+		assert_eq!( ecs.add_tag::< Player >( orc ), Err( EcsErr::NoSuchEntityId( 1 )));
+		assert!( !ecs.has_tag::< Dead >( orc ));
+		assert_eq!( ecs.tagged( &TagFilter::new() ).collect::< Vec<_> >(), [ hero, ghost ] );
+	}
+}
@@ -0,0 +1,409 @@
+use std::time::Duration;
+
+use crate::{ EntityId, Events, System, Time };
+
+//------------------------------------------------------------------------------
+// enum - TimerMode
+
+#[cfg_attr( feature = "serde", derive( serde::Serialize, serde::Deserialize ) )]
+#[derive( Debug, Clone, Copy, PartialEq, Eq )]
+pub enum TimerMode {
+	Once, // finishes once and stays finished until reset
+	Repeating, // starts over each time it finishes
+}
+
+// enum - TimerMode
+//------------------------------------------------------------------------------
+// struct - Timer
+
+/// Component counting time towards a duration, ticked by the system created by `Timer::system`.
+#[cfg_attr( feature = "serde", derive( serde::Serialize, serde::Deserialize ) )]
+#[derive( Debug, Clone, PartialEq )]
+pub struct Timer {
+	duration: Duration,
+	elapsed: Duration,
+	mode: TimerMode,
+	paused: bool,
+	times_finished: u32, // amount of times the timer finished during the last tick
+}
+
+impl Timer {
+	#[inline]
+	pub const fn new ( duration: Duration, mode: TimerMode ) -> Self {
+		Self {
+			duration,
+			elapsed: Duration::ZERO,
+			mode,
+			paused: false,
+			times_finished: 0,
+		}
+	}
+	
+	#[inline]
+	pub const fn once ( duration: Duration ) -> Self {
+		Self::new( duration, TimerMode::Once )
+	}
+	
+	#[inline]
+	pub const fn repeating ( duration: Duration ) -> Self {
+		Self::new( duration, TimerMode::Repeating )
+	}
+	
+	/// Creates a system ticking every `Timer` by `Time::delta`, sending `TimerFinished` each time a timer finishes.
+	/// The ECS needs resources `Time` and `Events< TimerFinished< E > >`; the system is meant for the fixed update of an `App`.
+	#[inline]
+	pub fn system< E > ( name: &str ) -> System< E >
+	where
+		E: Clone + Send + Sync + 'static,
+	{
+		System::new( name, |data| {
+			let delta = data.resource::< Time >()?.delta();
+			let events = data.resource_mut::< Events< TimerFinished< E > > >()?;
+			for ( entity, timer ) in data.write::< Self >()?.iter_mut() {
+				for _ in 0..timer.tick( delta ) {
+					events.send( TimerFinished { entity });
+				}
+			}
+			Ok(())
+		})
+			.reads_resource::< Time >()
+			.writes::< Self >()
+			.writes_resource::< Events< TimerFinished< E > > >()
+	}
+	
+	/// Advances the timer by `delta`, unless it is paused or a finished one-shot timer.
+	/// Returns the amount of times the timer finished, more than one only for a repeating timer with `delta` longer than its duration.
+	#[inline]
+	pub fn tick ( &mut self, delta: Duration ) -> u32 {
+		self.times_finished = 0;
+		if self.paused || ( self.mode == TimerMode::Once && self.is_finished() ) {
+			return 0
+		}
+		
+		self.elapsed = self.elapsed.saturating_add( delta );
+		match self.mode {
+			TimerMode::Once => if self.elapsed >= self.duration {
+				self.elapsed = self.duration;
+				self.times_finished = 1;
+			},
+			// a zero duration finishes once per tick instead of endlessly
+			TimerMode::Repeating => if self.duration.is_zero() {
+				self.elapsed = Duration::ZERO;
+				self.times_finished = 1;
+			} else {
+				while self.elapsed >= self.duration {
+					self.elapsed -= self.duration;
+					self.times_finished = self.times_finished.saturating_add( 1 );
+				}
+			},
+		}
+		self.times_finished
+	}
+	
+	/// Starts the timer over, keeping its mode and pause state.
+	#[inline]
+	pub fn reset ( &mut self ) {
+		self.elapsed = Duration::ZERO;
+		self.times_finished = 0;
+	}
+	
+	#[inline]
+	pub fn pause ( &mut self ) {
+		self.paused = true;
+	}
+	
+	#[inline]
+	pub fn resume ( &mut self ) {
+		self.paused = false;
+	}
+	
+	#[inline]
+	pub const fn is_paused ( &self ) -> bool {
+		self.paused
+	}
+	
+	/// Returns `true` when a one-shot timer reached its duration, or a repeating one finished during the last tick.
+	#[inline]
+	pub fn is_finished ( &self ) -> bool {
+		match self.mode {
+			TimerMode::Once => self.elapsed >= self.duration,
+			TimerMode::Repeating => self.just_finished(),
+		}
+	}
+	
+	/// Returns `true` when the timer finished during the last tick.
+	#[inline]
+	pub const fn just_finished ( &self ) -> bool {
+		self.times_finished > 0
+	}
+	
+	#[inline]
+	pub const fn times_finished ( &self ) -> u32 {
+		self.times_finished
+	}
+	
+	#[inline]
+	pub const fn duration ( &self ) -> Duration {
+		self.duration
+	}
+	
+	/// Sets the duration, keeping the elapsed time.
+	#[inline]
+	pub fn set_duration ( &mut self, duration: Duration ) {
+		self.duration = duration;
+	}
+	
+	#[inline]
+	pub const fn elapsed ( &self ) -> Duration {
+		self.elapsed
+	}
+	
+	#[inline]
+	pub const fn remaining ( &self ) -> Duration {
+		self.duration.saturating_sub( self.elapsed )
+	}
+	
+	#[inline]
+	pub const fn mode ( &self ) -> TimerMode {
+		self.mode
+	}
+}
+
+// struct - Timer
+//------------------------------------------------------------------------------
+// struct - Cooldown
+
+/// Component limiting how often an action can be taken, ticked by the system created by `Cooldown::system`.
+/// 
+/// The cooldown is ready when created. `Cooldown::trigger` takes the action if ready, and starts counting down.
+/// Like a timer, it sends `TimerFinished` when it becomes ready again.
+#[cfg_attr( feature = "serde", derive( serde::Serialize, serde::Deserialize ) )]
+#[derive( Debug, Clone, PartialEq )]
+pub struct Cooldown {
+	timer: Timer,
+}
+
+impl Cooldown {
+	#[inline]
+	pub const fn new ( duration: Duration ) -> Self {
+		let mut timer = Timer::once( duration );
+		timer.elapsed = duration;
+		Self {
+			timer,
+		}
+	}
+	
+	/// Creates a system ticking every `Cooldown` by `Time::delta`, sending `TimerFinished` each time a cooldown becomes ready.
+	/// The ECS needs resources `Time` and `Events< TimerFinished< E > >`; the system is meant for the fixed update of an `App`.
+	/// 
+	/// It writes the same events as `Timer::system`, order the two via `System::after` to keep the order of events explicit.
+	#[inline]
+	pub fn system< E > ( name: &str ) -> System< E >
+	where
+		E: Clone + Send + Sync + 'static,
+	{
+		System::new( name, |data| {
+			let delta = data.resource::< Time >()?.delta();
+			let events = data.resource_mut::< Events< TimerFinished< E > > >()?;
+			for ( entity, cooldown ) in data.write::< Self >()?.iter_mut() {
+				if cooldown.tick( delta ) {
+					events.send( TimerFinished { entity });
+				}
+			}
+			Ok(())
+		})
+			.reads_resource::< Time >()
+			.writes::< Self >()
+			.writes_resource::< Events< TimerFinished< E > > >()
+	}
+	
+	/// Starts counting down if the cooldown is ready.
+	/// Returns `true` when the action can be taken, `false` otherwise.
+	#[inline]
+	pub fn trigger ( &mut self ) -> bool {
+		let ready = self.is_ready();
+		if ready {
+			self.timer.reset();
+		}
+		ready
+	}
+	
+	/// Advances the cooldown by `delta`, unless it is paused or ready.
+	/// Returns `true` when it became ready.
+	#[inline]
+	pub fn tick ( &mut self, delta: Duration ) -> bool {
+		self.timer.tick( delta ) > 0
+	}
+	
+	/// Makes the cooldown ready immediately.
+	#[inline]
+	pub fn finish ( &mut self ) {
+		self.timer.elapsed = self.timer.duration;
+	}
+	
+	#[inline]
+	pub fn pause ( &mut self ) {
+		self.timer.pause();
+	}
+	
+	#[inline]
+	pub fn resume ( &mut self ) {
+		self.timer.resume();
+	}
+	
+	#[inline]
+	pub const fn is_paused ( &self ) -> bool {
+		self.timer.is_paused()
+	}
+	
+	#[inline]
+	pub fn is_ready ( &self ) -> bool {
+		self.timer.is_finished()
+	}
+	
+	#[inline]
+	pub const fn remaining ( &self ) -> Duration {
+		self.timer.remaining()
+	}
+	
+	#[inline]
+	pub const fn duration ( &self ) -> Duration {
+		self.timer.duration()
+	}
+}
+
+// struct - Cooldown
+//------------------------------------------------------------------------------
+// struct - TimerFinished
+
+/// Event sent by the system created by `Timer::system` each time a timer finishes,
+/// and by the one created by `Cooldown::system` each time a cooldown becomes ready.
+#[derive( Debug, Clone, PartialEq )]
+pub struct TimerFinished< E > {
+	pub entity: EntityId< E >, // entity owning the timer
+}
+
+// struct - TimerFinished
+//------------------------------------------------------------------------------
+// --Tests
+
+#[cfg( test )]
+#[allow( dead_code )]// not every generated method is used by tests
+mod tests {
+	use crate::*;
+	use minecs_macro::ecs;
+	use std::time::Duration;
+	
+	#[ecs( TestArray )]
+	#[parallel]
+	pub struct World {
+		#[entities]
+		entities: Vec< TestEntity >,
+		#[component]
+		timer: Timer,
+		#[component]
+		cooldown: Cooldown,
+		#[resource]
+		time: Time,
+		#[resource]
+		finished: Events< TimerFinished< TestEntity > >,
+	}
+	
+	const fn millis ( millis: u64 ) -> Duration {
+		Duration::from_millis( millis )
+	}
+	
+	#[test]
+	fn one_shot_timer_finishes_once() {
+		let mut timer = Timer::once( millis( 30 ));
+		assert_eq!( timer.tick( millis( 20 )), 0 );
+		assert_eq!( timer.tick( millis( 20 )), 1 );
+		assert!( timer.is_finished() && timer.just_finished() );
+		assert_eq!( timer.elapsed(), millis( 30 ));
+		
+		assert_eq!( timer.tick( millis( 20 )), 0 );
+		assert!( timer.is_finished() && !timer.just_finished() );
+		
+		timer.reset();
+		assert!( !timer.is_finished() );
+		assert_eq!( timer.remaining(), millis( 30 ));
+	}
+	
+	#[test]
+	fn repeating_timer_counts_every_finish() {
+		let mut timer = Timer::repeating( millis( 10 ));
+		assert_eq!( timer.tick( millis( 35 )), 3 );
+		assert_eq!( timer.elapsed(), millis( 5 ));
+		assert_eq!( timer.tick( millis( 1 )), 0 );
+		assert!( !timer.is_finished() );
+		
+		// a zero duration finishes once per tick
+		timer.set_duration( Duration::ZERO );
+		assert_eq!( timer.tick( millis( 10 )), 1 );
+	}
+	
+	#[test]
+	fn paused_timer_does_not_advance() {
+		let mut timer = Timer::repeating( millis( 10 ));
+		timer.pause();
+		assert_eq!( timer.tick( millis( 50 )), 0 );
+		assert_eq!( timer.elapsed(), Duration::ZERO );
+		
+		timer.resume();
+		assert_eq!( timer.tick( millis( 10 )), 1 );
+	}
+	
+	#[test]
+	fn cooldown_becomes_ready_once() {
+		let mut cooldown = Cooldown::new( millis( 20 ));
+		assert!( cooldown.is_ready() );
+		assert!( cooldown.trigger() );
+		assert!( !cooldown.trigger() );
+		
+		assert!( !cooldown.tick( millis( 10 )));
+		assert!( cooldown.tick( millis( 10 )));
+		assert!( !cooldown.tick( millis( 10 )));
+		assert!( cooldown.is_ready() );
+		
+		assert!( cooldown.trigger() );
+		cooldown.finish();
+		assert!( cooldown.is_ready() );
+	}
+	
+	#[test]
+	fn systems_send_finished_events() {
+		let mut world = World::new();
+		let bomb = world.new_entity();
+		let gun = world.new_entity();
+		world.insert_timer( bomb, Timer::once( millis( 20 )));
+		world.insert_cooldown( gun, Cooldown::new( millis( 30 )));
+		
+		let mut app = App::new( world, millis( 10 ));
+		app
+			.add_update_system( Timer::system( "timers" ))
+			.add_update_system( Cooldown::system( "cooldowns" ).after( "timers" ));
+		assert_eq!( app.world_mut().cooldown_mut( gun ).map( Cooldown::trigger ), Some( true ));
+		
+		let mut finished = Vec::new();
+		for _ in 0..5 {
+			app.step( 1 ).unwrap();
+			finished.push( app.world_mut().finished_mut().drain().map( |event| event.entity ).collect::< Vec<_> >() );
+		}
+		assert_eq!( finished, [ vec![], vec![ bomb ], vec![ gun ], vec![], vec![] ] );
+	}
+	
+	#[test]
+	fn events_are_kept_until_drained() {
+		let mut world = World::new();
+		let bomb = world.new_entity();
+		world.insert_timer( bomb, Timer::repeating( millis( 10 )));
+		
+		let mut app = App::new( world, millis( 10 ));
+		app.add_update_system( Timer::system( "timers" ));
+		_ = app.frame( millis( 30 )).unwrap();
+		assert_eq!( app.world().finished().len(), 3 );
+		
+		app.world_mut().finished_mut().clear();
+		assert!( app.world().finished().is_empty() );
+	}
+}
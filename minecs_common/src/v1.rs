@@ -1,4 +1,8 @@
 pub use paste;
+#[cfg( feature = "rayon" )]
+pub use rayon;
+#[cfg( feature = "rayon" )]
+use rayon::prelude::*;
 
 //------------------------------------------------------------------------------
 // --Traits
@@ -46,12 +50,58 @@ where
 	/// Returns an iterator over components of type `T`.
 	/// 
 	/// The iterator yields all items from start to end.
-	fn iter ( &self ) -> CompIter< T, E >;// core::slice::Iter< 'a, Component< T, E > >
+	fn iter ( &self ) -> CompIter< '_, T, E >;// core::slice::Iter< 'a, Component< T, E > >
 	
 	/// Returns an iterator over components of type `T`, that allows modifying each value.
 	/// 
 	/// The iterator yields all items from start to end.
-	fn iter_mut ( &mut self ) -> CompIterMut< T, E >;// core::slice::IterMut< 'a, Component< T, E > >
+	fn iter_mut ( &mut self ) -> CompIterMut< '_, T, E >;// core::slice::IterMut< 'a, Component< T, E > >
+	
+	
+	/// Attempts to mutably borrow several components of type `T` at once.
+	/// On success returns the components in the same order as `ids`, or `EcsErr` otherwise.
+	/// 
+	/// # Errors
+	/// 
+	/// EcsErr::DuplicateCompId - when the same id is present more than once
+	/// EcsErr::NoSuchCompId - when component with specified id does not exist
+	fn get_many_mut< const N: usize > ( &mut self, ids: [CompId< T, E >; N] ) -> Result< [&mut Component< T, E >; N], EcsErr >;
+}
+
+// per (A, B) fn, A != B
+pub trait CompVecPair< A, B, E >: CompVecFn< A, E > + CompVecFn< B, E >
+where
+	EntityId< E >: Clone,
+{
+	/// Mutably borrows all components of both types at once, including retained for overwrite.
+	#[allow( clippy::type_complexity )]
+	fn split_mut ( &mut self ) -> ( &mut [Component< A, E >], &mut [Component< B, E >] );
+	
+	/// Attempts to mutably borrow two components of different types at once.
+	/// On success returns `Ok(( &mut Component< A, E >, &mut Component< B, E > ))` or `EcsErr` otherwise.
+	/// 
+	/// # Errors
+	/// 
+	/// EcsErr::NoSuchCompId - when component with specified id does not exist
+	#[allow( clippy::type_complexity )]
+	#[inline]
+	fn get2_mut ( &mut self, id_a: CompId< A, E >, id_b: CompId< B, E > ) -> Result< ( &mut Component< A, E >, &mut Component< B, E > ), EcsErr > {
+		let first = usize::from( id_a );
+		let second = usize::from( id_b );
+		// skips components retained for overwrite
+		if CompVecFn::< A, E >::get( self, CompId::from( first )).is_none() {
+			return Err( EcsErr::NoSuchCompId( CompName::of::< A >(), first ))
+		}
+		if CompVecFn::< B, E >::get( self, CompId::from( second )).is_none() {
+			return Err( EcsErr::NoSuchCompId( CompName::of::< B >(), second ))
+		}
+		
+		let ( slice_a, slice_b ) = self.split_mut();
+		let comp_a = slice_a.get_mut( first ).ok_or( EcsErr::NoSuchCompId( CompName::of::< A >(), first ))?;
+		let comp_b = slice_b.get_mut( second ).ok_or( EcsErr::NoSuchCompId( CompName::of::< B >(), second ))?;
+		
+		Ok(( comp_a, comp_b ))
+	}
 }
 
 // ECS -------------------------------------------------------------------------
@@ -66,11 +116,11 @@ pub trait EcsMain< E > {
 	
 	
 	/// Attempts to borrow entity with specified id.
-	/// On success returns `Some( &Entity )` or `None` otherwise.
+	/// On success returns `Some( &Entity )` or `None` when it does not exist or was despawned.
 	fn entity ( &self, id: EntityId< E > ) -> Option< &E >;
 	
 	/// Attempts to mutably borrow entity with specified id.
-	/// On success returns `Some( &mut Entity )` or `None` otherwise.
+	/// On success returns `Some( &mut Entity )` or `None` when it does not exist or was despawned.
 	fn entity_mut ( &mut self, id: EntityId< E > ) -> Option< &mut E >;
 	
 	
@@ -112,13 +162,22 @@ pub trait EcsCompFn< T, E > {
 		F: FnMut( &mut T ) -> U
 	;
 	
+	
+	/// Attempts to mutably borrow several components with specified ids at once.
+	/// On success returns `Ok( [&mut T; N] )` in the same order as `ids`, or `EcsErr` otherwise.
+	/// 
+	/// # Errors
+	/// 
+	/// EcsErr::DuplicateCompId - when the same id is present more than once
+	/// EcsErr::NoSuchCompId - when component with specified id does not exist
+	fn get_many_mut< const N: usize > ( &mut self, ids: [CompId< T, E >; N] ) -> Result< [&mut T; N], EcsErr >;
 }
 
 pub trait EcsFn< V, E > {
 	/// Returns an iterator over components of type `T`.
 	/// 
 	/// The iterator yields all items from start to end.
-	fn iter< T > ( &self ) -> CompIter< T, E >
+	fn iter< T > ( &self ) -> CompIter< '_, T, E >
 	where
 		V: CompVecFn< T, E >,
 		E: Clone,
@@ -127,7 +186,7 @@ pub trait EcsFn< V, E > {
 	/// Returns an iterator over components of type `T`, that allows modifying each value.
 	/// 
 	/// The iterator yields all items from start to end.
-	fn iter_mut< T > ( &mut self ) -> CompIterMut< T, E >
+	fn iter_mut< T > ( &mut self ) -> CompIterMut< '_, T, E >
 	where
 		V: CompVecFn< T, E >,
 		E: Clone,
@@ -149,13 +208,118 @@ pub trait EcsFn< V, E > {
 		T: Clone,
 	;
 	
+	/// Attempts to borrow component `T` of the entity with specified id.
+	/// On success returns `Ok( &T )` or `EcsErr` otherwise.
+	/// 
+	/// # Errors
+	/// 
+	/// EcsErr::CompVecMissingComponent - when component is present in entity, but cannot be found in `CompVec`
+	/// EcsErr::EntityMissingComponent - when component is not present in entity
+	/// EcsErr::NoSuchEntityId - when entity with specified id does not exist
+	fn get_for< T > ( &self, e_id: EntityId< E > ) -> Result< &T, EcsErr >
+	where
+		V: CompVecFn< T, E >,
+		E: EntityFn< T > + Clone,
+		T: Clone,
+	;
+	
+	/// Attempts to mutably borrow component `T` of the entity with specified id.
+	/// On success returns `Ok( &mut T )` or `EcsErr` otherwise.
+	/// 
+	/// # Errors
+	/// 
+	/// EcsErr::CompVecMissingComponent - when component is present in entity, but cannot be found in `CompVec`
+	/// EcsErr::EntityMissingComponent - when component is not present in entity
+	/// EcsErr::NoSuchEntityId - when entity with specified id does not exist
+	fn get_for_mut< T > ( &mut self, e_id: EntityId< E > ) -> Result< &mut T, EcsErr >
+	where
+		V: CompVecFn< T, E >,
+		E: EntityFn< T > + Clone,
+		T: Clone,
+	;
+	
+	/// Returns true if the entity with specified id has component `T`, or false otherwise.
+	fn has< T > ( &self, e_id: EntityId< E > ) -> bool
+	where
+		V: CompVecFn< T, E >,
+		E: EntityFn< T > + Clone,
+		T: Clone,
+	;
+	
+	/// Attempts to remove component `T` from the entity with specified id.
+	/// On success returns the removed value in `Ok( T )` or `EcsErr` otherwise.
+	/// 
+	/// The value is swapped out of the storage, leaving `T::default()` in the slot retained for overwrite.
+	/// 
+	/// # Errors
+	/// 
+	/// EcsErr::CompVecMissingComponent - when component is present in entity, but cannot be found in `CompVec`
+	/// EcsErr::EntityMissingComponent - when component is not present in entity
+	/// EcsErr::NoSuchEntityId - when entity with specified id does not exist
+	fn take< T > ( &mut self, e_id: EntityId< E > ) -> Result< T, EcsErr >
+	where
+		V: CompVecFn< T, E >,
+		E: EntityFn< T > + Clone,
+		T: Default,
+	;
+	
+	/// Attempts to mutably borrow two components of different types of the entity with specified id.
+	/// On success returns `Ok(( &mut A, &mut B ))` or `EcsErr` otherwise.
+	/// 
+	/// Named fields sharing a type are borrowed with `EcsFn::get_fields_mut` instead.
+	/// 
+	/// # Errors
+	/// 
+	/// EcsErr::CompVecMissingComponent - when component is present in entity, but cannot be found in `CompVec`
+	/// EcsErr::EntityMissingComponent - when component is not present in entity
+	/// EcsErr::NoSuchEntityId - when entity with specified id does not exist
+	fn get2_mut< A, B > ( &mut self, e_id: EntityId< E > ) -> Result< ( &mut A, &mut B ), EcsErr >
+	where
+		V: CompVecPair< A, B, E >,
+		E: EntityFn< A > + EntityFn< B > + Clone,
+		A: Clone,
+		B: Clone,
+	;
+	
+	/// Attempts to mutably borrow several components of type `T` of the entity with specified id at once,
+	/// each selected by an accessor of the entity, fe. `[ TestEntity::health, TestEntity::armor ]` for named fields sharing a type.
+	/// On success returns the components in the same order as `fields`, or `EcsErr` otherwise.
+	/// 
+	/// # Errors
+	/// 
+	/// EcsErr::CompVecMissingComponent - when component is present in entity, but cannot be found in `CompVec`
+	/// EcsErr::DuplicateCompId - when the same component is selected more than once
+	/// EcsErr::EntityMissingComponent - when component is not present in entity
+	/// EcsErr::NoSuchEntityId - when entity with specified id does not exist
+	fn get_fields_mut< T, const N: usize > ( &mut self, e_id: EntityId< E >, fields: [CompGetter< T, E >; N] ) -> Result< [&mut T; N], EcsErr >
+	where
+		V: CompVecFn< T, E >,
+		E: Clone,
+		T: Clone,
+	;
+	
+	/// Returns the entry of component `T` of the entity with specified id, for in-place insert-or-update.
+	/// 
+	/// # Errors
+	/// 
+	/// EcsErr::NoSuchCompId - when component is present in entity, but cannot be borrowed from `CompVec`
+	/// EcsErr::NoSuchEntityId - when entity with specified id does not exist
+	fn entry< T > ( &mut self, e_id: EntityId< E > ) -> Result< CompEntry< '_, T, V, E >, EcsErr >
+	where
+		V: CompVecFn< T, E >,
+		E: EntityFn< T > + Clone,
+		T: Clone,
+	;
+	
 	/// Calls a provided function or closure for each entity in ecs.
 	/// FnMut gets access to `&mut V: CompVec` and `&E: Entity` during each iteration.
 	/// 
 	/// ```rust
-	/// #use min_ecs::*;
+	/// use minecs_common::*;
+	/// use minecs_macro::minecs;
 	/// minecs!( ecs MinEcs< CompArray, TestEntity> { types [ f64, usize, ] some_fld: usize } );
 	/// 
+	/// let mut ecs = MinEcs::new();
 	/// let eid_0 = ecs.new_entity();
 	/// let eid_1 = ecs.new_entity();
 	/// 
@@ -174,16 +338,75 @@ pub trait EcsFn< V, E > {
 	/// 		println!( "comp_vec.get( id ) = {:#?}", opt );
 	/// 	}
 	/// 	if let Some( id ) = entity.get() {
-	/// 		let opt: Option< &f64 > = comp_vec.get( id ).map( Component::borrow );
-	/// 		println!( "comp_vec.get( id ).map( Component::borrow ) = {:#?}", opt );
+	/// 		let opt: Option< &f64 > = comp_vec.get( id ).map( Component::inner );
+	/// 		println!( "comp_vec.get( id ).map( Component::inner ) = {:#?}", opt );
 	/// 	}
 	/// 	if let Some( id ) = entity.some_fld() {
-	/// 		let val = comp_vec.get( id ).unwrap().borrow();
-	/// 		println!( "comp_vec.get( id ).unwrap().borrow() = {val}" );
+	/// 		let val = comp_vec.get( id ).unwrap().inner();
+	/// 		println!( "comp_vec.get( id ).unwrap().inner() = {val}" );
 	/// 	}
 	/// });
 	/// ```
 	fn run_system< F: FnMut( &mut V, &E )> ( &mut self, system_fn: F );
+	
+	/// Borrows all entities along with mutably borrowed components, fe. to access components of several types at once.
+	fn parts_mut ( &mut self ) -> ( &[E], &mut V );
+	
+	/// Returns a parallel iterator over components of type `T`, yielding the same items as `EcsFn::iter`.
+	#[cfg( feature = "rayon" )]
+	#[inline]
+	fn par_iter< T > ( &self ) -> CompParIter< '_, T, E >
+	where
+		V: CompVecFn< T, E >,
+		E: Clone + Sync,
+		T: Clone + Sync,
+	{
+		self.iter().into_par_iter()
+	}
+	
+	/// Returns a parallel iterator over components of type `T`, that allows modifying each value.
+	/// Yields the same items as `EcsFn::iter_mut`.
+	#[cfg( feature = "rayon" )]
+	#[inline]
+	fn par_iter_mut< T > ( &mut self ) -> CompParIterMut< '_, T, E >
+	where
+		V: CompVecFn< T, E >,
+		E: Clone + Send,
+		T: Clone + Send,
+	{
+		self.iter_mut().into_par_iter()
+	}
+	
+	/// Calls a provided function or closure in parallel for each entity having components of both type `A` and `B`,
+	/// passing the entity id, mutably borrowed `A` and borrowed `B`.
+	#[cfg( feature = "rayon" )]
+	#[inline]
+	fn par_for_each< A, B, F > ( &mut self, fcn: F )
+	where
+		V: CompVecPair< A, B, E >,
+		E: EntityFn< A > + EntityFn< B > + Clone + Send + Sync,
+		A: Clone + Send,
+		B: Clone + Sync,
+		F: Fn( EntityId< E >, &mut A, &B ) + Sync + Send,
+	{
+		let ( entities, components ) = self.parts_mut();
+		let ( slice_a, slice_b_mut ) = components.split_mut();
+		let slice_b: &[Component< B, E >] = slice_b_mut;
+		
+		slice_a.par_iter_mut().enumerate().for_each( |( idx, comp_a )| {
+			let e_id = comp_a.id();
+			let Some( entity ) = entities.get( usize::from( e_id )) else {
+				return
+			};
+			// skips components retained for overwrite, no longer associated with the entity
+			if EntityFn::< A >::get( entity ).map( usize::from ) != Some( idx ) {
+				return
+			}
+			if let Some( comp_b ) = EntityFn::< B >::get( entity ).and_then( |cid| slice_b.get( usize::from( cid ))) {
+				fcn( e_id, comp_a.inner_mut(), comp_b.inner() );
+			}
+		});
+	}
 }
 
 // Entity ----------------------------------------------------------------------
@@ -254,7 +477,6 @@ pub struct Component< T, E > {
 
 impl< T, E > Component< T, E >
 where
-	T: Clone,
 	EntityId< E >: Copy,
 {
 	/// Creates a new component.
@@ -317,11 +539,14 @@ where
 //------------------------------------------------------------------------------
 // struct - EntityId
 
+/// Id of an entity, typed by the entity it refers to.
+/// 
+/// The id is `Send` and `Sync` regardless of `E`, as it holds no value of `E`.
 #[cfg_attr( feature = "serde", derive( serde::Serialize, serde::Deserialize ) )]
 #[derive( Debug, Clone, PartialEq )]
 pub struct EntityId< E > {
 	id: usize,
-	marker: std::marker::PhantomData< E >,
+	marker: std::marker::PhantomData< fn() -> E >,// `fn() -> E` keeps auto traits independent of `E`
 }
 
 impl< E > EntityId< E > {
@@ -341,7 +566,7 @@ impl< E > EntityId< E > {
 	pub const fn new ( value: usize ) -> Self {
 		Self {
 			id: value,
-			marker: std::marker::PhantomData::< E >,
+			marker: std::marker::PhantomData,
 		}
 	}
 }
@@ -365,12 +590,15 @@ impl< E: Clone > Copy for EntityId< E > {}
 //------------------------------------------------------------------------------
 // struct - CompId
 
+/// Id of a component of type `T`, typed by the entity it can be stored in.
+/// 
+/// The id is `Send` and `Sync` regardless of `T` and `E`, as it holds no value of either.
 #[cfg_attr( feature = "serde", derive( serde::Serialize, serde::Deserialize ) )]
 #[derive( Debug, Clone, PartialEq )]
 pub struct CompId< T, E > {
 	id: usize,
-	comp_marker: std::marker::PhantomData< T >,
-	entity_marker: std::marker::PhantomData< E >,
+	comp_marker: std::marker::PhantomData< fn() -> T >,// `fn() -> T` keeps auto traits independent of `T`
+	entity_marker: std::marker::PhantomData< fn() -> E >,
 }
 
 impl< T, E > CompId< T, E > {
@@ -390,8 +618,8 @@ impl< T, E > CompId< T, E > {
 	pub const fn new ( id: usize ) -> Self {
 		Self {
 			id,
-			comp_marker: std::marker::PhantomData::< T >,
-			entity_marker: std::marker::PhantomData::< E >,
+			comp_marker: std::marker::PhantomData,
+			entity_marker: std::marker::PhantomData,
 		}
 	}
 }
@@ -467,14 +695,296 @@ impl< 'a, T: Clone, E: Clone > Iterator for CompIterMut< 'a, T, E > {
 
 // struct - CompIterMut
 //------------------------------------------------------------------------------
+// --Impl rayon
+
+/// Parallel iterator over components of type `T`, created by `EcsFn::par_iter`.
+#[cfg( feature = "rayon" )]
+pub type CompParIter< 'a, T, E > = rayon::iter::Map< rayon::slice::Iter< 'a, Component< T, E > >, fn( &'a Component< T, E > ) -> &'a T >;
+
+/// Parallel iterator over components of type `T`, created by `EcsFn::par_iter_mut`.
+#[cfg( feature = "rayon" )]
+pub type CompParIterMut< 'a, T, E > = rayon::iter::Map< rayon::slice::IterMut< 'a, Component< T, E > >, fn( &'a mut Component< T, E > ) -> &'a mut T >;
+
+#[cfg( feature = "rayon" )]
+impl< 'a, T: Clone + Sync, E: Clone + Sync > IntoParallelIterator for CompIter< 'a, T, E > {
+	type Iter = CompParIter< 'a, T, E >;
+	type Item = &'a T;
+	
+	#[inline]
+	fn into_par_iter( self ) -> Self::Iter {
+		self.data.as_slice().par_iter().map( Component::inner )
+	}
+}
+
+#[cfg( feature = "rayon" )]
+impl< 'a, T: Clone + Send, E: Clone + Send > IntoParallelIterator for CompIterMut< 'a, T, E > {
+	type Iter = CompParIterMut< 'a, T, E >;
+	type Item = &'a mut T;
+	
+	#[inline]
+	fn into_par_iter( self ) -> Self::Iter {
+		self.data.into_slice().par_iter_mut().map( Component::inner_mut )
+	}
+}
+
+// --Impl rayon
+//------------------------------------------------------------------------------
+// enum - CompEntry
+
+/// Function storing component id in an entity, fe. `EntityFn::set`.
+pub type CompSetter< T, E > = fn( &mut E, CompId< T, E > ) -> Option< CompId< T, E >>;
+
+/// Function reading component id from an entity, fe. `EntityFn::get` or the accessor of a named field.
+pub type CompGetter< T, E > = fn( &E ) -> Option< CompId< T, E >>;
+
+/// A view into a single component of an entity, which may either be present or absent.
+/// 
+/// Created by `EcsFn::entry` or by the `entry_<fld>` methods generated for named fields.
+#[derive( Debug )]
+pub enum CompEntry< 'a, T, V, E > {
+	Occupied( OccupiedCompEntry< 'a, T, E > ),
+	Vacant( VacantCompEntry< 'a, T, V, E > ),
+}
+
+impl< 'a, T, V, E > CompEntry< 'a, T, V, E >
+where
+	V: CompVecFn< T, E >,
+	E: Clone + 'a,
+	T: Clone,
+{
+	/// Creates a new entry.
+	/// 
+	/// This function is intended for internal use only, it is called by the ECS generated with `minecs!`.
+	/// 
+	/// `comp_id` is the id currently stored in `entity`, and `set` stores a new id in `entity`.
+	/// 
+	/// # Errors
+	/// 
+	/// EcsErr::NoSuchCompId - when the `CompVec` does not lend the component it reports as present
+	#[inline]
+	pub fn new (
+		id: EntityId< E >,
+		entity: &'a mut E,
+		components: &'a mut V,
+		comp_id: Option< CompId< T, E >>,
+		set: CompSetter< T, E >,
+	) -> Result< Self, EcsErr > {
+		match comp_id.filter( |cid| components.get( *cid ).is_some() ) {
+			Some( cid ) => components.get_mut( cid )
+				.map( |comp| Self::Occupied( OccupiedCompEntry { value: comp.inner_mut(), comp_id: cid } ))
+				.ok_or( EcsErr::NoSuchCompId( CompName::of::< T >(), usize::from( cid ))),
+			None => Ok( Self::Vacant( VacantCompEntry { id, entity, components, set } )),
+		}
+	}
+	
+	/// Ensures a component is present by inserting `default` if absent.
+	/// Returns a mutable reference to the component.
+	/// 
+	/// # Errors
+	/// 
+	/// EcsErr::NoSuchCompId - when the `CompVec` does not lend the component it has just stored
+	#[inline]
+	pub fn or_insert ( self, default: T ) -> Result< &'a mut T, EcsErr > {
+		match self {
+			Self::Occupied( entry ) => Ok( entry.into_mut() ),
+			Self::Vacant( entry ) => entry.insert( default ),
+		}
+	}
+	
+	/// Ensures a component is present by inserting the result of `fcn` if absent.
+	/// Returns a mutable reference to the component.
+	/// 
+	/// # Errors
+	/// 
+	/// EcsErr::NoSuchCompId - when the `CompVec` does not lend the component it has just stored
+	#[inline]
+	pub fn or_insert_with< F: FnOnce() -> T > ( self, fcn: F ) -> Result< &'a mut T, EcsErr > {
+		match self {
+			Self::Occupied( entry ) => Ok( entry.into_mut() ),
+			Self::Vacant( entry ) => entry.insert( fcn() ),
+		}
+	}
+	
+	/// Ensures a component is present by inserting `T::default()` if absent.
+	/// Returns a mutable reference to the component.
+	/// 
+	/// # Errors
+	/// 
+	/// EcsErr::NoSuchCompId - when the `CompVec` does not lend the component it has just stored
+	#[inline]
+	pub fn or_default ( self ) -> Result< &'a mut T, EcsErr >
+	where
+		T: Default,
+	{
+		self.or_insert_with( T::default )
+	}
+	
+	/// Calls `fcn` on the component if it is present.
+	/// Returns the entry, allowing further calls fe. `or_insert`.
+	#[inline]
+	#[must_use]
+	pub fn and_modify< F: FnOnce( &mut T ) > ( mut self, fcn: F ) -> Self {
+		if let Self::Occupied( ref mut entry ) = self {
+			fcn( entry.get_mut() );
+		}
+		self
+	}
+}
+
+/// An entry of a component that is present, part of `CompEntry`.
+#[derive( Debug )]
+pub struct OccupiedCompEntry< 'a, T, E > {
+	value: &'a mut T,
+	comp_id: CompId< T, E >,
+}
+
+impl< 'a, T, E > OccupiedCompEntry< 'a, T, E >
+where
+	E: Clone + 'a,
+	T: Clone,
+{
+	/// Returns id of the component.
+	#[inline]
+	pub const fn id ( &self ) -> CompId< T, E > {
+		self.comp_id
+	}
+	
+	/// Mutably borrows the component.
+	#[inline]
+	pub fn get_mut ( &mut self ) -> &mut T {
+		self.value
+	}
+	
+	/// Converts the entry into a mutable reference to the component.
+	#[inline]
+	pub fn into_mut ( self ) -> &'a mut T {
+		self.value
+	}
+}
+
+/// An entry of a component that is absent, part of `CompEntry`.
+pub struct VacantCompEntry< 'a, T, V, E > {
+	id: EntityId< E >,
+	entity: &'a mut E,
+	components: &'a mut V,
+	set: CompSetter< T, E >,
+}
+
+impl< T, V, E > std::fmt::Debug for VacantCompEntry< '_, T, V, E >
+where
+	E: std::fmt::Debug,
+{
+	#[allow( clippy::min_ident_chars )]
+	#[inline]
+	fn fmt( &self, f: &mut std::fmt::Formatter<'_> ) -> std::fmt::Result {
+		f.debug_struct( "VacantCompEntry" ).field( "id", &self.id ).field( "entity", &self.entity ).finish_non_exhaustive()
+	}
+}
+
+impl< 'a, T, V, E > VacantCompEntry< 'a, T, V, E >
+where
+	V: CompVecFn< T, E >,
+	E: Clone + 'a,
+	T: Clone,
+{
+	/// Stores `item` as the component of the entity.
+	/// Returns a mutable reference to the component.
+	/// 
+	/// # Errors
+	/// 
+	/// EcsErr::NoSuchCompId - when the `CompVec` does not lend the component it has just stored
+	#[inline]
+	pub fn insert ( self, item: T ) -> Result< &'a mut T, EcsErr > {
+		let comp_id = self.components.insert( Component::new( self.id, item ));
+		_ = ( self.set )( self.entity, comp_id );
+		
+		self.components.get_mut( comp_id )
+			.map( Component::inner_mut )
+			.ok_or( EcsErr::NoSuchCompId( CompName::of::< T >(), usize::from( comp_id )))
+	}
+}
+
+// enum - CompEntry
+//------------------------------------------------------------------------------
+// struct - CompName
+
+/// Names of a component type, carried by `EcsErr` to tell which storage was involved.
+#[derive( Debug, Clone, Copy, PartialEq, Eq )]
+pub struct CompName {
+	/// Name of the type, as returned by `std::any::type_name`.
+	pub type_name: &'static str,
+	/// Name of the field for named components.
+	pub field: Option< &'static str >,
+}
+
+impl CompName {
+	/// Creates the name of component type `T`.
+	#[inline]
+	pub fn of< T > () -> Self {
+		Self {
+			type_name: std::any::type_name::< T >(),
+			field: None,
+		}
+	}
+	
+	/// Adds the name of the field of a named component.
+	#[inline]
+	#[must_use]
+	pub const fn with_field ( mut self, field: &'static str ) -> Self {
+		self.field = Some( field );
+		self
+	}
+}
+
+#[allow( clippy::min_ident_chars )]
+impl std::fmt::Display for CompName {
+	#[inline]
+	fn fmt( &self, f: &mut std::fmt::Formatter<'_> ) -> std::fmt::Result {
+		match self.field {
+			Some( field ) => format!( "`{field}` ({})", self.type_name ),
+			None => format!( "`{}`", self.type_name ),
+		}.fmt(f)
+	}
+}
+
+// struct - CompName
+//------------------------------------------------------------------------------
 // enum - EcsErr
 
 #[derive( Debug, Clone, PartialEq,  )]
+#[non_exhaustive]
 pub enum EcsErr {
-	CompVecMissingComponent( usize, usize ), // `Component` present in `Entity` cannot be found in `CompVec`
-	EntityMissingComponent( usize ), // `Component` not present in `Entity`
+	BorrowConflict( CompName, CompName ), // the same component type was requested mutably more than once, fe. by systems running in parallel
+	CompVecMissingComponent( CompName, usize, usize ), // `Component` present in `Entity` cannot be found in `CompVec`
+	ComponentOwnerMismatch( CompName, usize, usize, usize ), // `Component` present in `Entity` is associated with another `Entity`
+	DoubleRemoval( CompName, usize ), // `Component` with specified id was already removed
+	DuplicateCompId( CompName, usize ), // the same `CompId` was requested more than once
+	EntityMissingComponent( CompName, usize ), // `Component` not present in `Entity`
+	HierarchyCycle( usize, usize ), // `Entity` cannot become a child of itself or of its descendant
 	NoSuchEntityId( usize ), // `Entity` with specified id does not exist
-	NoSuchCompId( usize ), // `Component` with specified id does not exist
+	NoSuchCompId( CompName, usize ), // `Component` with specified id does not exist
+	OrphanComponent( CompName, usize, usize ), // `Component` is not present in the `Entity` it is associated with
+	UndeclaredAccess( CompName ), // system borrowed component type or resource not declared in its `Access`
+}
+
+impl EcsErr {
+	/// Adds the name of the field of a named component to the `CompName` carried by the error.
+	#[inline]
+	#[must_use]
+	pub const fn with_field ( mut self, field: &'static str ) -> Self {
+		match self {
+			Self::CompVecMissingComponent( ref mut name, .. ) |
+			Self::ComponentOwnerMismatch( ref mut name, .. ) |
+			Self::DoubleRemoval( ref mut name, _ ) |
+			Self::DuplicateCompId( ref mut name, _ ) |
+			Self::EntityMissingComponent( ref mut name, _ ) |
+			Self::NoSuchCompId( ref mut name, _ ) |
+			Self::OrphanComponent( ref mut name, .. ) |
+			Self::UndeclaredAccess( ref mut name ) => name.field = Some( field ),
+			Self::BorrowConflict( .. ) | Self::HierarchyCycle( .. ) | Self::NoSuchEntityId( _ ) => {}
+		}
+		self
+	}
 }
 
 #[allow( clippy::min_ident_chars )]
@@ -482,10 +992,17 @@ impl std::fmt::Display for EcsErr {
 	#[inline]
 	fn fmt( &self, f: &mut std::fmt::Formatter<'_> ) -> std::fmt::Result {
 		match *self {
-			Self::CompVecMissingComponent( e_id, c_id ) => format!( "component with id: {c_id} is present in entity with id {e_id}, but cannot be found in CompVec" ),
-			Self::EntityMissingComponent( id ) => format!( "missing component in entity with id: {id}" ),
+			Self::BorrowConflict( first, second ) => format!( "component {first} cannot be borrowed while {second} is mutably borrowed" ),
+			Self::CompVecMissingComponent( name, e_id, c_id ) => format!( "component {name} with id: {c_id} is present in entity with id: {e_id}, but cannot be found in CompVec" ),
+			Self::ComponentOwnerMismatch( name, e_id, c_id, owner ) => format!( "component {name} with id: {c_id} is present in entity with id: {e_id}, but is associated with entity with id: {owner}" ),
+			Self::DoubleRemoval( name, id ) => format!( "component {name} with id: {id} was already removed" ),
+			Self::DuplicateCompId( name, id ) => format!( "component {name} with id: {id} was requested more than once" ),
+			Self::EntityMissingComponent( name, id ) => format!( "missing component {name} in entity with id: {id}" ),
+			Self::HierarchyCycle( child, parent ) => format!( "entity with id: {parent} cannot become parent of entity with id: {child}, which is the same entity or its ancestor" ),
 			Self::NoSuchEntityId( id ) => format!( "entity with id: {id} does not exist" ),
-			Self::NoSuchCompId( id ) => format!( "component with id: {id} does not exist" ),
+			Self::NoSuchCompId( name, id ) => format!( "component {name} with id: {id} does not exist" ),
+			Self::OrphanComponent( name, c_id, owner ) => format!( "component {name} with id: {c_id} is associated with entity with id: {owner}, but is not present in it" ),
+			Self::UndeclaredAccess( name ) => format!( "{name} was borrowed by a system, but is not declared in its access" ),
 		}.fmt(f)
 	}
 }
@@ -493,6 +1010,48 @@ impl std::error::Error for EcsErr {}
 
 // enum - EcsErr
 //------------------------------------------------------------------------------
+// fn - get_many_mut
+
+/// This function is intended for internal use only, it is called by `CompVecFn::get_many_mut` implementations.
+/// 
+/// Mutably borrows `N` distinct elements of `slice` at once, returning them in the same order as `indices`.
+/// 
+/// # Errors
+/// 
+/// EcsErr::DuplicateCompId - when the same index is present more than once
+/// EcsErr::NoSuchCompId - when index is out of bounds
+#[inline]
+pub fn slice_get_many_mut< X, const N: usize > ( name: CompName, slice: &mut [X], indices: [usize; N] ) -> Result< [&mut X; N], EcsErr > {
+	let mut sorted: Vec< ( usize, usize ) > = indices.iter().copied().zip( 0 .. ).collect();
+	sorted.sort_unstable();
+	
+	let mut slots: Vec< Option< &mut X >> = ( 0 .. N ).map( |_| None ).collect();
+	let mut rest = slice;
+	let mut consumed = 0;
+	
+	for ( idx, pos ) in sorted {
+		if idx < consumed {
+			return Err( EcsErr::DuplicateCompId( name, idx ))
+		}
+		
+		let tail = core::mem::take( &mut rest );
+		let ( item, new_rest ) = tail.get_mut( idx - consumed .. )
+			.and_then( <[X]>::split_first_mut )
+			.ok_or( EcsErr::NoSuchCompId( name, idx ))?;
+		
+		if let Some( slot ) = slots.get_mut( pos ) {
+			*slot = Some( item );
+		}
+		rest = new_rest;
+		consumed = idx + 1;
+	}
+	
+	let out: Vec< &mut X > = slots.into_iter().flatten().collect();
+	<[&mut X; N]>::try_from( out ).map_err( |_| EcsErr::NoSuchCompId( name, N ))
+}
+
+// fn - get_many_mut
+//------------------------------------------------------------------------------
 // macro - min_ecs!
 
 #[macro_export]
@@ -510,22 +1069,52 @@ impl std::error::Error for EcsErr {}
 /// 	`$fld_name`: identifier of the new field<br>
 /// 	colon<br>
 /// 	`$t`: type of the new field, must be present in `$ca`<br>
+/// // optional resources section: `; resources [ $( , $res_name: ident : $res_t: ty )* ]`<br>
+/// 	semicolon<br>
+/// 	keyword `resources` followed by square brackets, each entry preceded by comma<br>
+/// 	`$res_name`: identifier of the new field, stored directly in the ECS<br>
+/// 	colon<br>
+/// 	`$res_t`: type of the new field, must implement `Default`<br>
+/// // optional relations section, only after resources section: `; relations [ $( , $rel_name: ident : $rel_t: ty )* ]`<br>
+/// 	semicolon<br>
+/// 	keyword `relations` followed by square brackets, each entry preceded by comma<br>
+/// 	`$rel_name`: identifier of the new field, storing `Relations< $rel_t >` in the ECS<br>
+/// 	colon<br>
+/// 	`$rel_t`: type implementing `min_ecs::Relation`<br>
 /// // optional derives section: `; $( $derives: ty ),+`<br>
 /// 	semicolon<br>
 /// 	`$derives`: identifier (fe. `Hash`) or path (fe. `serde::Serialize`), entries must be separated by comma<br>
 /// // optional trailing comma<br>
 macro_rules! new_ecs {
 	( $name: ident, $ca: ident, $entity: ident $( , $fld_name: ident : $t: ty )* $(,)? ) => {
-		$crate::new_ecs!( @inner, $name, $ca, $entity $( , $fld_name : $t )*; Debug, Clone, PartialEq, );
+		$crate::new_ecs!( @inner, [] pub $name, $ca, $entity $( , $fld_name : $t )*; resources []; relations []; Debug, Clone, );
+	};
+	( $( #[$attr: meta] )* $vis: vis $name: ident, $ca: ident, $entity: ident $( , $( #[$fld_attr: meta] )* $fld_name: ident : $t: ty )* ; resources [ $( , $( #[$res_attr: meta] )* $res_name: ident : $res_t: ty )* ] ; relations [ $( , $rel_name: ident : $rel_t: ty )* ] ; $( $derives: ty ),+ $(,)? ) => {
+		$crate::new_ecs!( @inner, [ $( #[$attr] )* ] $vis $name, $ca, $entity $( , $( #[$fld_attr] )* $fld_name : $t )*; resources [ $( , $( #[$res_attr] )* $res_name : $res_t )* ]; relations [ $( , $rel_name : $rel_t )* ]; $( $derives ),+ );
+	};
+	( $name: ident, $ca: ident, $entity: ident $( , $fld_name: ident : $t: ty )* ; resources [ $( , $res_name: ident : $res_t: ty )* ] ; $( $derives: ty ),+ $(,)? ) => {
+		$crate::new_ecs!( @inner, [] pub $name, $ca, $entity $( , $fld_name : $t )*; resources [ $( , $res_name : $res_t )* ]; relations []; $( $derives ),+ );
 	};
 	( $name: ident, $ca: ident, $entity: ident $( , $fld_name: ident : $t: ty )* ; $( $derives: ty ),+ $(,)? ) => {
-		$crate::new_ecs!( @inner, $name, $ca, $entity $( , $fld_name : $t )*; $( $derives ),+ );
+		$crate::new_ecs!( @inner, [] pub $name, $ca, $entity $( , $fld_name : $t )*; resources []; relations []; $( $derives ),+ );
 	};
-	( @inner, $name: ident, $ca: ident, $entity: ident $( , $fld_name: ident : $t: ty )* ; $( $derives: ty ),+ $(,)? ) => {
+	( @inner, [ $( #[$attr: meta] )* ] $vis: vis $name: ident, $ca: ident, $entity: ident $( , $( #[$fld_attr: meta] )* $fld_name: ident : $t: ty )* ; resources [ $( , $( #[$res_attr: meta] )* $res_name: ident : $res_t: ty )* ] ; relations [ $( , $rel_name: ident : $rel_t: ty )* ] ; $( $derives: ty ),+ $(,)? ) => {
 		#[derive( $( $derives ),+ )]
-		pub struct $name {
+		$( #[$attr] )*
+		$vis struct $name {
 			entities: Vec< $entity >,
 			components: $ca,
+			hierarchy: Hierarchy,
+			observers: Observers< $name, $entity >,
+			tags: Tags,
+			despawned: Despawned,
+			$(
+				$rel_name: Relations< $rel_t >,
+			)*
+			$(
+				$( #[$res_attr] )*
+				$res_name: $res_t,
+			)*
 		}
 		
 		impl EcsMain< $entity > for $name where
@@ -545,12 +1134,12 @@ macro_rules! new_ecs {
 			
 			#[inline]
 			fn entity ( &self, id: EntityId< $entity > ) -> Option< &$entity > {
-				self.entities.get( usize::from( id ) )
+				self.despawned.get( &self.entities, usize::from( id ) )
 			}
 			
 			#[inline]
 			fn entity_mut ( &mut self, id: EntityId< $entity > ) -> Option< &mut $entity > {
-				self.entities.get_mut( usize::from( id ) )
+				self.despawned.get_mut( &mut self.entities, usize::from( id ) )
 			}
 			
 			#[inline]
@@ -601,9 +1190,14 @@ macro_rules! new_ecs {
 				}
 			}
 			
+			#[inline]
+			fn get_many_mut< const N: usize > ( &mut self, ids: [CompId< T, $entity >; N] ) -> Result< [&mut T; N], EcsErr > {
+				self.components.get_many_mut( ids ).map( |arr| arr.map( Component::inner_mut ))
+			}
+			
 			#[inline]
 			fn insert ( &mut self, id: EntityId< $entity >, item: T ) -> Option< CompId< T, $entity >> {
-				if let Some( entity ) = self.entities.get_mut( usize::from( id ) ) {
+				if let Some( entity ) = self.despawned.get_mut( &mut self.entities, usize::from( id ) ) {
 					let comp = Component::new( id, item );
 					
 					let comp_id = self.components.insert( comp );
@@ -621,7 +1215,7 @@ macro_rules! new_ecs {
 			$entity: Entity,
 		{
 			#[inline]
-			fn iter< T > ( &self ) -> CompIter< T, $entity >
+			fn iter< T > ( &self ) -> CompIter< '_, T, $entity >
 			where
 				$ca: CompVec + CompVecFn< T, $entity >,
 			{
@@ -629,7 +1223,7 @@ macro_rules! new_ecs {
 			}
 			
 			#[inline]
-			fn iter_mut< T > ( &mut self ) -> CompIterMut< T, $entity >
+			fn iter_mut< T > ( &mut self ) -> CompIterMut< '_, T, $entity >
 			where
 				$ca: CompVec + CompVecFn< T, $entity >,
 			{
@@ -643,7 +1237,7 @@ macro_rules! new_ecs {
 				$entity: EntityFn< T > + Clone,
 				T: Clone,
 			{
-				if let Some( entity ) = self.entities.get_mut( usize::from( e_id ) ) {
+				if let Some( entity ) = self.despawned.get_mut( &mut self.entities, usize::from( e_id ) ) {
 					let comp_id = entity.get();
 					//let comp_id = entity.remove();
 					if let Some( cid ) = comp_id {
@@ -653,17 +1247,132 @@ macro_rules! new_ecs {
 							entity.remove();
 							Ok(())
 						} else {
-							Err( EcsErr::CompVecMissingComponent( usize::from( e_id ), usize::from( cid ) ))
+							Err( EcsErr::CompVecMissingComponent( CompName::of::< T >(), usize::from( e_id ), usize::from( cid ) ))
 						}
-						
+					
 					} else {
-						Err( EcsErr::EntityMissingComponent( usize::from( e_id ) ))
+						Err( EcsErr::EntityMissingComponent( CompName::of::< T >(), usize::from( e_id ) ))
 					}
 				} else {
 					Err( EcsErr::NoSuchEntityId( usize::from( e_id ) ))
 				}
 			}
 			
+			#[inline]
+			fn get_for<T> ( &self, e_id: EntityId< $entity > ) -> Result< &T, EcsErr >
+			where
+				$ca: CompVec + CompVecFn< T, $entity >,
+				$entity: EntityFn< T > + Clone,
+				T: Clone,
+			{
+				let entity = self.despawned.get( &self.entities, usize::from( e_id ) ).ok_or( EcsErr::NoSuchEntityId( usize::from( e_id ) ))?;
+				let cid = entity.get().ok_or( EcsErr::EntityMissingComponent( CompName::of::< T >(), usize::from( e_id ) ))?;
+				
+				self.components.get( cid )
+					.map( Component::inner )
+					.ok_or( EcsErr::CompVecMissingComponent( CompName::of::< T >(), usize::from( e_id ), usize::from( cid ) ))
+			}
+			
+			#[inline]
+			fn get_for_mut<T> ( &mut self, e_id: EntityId< $entity > ) -> Result< &mut T, EcsErr >
+			where
+				$ca: CompVec + CompVecFn< T, $entity >,
+				$entity: EntityFn< T > + Clone,
+				T: Clone,
+			{
+				let entity = self.despawned.get( &self.entities, usize::from( e_id ) ).ok_or( EcsErr::NoSuchEntityId( usize::from( e_id ) ))?;
+				let cid = entity.get().ok_or( EcsErr::EntityMissingComponent( CompName::of::< T >(), usize::from( e_id ) ))?;
+				
+				self.components.get_mut( cid )
+					.map( Component::inner_mut )
+					.ok_or( EcsErr::CompVecMissingComponent( CompName::of::< T >(), usize::from( e_id ), usize::from( cid ) ))
+			}
+			
+			#[inline]
+			fn has<T> ( &self, e_id: EntityId< $entity > ) -> bool
+			where
+				$ca: CompVec + CompVecFn< T, $entity >,
+				$entity: EntityFn< T > + Clone,
+				T: Clone,
+			{
+				self.get_for::< T >( e_id ).is_ok()
+			}
+			
+			#[inline]
+			fn take<T> ( &mut self, e_id: EntityId< $entity > ) -> Result< T, EcsErr >
+			where
+				$ca: CompVec + CompVecFn< T, $entity >,
+				$entity: EntityFn< T > + Clone,
+				T: Default,
+			{
+				let entity = self.despawned.get_mut( &mut self.entities, usize::from( e_id ) ).ok_or( EcsErr::NoSuchEntityId( usize::from( e_id ) ))?;
+				// `CompId< T, E >` is `Copy` only for `T: Clone`, so it is rebuilt from its index
+				let idx = entity.get().map( usize::from ).ok_or( EcsErr::EntityMissingComponent( CompName::of::< T >(), usize::from( e_id ) ))?;
+				if self.components.get( CompId::from( idx )).is_none() {
+					return Err( EcsErr::CompVecMissingComponent( CompName::of::< T >(), usize::from( e_id ), idx ))
+				}
+				
+				self.components.remove( CompId::from( idx ))?;
+				_ = entity.remove();
+				
+				// the slot is retained for overwrite, so the value is swapped out instead of moved
+				self.components.get_mut( CompId::from( idx ))
+					.map( |comp| std::mem::take( comp.inner_mut() ))
+					.ok_or( EcsErr::CompVecMissingComponent( CompName::of::< T >(), usize::from( e_id ), idx ))
+			}
+			
+			#[inline]
+			fn get2_mut<A, B> ( &mut self, e_id: EntityId< $entity > ) -> Result< ( &mut A, &mut B ), EcsErr >
+			where
+				$ca: CompVecPair< A, B, $entity >,
+				$entity: EntityFn< A > + EntityFn< B > + Clone,
+				A: Clone,
+				B: Clone,
+			{
+				let entity = self.despawned.get( &self.entities, usize::from( e_id ) ).ok_or( EcsErr::NoSuchEntityId( usize::from( e_id ) ))?;
+				let cid_a: CompId< A, $entity > = entity.get().ok_or( EcsErr::EntityMissingComponent( CompName::of::< A >(), usize::from( e_id ) ))?;
+				let cid_b: CompId< B, $entity > = entity.get().ok_or( EcsErr::EntityMissingComponent( CompName::of::< B >(), usize::from( e_id ) ))?;
+				
+				match self.components.get2_mut( cid_a, cid_b ) {
+					Ok(( comp_a, comp_b )) => Ok(( comp_a.inner_mut(), comp_b.inner_mut() )),
+					Err( EcsErr::NoSuchCompId( name, c_id )) => Err( EcsErr::CompVecMissingComponent( name, usize::from( e_id ), c_id )),
+					Err( err ) => Err( err ),
+				}
+			}
+			
+			#[inline]
+			fn get_fields_mut< T, const N: usize > ( &mut self, e_id: EntityId< $entity >, fields: [CompGetter< T, $entity >; N] ) -> Result< [&mut T; N], EcsErr >
+			where
+				$ca: CompVec + CompVecFn< T, $entity >,
+				T: Clone,
+			{
+				let entity = self.despawned.get( &self.entities, usize::from( e_id ) ).ok_or( EcsErr::NoSuchEntityId( usize::from( e_id ) ))?;
+				let ids = fields.map( |field| field( entity ));
+				if ids.iter().any( Option::is_none ) {
+					return Err( EcsErr::EntityMissingComponent( CompName::of::< T >(), usize::from( e_id ) ))
+				}
+				
+				self.components.get_many_mut( ids.map( |cid| cid.map_or( 0, usize::from ).into() ))
+					.map( |arr| arr.map( Component::inner_mut ))
+					.map_err( |err| match err {
+						EcsErr::NoSuchCompId( name, c_id ) => EcsErr::CompVecMissingComponent( name, usize::from( e_id ), c_id ),
+						other => other,
+					})
+			}
+			
+			#[inline]
+			fn entry<T> ( &mut self, e_id: EntityId< $entity > ) -> Result< CompEntry< '_, T, $ca, $entity >, EcsErr >
+			where
+				$ca: CompVec + CompVecFn< T, $entity >,
+				$entity: EntityFn< T > + Clone,
+				T: Clone,
+			{
+				let entity = self.despawned.get_mut( &mut self.entities, usize::from( e_id ) ).ok_or( EcsErr::NoSuchEntityId( usize::from( e_id ) ))?;
+				let comp_id = EntityFn::< T >::get( entity );
+				
+				CompEntry::new( e_id, entity, &mut self.components, comp_id, <$entity as EntityFn< T >>::set )
+			}
+			
 			#[inline]
 			fn run_system< F: FnMut( &mut $ca, &$entity )> ( &mut self, mut system_fn: F ) {
 				for ent in self.entities.iter() {
@@ -671,6 +1380,11 @@ macro_rules! new_ecs {
 				}
 			}
 			
+			#[inline]
+			fn parts_mut ( &mut self ) -> ( &[$entity], &mut $ca ) {
+				( &self.entities, &mut self.components )
+			}
+		
 		}
 		
 		impl $name {
@@ -679,6 +1393,16 @@ macro_rules! new_ecs {
 				Self {
 					entities: Vec::new(),
 					components: $ca::new(),
+					hierarchy: Hierarchy::new(),
+					observers: Observers::new(),
+					tags: Tags::new(),
+					despawned: Despawned::new(),
+					$(
+						$rel_name: Relations::new(),
+					)*
+					$(
+						$res_name: <$res_t>::default(),
+					)*
 				}
 			}
 			
@@ -690,11 +1414,72 @@ macro_rules! new_ecs {
 		$crate::paste::paste!{
 			impl $name {
 				$(
+					$( #[$res_attr] )*
+					/// 
+					/// Borrows the resource stored in this field.
+					#[inline]
+					pub const fn $res_name ( &self ) -> &$res_t {
+						&self.$res_name
+					}
+					
+					/// Mutably borrows the resource stored in this field.
+					#[inline]
+					pub fn [<$res_name _mut>] ( &mut self ) -> &mut $res_t {
+						&mut self.$res_name
+					}
+				)*
+				
+				$(
+					$( #[$fld_attr] )*
+					/// 
+					/// Attempts to borrow this component of the entity with specified id.
+					/// On success returns `Some( &T )` or `None` otherwise.
+					#[inline]
+					pub fn $fld_name ( &self, id: EntityId< $entity > ) -> Option< &$t > {
+						let comp_id = self.despawned.get( &self.entities, usize::from( id ) )?.$fld_name()?;
+						self.components.get( comp_id ).map( Component::inner )
+					}
+					
+					/// Attempts to mutably borrow this component of the entity with specified id.
+					/// On success returns `Some( &mut T )` or `None` otherwise.
+					#[inline]
+					pub fn [<$fld_name _mut>] ( &mut self, id: EntityId< $entity > ) -> Option< &mut $t > {
+						let comp_id = self.despawned.get( &self.entities, usize::from( id ) )?.$fld_name()?;
+						self.components.get_mut( comp_id ).map( Component::inner_mut )
+					}
+					
+					/// Returns true if the entity with specified id has this component, or false otherwise.
+					#[inline]
+					pub fn [<has_ $fld_name>] ( &self, id: EntityId< $entity > ) -> bool {
+						self.$fld_name( id ).is_some()
+					}
+					
+					/// Attempts to call a function `Fn( &T ) -> U` on this component of the entity with specified id.
+					/// On success returns `Some( U )` or `None` otherwise.
+					#[inline]
+					pub fn [<call_ $fld_name>]< F: Fn( &$t ) -> U, U > ( &self, id: EntityId< $entity >, fcn: F ) -> Option< U > {
+						self.$fld_name( id ).map( fcn )
+					}
+					
+					/// Returns the entry of this component of the entity with specified id, for in-place insert-or-update.
+					/// 
+					/// # Errors
+					/// 
+					/// EcsErr::NoSuchCompId - when component is present in entity, but cannot be borrowed from `CompVec`
+					/// EcsErr::NoSuchEntityId - when entity with specified id does not exist
+					#[inline]
+					pub fn [<entry_ $fld_name>] ( &mut self, id: EntityId< $entity > ) -> Result< CompEntry< '_, $t, $ca, $entity >, EcsErr > {
+						let entity = self.despawned.get_mut( &mut self.entities, usize::from( id ) ).ok_or( EcsErr::NoSuchEntityId( usize::from( id ) ))?;
+						let comp_id = entity.$fld_name();
+						
+						CompEntry::new( id, entity, &mut self.components, comp_id, $entity::[<set_ $fld_name>] )
+					}
+					
 					/// Attempts to add component to the entity, potentially discarding the previous component.
 					/// On success returns `Some( CompId< T, E > )` or `None` otherwise.
 					#[inline]
 					pub fn [<insert_ $fld_name>] ( &mut self, id: EntityId< $entity >, item: $t ) -> Option< CompId< $t, $entity >> {
-						if let Some( entity ) = self.entities.get_mut( usize::from( id ) ) {
+						if let Some( entity ) = self.despawned.get_mut( &mut self.entities, usize::from( id ) ) {
 							let comp = Component::new( id, item );
 							
 							let comp_id = self.components.insert( comp );
@@ -711,11 +1496,11 @@ macro_rules! new_ecs {
 					#[inline]
 					pub fn [<remove_ $fld_name>] ( &mut self, id: EntityId< $entity > ) -> Result< (), EcsErr > {
 						let e_id = usize::from( id );
-						if let Some( entity ) = self.entities.get_mut( e_id ) {
+						if let Some( entity ) = self.despawned.get_mut( &mut self.entities, e_id ) {
 							let opt = entity.$fld_name();
 							
 							if let Some( cid ) = opt {
-								let out = self.components.remove( cid );
+								let out = self.components.remove( cid ).map_err( |err| err.with_field( stringify!( $fld_name )));
 								
 								if out.is_ok() {
 									_ = entity.[<remove_ $fld_name>]();
@@ -723,7 +1508,7 @@ macro_rules! new_ecs {
 								
 								out
 							} else {
-								Err( EcsErr::EntityMissingComponent( usize::from( e_id ) ))
+								Err( EcsErr::EntityMissingComponent( CompName::of::< $t >().with_field( stringify!( $fld_name )), e_id ))
 							}
 						} else {
 							Err( EcsErr::NoSuchEntityId( e_id ) )
@@ -791,7 +1576,7 @@ impl< V, E > EcsMain< E > for TestEcs< V, E > where
 			false
 		}
 	}
-	
+
 }
 
 impl< T, V, E > EcsCompFn< T, E > for TestEcs< V, E > where
@@ -802,7 +1587,7 @@ impl< T, V, E > EcsCompFn< T, E > for TestEcs< V, E > where
 	T: Clone,
 {
 	fn get ( &self, id: CompId< T, E > ) -> Option< &T > {
-		self.components.get( id ).map( Component::borrow )
+		self.components.get( id ).map( Component::inner )
 	}
 	
 	fn get_mut ( &mut self, id: CompId< T, E > ) -> Option< &mut T > {
@@ -837,14 +1622,14 @@ impl< T, V, E > EcsCompFn< T, E > for TestEcs< V, E > where
 			None
 		}
 	}
-	
+
 }
 
 impl< V, E > EcsFn< V, E > for TestEcs< V, E > where
 	V: CompVec,
 	E: Entity,
 {
-	fn iter< T > ( &self ) -> CompIter< T, E >
+	fn iter< T > ( &self ) -> CompIter< '_, T, E >
 	where
 		V: CompVec + CompVecFn< T, E >,
 		E: Clone,
@@ -852,7 +1637,7 @@ impl< V, E > EcsFn< V, E > for TestEcs< V, E > where
 		self.components.iter()
 	}
 	
-	fn iter_mut< T > ( &mut self ) -> CompIterMut< T, E >
+	fn iter_mut< T > ( &mut self ) -> CompIterMut< '_, T, E >
 	where
 		V: CompVec + CompVecFn< T, E >,
 		E: Clone,
@@ -889,7 +1674,7 @@ impl< V, E > EcsFn< V, E > for TestEcs< V, E > where
 			system_fn( &mut self.components, ent );
 		}
 	}
-	
+
 }
 
 #[allow( dead_code )]
@@ -997,6 +1782,182 @@ impl<T> CompVecFn< T, Voidunia > for Testunia where
 	fn iter_mut ( &mut self ) -> CompIterMut< T, Voidunia > {
 		todo!()
 	}
-	
+
 }
 // */
+
+//------------------------------------------------------------------------------
+// --Tests
+
+#[cfg( test )]
+#[allow( dead_code )]// not every generated method is used by tests
+mod tests {
+	use crate::*;
+	use minecs_macro::minecs;
+	
+	#[derive( Debug, Clone, Default, PartialEq )]
+	pub struct Points( u32 );
+	
+	minecs!( ecs TestEcs< TestArray, TestEntity > { types [ f64, usize ] health: Points, armor: Points, } );
+	
+	#[test]
+	fn named_fields_sharing_a_type_are_separate() {
+		let mut ecs = TestEcs::new();
+		let eid = ecs.new_entity();
+		
+		assert_eq!( ecs.health( eid ), None );
+		assert!( !ecs.has_health( eid ));
+		
+		_ = ecs.insert_health( eid, Points( 100 ));
+		_ = ecs.insert_armor( eid, Points( 5 ));
+		
+		assert_eq!( ecs.health( eid ), Some( &Points( 100 )));
+		assert_eq!( ecs.armor( eid ), Some( &Points( 5 )));
+		assert!( ecs.has_health( eid ) && ecs.has_armor( eid ));
+		
+		if let Some( armor ) = ecs.armor_mut( eid ) {
+			armor.0 += 1;
+		}
+		assert_eq!( ecs.call_armor( eid, |armor| armor.0 * 2 ), Some( 12 ));
+		assert_eq!( ecs.health( eid ), Some( &Points( 100 )));
+		
+		assert_eq!( ecs.remove_health( eid ), Ok(()));
+		assert!( !ecs.has_health( eid ));
+		assert_eq!( ecs.armor( eid ), Some( &Points( 6 )));
+	}
+	
+	#[test]
+	fn named_field_accessors_on_missing_entity() {
+		let mut ecs = TestEcs::new();
+		let eid = ecs.new_entity();
+		let missing = EntityId::new( 7 );
+		_ = ecs.insert_health( eid, Points( 1 ));
+		
+		assert_eq!( ecs.health( missing ), None );
+		assert_eq!( ecs.health_mut( missing ), None );
+		assert!( !ecs.has_health( missing ));
+		assert_eq!( ecs.call_health( missing, |health| health.0 ), None );
+		assert_eq!( ecs.insert_health( missing, Points( 1 )), None );
+		assert_eq!( ecs.remove_health( missing ), Err( EcsErr::NoSuchEntityId( 7 )));
+	}
+	
+	#[test]
+	fn get_for_and_has() {
+		let mut ecs = TestEcs::new();
+		let eid = ecs.new_entity();
+		let missing = EntityId::new( 3 );
+		_ = ecs.insert( eid, 2.5_f64 );
+		
+		assert_eq!( ecs.get_for::< f64 >( eid ), Ok( &2.5 ));
+		assert!( ecs.has::< f64 >( eid ));
+		assert!( !ecs.has::< usize >( eid ));
+		assert!( !ecs.has::< f64 >( missing ));
+		
+		assert_eq!( ecs.get_for::< f64 >( missing ), Err( EcsErr::NoSuchEntityId( 3 )));
+		assert_eq!( ecs.get_for::< usize >( eid ), Err( EcsErr::EntityMissingComponent( CompName::of::< usize >(), 0 )));
+		
+		if let Ok( val ) = ecs.get_for_mut::< f64 >( eid ) {
+			*val *= 2.0;
+		}
+		assert_eq!( ecs.get_for::< f64 >( eid ), Ok( &5.0 ));
+		assert_eq!( ecs.get_for_mut::< usize >( missing ), Err( EcsErr::NoSuchEntityId( 3 )));
+	}
+	
+	#[test]
+	fn take_moves_the_value_out() {
+		let mut ecs = TestEcs::new();
+		let eid = ecs.new_entity();
+		_ = ecs.insert( eid, 9_usize );
+		
+		assert_eq!( ecs.take::< usize >( eid ), Ok( 9 ));
+		assert!( !ecs.has::< usize >( eid ));
+		assert_eq!( ecs.take::< usize >( eid ), Err( EcsErr::EntityMissingComponent( CompName::of::< usize >(), 0 )));
+		assert_eq!( ecs.take::< usize >( EntityId::new( 4 )), Err( EcsErr::NoSuchEntityId( 4 )));
+		
+		// the retained slot is reused by the next insert
+		_ = ecs.insert( eid, 11_usize );
+		assert_eq!( ecs.get_for::< usize >( eid ), Ok( &11 ));
+	}
+	
+	#[test]
+	fn get2_mut_borrows_two_types() {
+		let mut ecs = TestEcs::new();
+		let eid = ecs.new_entity();
+		_ = ecs.insert( eid, 1.5_f64 );
+		_ = ecs.insert( eid, 2_usize );
+		
+		if let Ok(( float, int )) = ecs.get2_mut::< f64, usize >( eid ) {
+			*float += 1.0;
+			*int += 1;
+		}
+		assert_eq!( ecs.get_for::< f64 >( eid ), Ok( &2.5 ));
+		assert_eq!( ecs.get_for::< usize >( eid ), Ok( &3 ));
+		
+		ecs.remove::< usize >( eid ).ok();
+		assert_eq!( ecs.get2_mut::< f64, usize >( eid ).map( |_| () ), Err( EcsErr::EntityMissingComponent( CompName::of::< usize >(), 0 )));
+	}
+	
+	#[test]
+	fn get_fields_mut_borrows_fields_sharing_a_type() {
+		let mut ecs = TestEcs::new();
+		let eid = ecs.new_entity();
+		_ = ecs.insert_health( eid, Points( 10 ));
+		_ = ecs.insert_armor( eid, Points( 3 ));
+		
+		if let Ok([ health, armor ]) = ecs.get_fields_mut( eid, [ TestEntity::health, TestEntity::armor ] ) {
+			health.0 -= armor.0;
+			armor.0 = 0;
+		}
+		assert_eq!( ecs.health( eid ), Some( &Points( 7 )));
+		assert_eq!( ecs.armor( eid ), Some( &Points( 0 )));
+		
+		let health_id = ecs.entity( eid ).and_then( TestEntity::health ).map( usize::from );
+		assert_eq!( ecs.get_fields_mut( eid, [ TestEntity::health, TestEntity::health ] ).map( |_| () ), Err( EcsErr::DuplicateCompId( CompName::of::< Points >(), health_id.unwrap_or_default() )));
+		
+		_ = ecs.remove_armor( eid );
+		assert_eq!( ecs.get_fields_mut( eid, [ TestEntity::health, TestEntity::armor ] ).map( |_| () ), Err( EcsErr::EntityMissingComponent( CompName::of::< Points >(), 0 )));
+		assert_eq!( ecs.get_fields_mut( EntityId::new( 5 ), [ TestEntity::health ] ).map( |_| () ), Err( EcsErr::NoSuchEntityId( 5 )));
+	}
+	
+	#[test]
+	fn entry_inserts_then_updates_in_place() {
+		let mut ecs = TestEcs::new();
+		let eid = ecs.new_entity();
+		
+		assert!( matches!( ecs.entry::< usize >( eid ), Ok( CompEntry::Vacant( _ ))));
+		assert_eq!( ecs.entry::< usize >( eid ).and_then( |entry| entry.or_insert( 1 ).copied() ), Ok( 1 ));
+		
+		let comp_id = ecs.entity( eid ).and_then( EntityFn::< usize >::get );
+		if let Ok( CompEntry::Occupied( entry )) = ecs.entry::< usize >( eid ) {
+			assert_eq!( Some( entry.id() ), comp_id );
+		} else {
+			panic!( "expected an occupied entry" );
+		}
+		
+		// the existing slot is reused, and `and_modify` skips absent components
+		assert_eq!( ecs.entry::< usize >( eid ).and_then( |entry| entry.and_modify( |val| *val += 10 ).or_default().copied() ), Ok( 11 ));
+		assert_eq!( ecs.iter::< usize >().count(), 1 );
+		assert_eq!( ecs.entry::< f64 >( eid ).and_then( |entry| entry.and_modify( |val| *val += 10.0 ).or_default().copied() ), Ok( 0.0 ));
+		
+		assert!( matches!( ecs.entry::< usize >( EntityId::new( 2 )), Err( EcsErr::NoSuchEntityId( 2 ))));
+	}
+	
+	#[test]
+	fn entry_of_named_fields_sharing_a_type() {
+		let mut ecs = TestEcs::new();
+		let eid = ecs.new_entity();
+		
+		_ = ecs.entry_health( eid ).and_then( |entry| entry.or_insert_with( || Points( 5 )).map( |health| health.0 += 1 ));
+		_ = ecs.entry_armor( eid ).and_then( CompEntry::or_default );
+		
+		assert_eq!( ecs.health( eid ), Some( &Points( 6 )));
+		assert_eq!( ecs.armor( eid ), Some( &Points( 0 )));
+		
+		// after removal the entry is vacant again, and the retained slot is reused
+		_ = ecs.remove_health( eid );
+		assert!( matches!( ecs.entry_health( eid ), Ok( CompEntry::Vacant( _ ))));
+		_ = ecs.entry_health( eid ).and_then( |entry| entry.or_insert( Points( 1 )).map( |_| () ));
+		assert_eq!( ecs.health( eid ), Some( &Points( 1 )));
+		assert_eq!( ecs.iter::< Points >().count(), 2 );
+	}
+}
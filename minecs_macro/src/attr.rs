@@ -0,0 +1,185 @@
+use proc_macro2::Span;
+use syn::{ Token, punctuated::Punctuated, spanned::Spanned };
+
+use crate::v4::{ CompArray, CompFlags, DeriveType, IdentTypePair };
+
+/// Role of a single field of the struct annotated with `#[ecs]`.
+enum FieldKind {
+	Entities,
+	Component,
+	GenericComponent,
+	Resource,
+}
+
+impl FieldKind {
+	/// Returns the role of the field and its component attributes, fe. `#[transient]`.
+	fn from_attrs( field: &syn::Field ) -> syn::Result< ( Self, CompFlags ) > {
+		let mut out = None;
+		let mut flags = CompFlags::new();
+		
+		for attr in &field.attrs {
+			let kind = if attr.path().is_ident( "entities" ) {
+				attr.meta.require_path_only()?;
+				Self::Entities
+			} else if attr.path().is_ident( "resource" ) {
+				attr.meta.require_path_only()?;
+				Self::Resource
+			} else if attr.path().is_ident( "component" ) {
+				if let syn::Meta::List( _ ) = attr.meta {
+					let arg: syn::Ident = attr.parse_args()?;
+					if arg != "generic" {
+						return Err( syn::Error::new( arg.span(), "expected `generic`" ))
+					}
+					Self::GenericComponent
+				} else {
+					attr.meta.require_path_only()?;
+					Self::Component
+				}
+			} else if attr.path().is_ident( "transient" ) {
+				attr.meta.require_path_only()?;
+				flags.transient = true;
+				continue
+			} else if attr.path().is_ident( "map_entities" ) {
+				attr.meta.require_path_only()?;
+				flags.map_entities = true;
+				continue
+			} else if attr.path().is_ident( "doc" ) {
+				continue
+			} else {
+				return Err( syn::Error::new( attr.span(), "unexpected attribute: expected one of `#[entities]`, `#[component]`, `#[resource]`" ))
+			};
+			
+			if out.replace( kind ).is_some() {
+				return Err( syn::Error::new( attr.span(), "field may only have one of `#[entities]`, `#[component]`, `#[resource]`" ))
+			}
+		}
+		
+		let kind = out.ok_or_else( || syn::Error::new( field.span(), "missing attribute: expected one of `#[entities]`, `#[component]`, `#[resource]`" ))?;
+		
+		if ( flags.transient || flags.map_entities ) && matches!( kind, Self::Entities | Self::Resource ) {
+			return Err( syn::Error::new( field.span(), "only components can be `#[transient]` or `#[map_entities]`" ))
+		}
+		
+		Ok(( kind, flags ))
+	}
+}
+
+/// Names of fields the generated ecs keeps for itself, which can not be used by resources.
+const RESERVED_NAMES: [&str; 6] = [ "entities", "components", "hierarchy", "observers", "tags", "despawned" ];
+
+/// Returns doc attributes of a field or struct, which are forwarded to generated items.
+fn doc_attrs( attrs: &[syn::Attribute] ) -> Vec< syn::Attribute > {
+	attrs.iter().filter( |attr| attr.path().is_ident( "doc" )).cloned().collect()
+}
+
+/// Extracts `TestEntity` from `Vec< TestEntity >`.
+fn entity_name( ty: &syn::Type ) -> syn::Result< syn::Ident > {
+	if let syn::Type::Path( ref path ) = *ty {
+		if let Some( seg ) = path.path.segments.last() {
+			if seg.ident == "Vec" {
+				if let syn::PathArguments::AngleBracketed( ref args ) = seg.arguments {
+					if let Some( &syn::GenericArgument::Type( syn::Type::Path( ref inner ) )) = args.args.first() {
+						if let Some( ident ) = inner.path.get_ident() {
+							return Ok( ident.clone() )
+						}
+					}
+				}
+			}
+		}
+	}
+	
+	Err( syn::Error::new( ty.span(), "expected `Vec< EntityName >`" ))
+}
+
+impl CompArray {
+	/// Builds `CompArray` out of a struct annotated with `#[ecs( CompArrayName )]`.
+	pub fn from_struct( ca_name: syn::Ident, input: syn::DeriveInput ) -> syn::Result< Self > {
+		if !input.generics.params.is_empty() {
+			return Err( syn::Error::new( input.generics.span(), "generic parameters are not supported" ))
+		}
+		
+		let mut derives = DeriveType::new_vec();
+		let mut snapshot = false;
+		let mut parallel = false;
+		for attr in &input.attrs {
+			if attr.path().is_ident( "doc" ) {
+				continue
+			}
+			if attr.path().is_ident( "snapshot" ) {
+				attr.meta.require_path_only()?;
+				snapshot = true;
+				continue
+			}
+			if attr.path().is_ident( "parallel" ) {
+				attr.meta.require_path_only()?;
+				parallel = true;
+				continue
+			}
+			if !attr.path().is_ident( "derive" ) {
+				return Err( syn::Error::new( attr.span(), "unexpected attribute: expected `derive`, `snapshot` or `parallel`" ))
+			}
+			
+			let list = attr.parse_args_with( Punctuated::< DeriveType, Token![,] >::parse_terminated )?;
+			DeriveType::extend_vec( &mut derives, list )?;
+		}
+		
+		let syn::Data::Struct( data ) = input.data else {
+			return Err( syn::Error::new( input.ident.span(), "`#[ecs]` can only be applied to structs" ))
+		};
+		let syn::Fields::Named( fields ) = data.fields else {
+			return Err( syn::Error::new( input.ident.span(), "`#[ecs]` requires a struct with named fields" ))
+		};
+		
+		let mut entity = None;
+		let mut entity_attrs = Vec::new();
+		let mut names: Vec< syn::Ident > = Vec::new();
+		let mut generic_comps = Vec::new();
+		let mut named_comps = Vec::new();
+		let mut resources = Vec::new();
+		
+		for field in fields.named {
+			let ( kind, flags ) = FieldKind::from_attrs( &field )?;
+			let Some( ident ) = field.ident else {
+				return Err( syn::Error::new( field.ty.span(), "expected named field" ))
+			};
+			if names.contains( &ident ) {
+				return Err( syn::Error::new( ident.span(), "duplicate field name" ))
+			}
+			names.push( ident.clone() );
+			let attrs = doc_attrs( &field.attrs );
+			
+			match kind {
+				FieldKind::Entities => {
+					if ident != "entities" {
+						return Err( syn::Error::new( ident.span(), "the `#[entities]` field must be named `entities`" ))
+					}
+					if entity.replace( entity_name( &field.ty )? ).is_some() {
+						return Err( syn::Error::new( ident.span(), "duplicate `#[entities]` field" ))
+					}
+					entity_attrs = attrs;
+				}
+				FieldKind::Component => named_comps.push( IdentTypePair::new( ident, field.ty ).with_flags( flags ).with_attrs( attrs )),
+				FieldKind::GenericComponent => {
+					if generic_comps.iter().any( |pair: &IdentTypePair| syn::Type::from( pair ) == field.ty ) {
+						return Err( syn::Error::new( field.ty.span(), "duplicate generic component type" ))
+					}
+					generic_comps.push( IdentTypePair::new( ident, field.ty ).with_flags( flags ).with_attrs( attrs ));
+				}
+				FieldKind::Resource => {
+					if RESERVED_NAMES.iter().any( |name| ident == name ) || ident.to_string().starts_with( "relation_" ) {
+						return Err( syn::Error::new( ident.span(), format!( "`{ident}` is used internally by the ecs, rename the resource" )))
+					}
+					resources.push( IdentTypePair::new( ident, field.ty ).with_attrs( attrs ));
+				}
+			}
+		}
+		
+		let entity_name = entity.ok_or_else( || syn::Error::new( Span::call_site(), "missing field: `#[entities] entities: Vec< EntityName >`" ))?;
+		
+		Ok( Self::new( input.ident, ca_name, entity_name, generic_comps, named_comps, resources, derives )
+			.with_attrs( input.vis, doc_attrs( &input.attrs ))
+			.with_entity_attrs( entity_attrs )
+			.with_snapshot( snapshot )
+			.with_parallel( parallel ))
+	}
+}
@@ -185,12 +185,16 @@ use quote::quote;
 //use v3::*;
 mod v4;
 use v4::*;
+mod attr;
+mod map;
 
 /// Creates ECS structs and implements necessary traits for them.
 /// 
 /// # Usage
 /// 
 /// 1. optional derive attribute, ( on top of: `Debug`, `Clone`, `PartialEq` ) fe. `#[derive( serde::Serialize, serde::Deserialize )]`
+/// 1. optional `#[snapshot]` attribute, implements `EcsSnapshot` for the ecs; every component has to implement `SnapComp`,
+/// 1. optional `#[parallel]` attribute, implements `EcsColumns` for the ecs, so that it can be run by `Schedule`; every component has to be `Send + Sync`,
 /// 1. ecs declaration, fe. `ecs MinEcs< CompArray, TestEntity >`
 /// 	1. keyword `ecs`
 /// 	1. identifier - name of the ecs,
@@ -198,6 +202,7 @@ use v4::*;
 /// 1. curly braces `{}` surrounding component declarations ( either or both )
 /// 	- keyword `types` followed by square brackets `[]` surrounding comma separated list of not-repeating types; fe. `types [usize, f64]`,
 /// 	- comma separated field declarations, such as for struct, in form: identifier, colon, type; fe. `names: Vec< Rc< str >>`.
+/// 	- any type or field may be preceded by `#[transient]`, such components are skipped by snapshots; fe. `types [ #[transient] Cache ]`.
 /// 
 /// ```rust
 /// # use minecs_common::*;
@@ -218,3 +223,137 @@ pub fn minecs ( tokens: proc_macro::TokenStream ) -> proc_macro::TokenStream {
 	let ir = parse_macro_input!( tokens with CompArray::parse );
 	quote! { #ir }.into()
 }
+
+/// Creates ECS structs out of an annotated struct, an alternative to `minecs!`.
+/// 
+/// Generates the same types and impls as `minecs!`, with the annotated struct becoming the ECS.
+/// The ECS, entity and component array share the visibility of the struct, docs of the struct are kept on the ECS.
+/// 
+/// # Usage
+/// 
+/// 1. attribute `#[ecs( CompArray )]` naming the component array, placed above any `#[derive]`,
+/// 1. optional derive attribute, ( on top of: `Debug`, `Clone`, `PartialEq` ); `Default` and `Copy` can not be derived,
+/// 1. optional `#[snapshot]` attribute, same as in `minecs!`,
+/// 1. optional `#[parallel]` attribute, same as in `minecs!`; every resource has to be `Send + Sync` as well,
+/// 1. struct with named fields, each annotated with one of:
+/// 	- `#[entities]` - exactly one field named `entities` of type `Vec< EntityName >`, which names the entity; its docs are kept on the entity,
+/// 	- `#[component]` - a named component, same as field declarations in `minecs!`,
+/// 	- `#[component( generic )]` - a component accessed via its type, same as `types [...]` in `minecs!`; the field name is used in the entity,
+/// 	- `#[resource]` - a value stored directly in the ECS, created with `Default`; accessed via `name()` and `name_mut()`.
+/// 
+/// 	components may additionally be marked `#[transient]`, same as in `minecs!`.
+/// 
+/// Docs of components and resources are kept on the entity field and the generated accessor.
+/// Names used by the ECS itself ( `components`, `hierarchy`, `observers`, `tags`, `relation_*` ) can not be used by resources.
+/// 
+/// ```rust
+/// # use minecs_common::*;
+/// # use minecs_macro::*;
+/// #[derive( Debug, Clone, Default, PartialEq )]
+/// pub struct Points( u32 );
+/// 
+/// #[ecs( CompArray )]
+/// #[derive( /* serde::Serialize, serde::Deserialize, ... */ )]
+/// /// The world.
+/// pub struct MinEcs {
+/// 	/// A single unit.
+/// 	#[entities]
+/// 	entities: Vec< TestEntity >,
+/// 	#[component( generic )]
+/// 	float: f64,
+/// 	/// Hit points left.
+/// 	#[component]
+/// 	health: Points,
+/// 	#[component]
+/// 	armor: Points,
+/// 	/// Amount of frames simulated so far.
+/// 	#[resource]
+/// 	tick: usize,
+/// }
+/// 
+/// let mut ecs = MinEcs::new();
+/// let eid = ecs.new_entity();
+/// ecs.insert( eid, 1.5 );
+/// ecs.insert_health( eid, Points( 100 ) );
+/// *ecs.tick_mut() += 1;
+/// 
+/// let float_id: CompId< f64, TestEntity > = ecs.entity( eid ).unwrap().get().unwrap();
+/// assert_eq!( ecs.get( float_id ), Some( &1.5 ) );
+/// 
+/// assert!( ecs.entity( eid ).unwrap().health().is_some() );
+/// assert!( ecs.entity( eid ).unwrap().armor().is_none() );
+/// assert_eq!( *ecs.tick(), 1 );
+/// ```
+/// 
+/// Derives which conflict with generated impls are rejected:
+/// 
+/// ```rust,compile_fail
+/// # use minecs_common::*;
+/// # use minecs_macro::*;
+/// #[ecs( CompArray )]
+/// #[derive( Default )]
+/// pub struct MinEcs {
+/// 	#[entities]
+/// 	entities: Vec< TestEntity >,
+/// 	#[component]
+/// 	health: u32,
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn ecs ( args: proc_macro::TokenStream, item: proc_macro::TokenStream ) -> proc_macro::TokenStream {
+	let ca_name = parse_macro_input!( args as syn::Ident );
+	let input = parse_macro_input!( item as syn::DeriveInput );
+	
+	match CompArray::from_struct( ca_name, input ) {
+		Ok( ir ) => quote! { #ir }.into(),
+		Err( err ) => err.to_compile_error().into(),
+	}
+}
+
+/// Implements `MapEntities`, rewriting every field marked `#[entity]`.
+/// 
+/// Marked fields have to implement `MapEntities` themselves, fe. `EntityId`, `Option< EntityId >` or `Vec< EntityId >`.
+/// 
+/// ```rust
+/// # use minecs_common::*;
+/// # use minecs_macro::*;
+/// #[derive( Debug, Clone, PartialEq )]
+/// pub struct Character;
+/// 
+/// #[derive( MapEntities )]
+/// pub struct Target {
+/// 	#[entity]
+/// 	entity: EntityId< Character >,
+/// 	#[entity]
+/// 	allies: Vec< EntityId< Character >>,
+/// 	distance: f32,
+/// }
+/// 
+/// #[derive( MapEntities )]
+/// pub enum Order {
+/// 	Follow( #[entity] EntityId< Character > ),
+/// 	Wait,
+/// }
+/// 
+/// let mut map = EntityMap::new();
+/// map.insert( 0, 5 );
+/// map.insert( 1, 7 );
+/// 
+/// let mut target = Target { entity: EntityId::new( 0 ), allies: vec![ EntityId::new( 1 ), EntityId::new( 2 ) ], distance: 1.0 };
+/// target.map_entities( &map );
+/// assert_eq!( target.entity, EntityId::new( 5 ));
+/// assert_eq!( target.allies, vec![ EntityId::new( 7 ), EntityId::new( 2 ) ]);
+/// 
+/// let mut order = Order::Follow( EntityId::new( 1 ));
+/// order.map_entities( &map );
+/// assert!( matches!( order, Order::Follow( id ) if id == EntityId::new( 7 )));
+/// ```
+#[proc_macro_derive( MapEntities, attributes( entity ))]
+pub fn derive_map_entities ( tokens: proc_macro::TokenStream ) -> proc_macro::TokenStream {
+	let input = parse_macro_input!( tokens as syn::DeriveInput );
+	
+	match map::derive_map_entities( &input ) {
+		Ok( out ) => out.into(),
+		Err( err ) => err.to_compile_error().into(),
+	}
+}
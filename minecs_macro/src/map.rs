@@ -0,0 +1,65 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{ format_ident, quote };
+use syn::spanned::Spanned;
+
+/// Returns members of fields marked `#[entity]`.
+fn entity_members( fields: &syn::Fields ) -> syn::Result< Vec< syn::Member >> {
+	let mut out = Vec::new();
+	
+	for ( idx, field ) in fields.iter().enumerate() {
+		let mut marked = false;
+		for attr in field.attrs.iter().filter( |attr| attr.path().is_ident( "entity" )) {
+			attr.meta.require_path_only()?;
+			marked = true;
+		}
+		
+		if marked {
+			out.push( field.ident.clone().map_or_else( || syn::Member::from( idx ), syn::Member::from ));
+		}
+	}
+	
+	Ok( out )
+}
+
+/// Generates `impl MapEntities`, mapping every field marked `#[entity]`.
+pub fn derive_map_entities( input: &syn::DeriveInput ) -> syn::Result< TokenStream2 > {
+	let name = &input.ident;
+	let ( impl_generics, ty_generics, where_clause ) = input.generics.split_for_impl();
+	
+	let body = match input.data {
+		syn::Data::Struct( ref data ) => {
+			let members = entity_members( &data.fields )?;
+			quote! {
+				#( MapEntities::map_entities( &mut self.#members, map ); )*
+			}
+		}
+		syn::Data::Enum( ref data ) => {
+			let mut arms = Vec::new();
+			for variant in &data.variants {
+				let ident = &variant.ident;
+				let members = entity_members( &variant.fields )?;
+				let bindings: Vec< syn::Ident > = ( 0 .. members.len() ).map( |idx| format_ident!( "field_{}", idx )).collect();
+				arms.push( quote! {
+					Self::#ident { #( #members: ref mut #bindings, )* .. } => {
+						#( MapEntities::map_entities( #bindings, map ); )*
+					}
+				});
+			}
+			quote! {
+				match *self {
+					#( #arms )*
+				}
+			}
+		}
+		syn::Data::Union( ref data ) => return Err( syn::Error::new( data.union_token.span(), "`MapEntities` cannot be derived for unions" )),
+	};
+	
+	Ok( quote! {
+		impl #impl_generics MapEntities for #name #ty_generics #where_clause {
+			#[allow( unused_variables )]
+			fn map_entities ( &mut self, map: &EntityMap ) {
+				#body
+			}
+		}
+	})
+}
@@ -3,26 +3,124 @@ use std::fmt::Write;
 use proc_macro2::TokenStream as TokenStream2;
 use proc_macro2::Span;
 use syn::{ Token, Type, parse::{ Parse, ParseStream } };
-use quote::{ ToTokens, quote };
+use quote::{ ToTokens, format_ident, quote };
 
 pub struct CompArray {
+	attrs: Vec< syn::Attribute >,
+	vis: syn::Visibility,
 	min_ecs_name: syn::Ident,
 	ca_name: syn::Ident,
+	entity_attrs: Vec< syn::Attribute >,
 	entity_name: syn::Ident,
 	all_types: Vec< Type >,
-	generic_types: Vec< Type >,
+	generic_comps: Vec< IdentTypePair >,
 	named_comps: Vec< IdentTypePair >,
+	resources: Vec< IdentTypePair >,
+	relations: Vec< RelationDecl >,
+	tags: Vec< syn::Ident >,
 	derives: Vec< DeriveType >,
+	snapshot: bool,
+	parallel: bool,
+}
+
+impl CompArray {
+	/// Builds the intermediate representation shared by `minecs!` and `#[ecs]`.
+	/// 
+	/// `generic_comps` pair each generic type with the name of its field in the entity.
+	pub(crate) fn new (
+		min_ecs_name: syn::Ident,
+		ca_name: syn::Ident,
+		entity_name: syn::Ident,
+		generic_comps: Vec< IdentTypePair >,
+		named_comps: Vec< IdentTypePair >,
+		resources: Vec< IdentTypePair >,
+		derives: Vec< DeriveType >,
+	) -> Self {
+		let mut all_types: Vec< Type > = generic_comps.iter().map( syn::Type::from ).collect();
+		for pair in &named_comps {
+			let bonus_type = syn::Type::from( pair );
+			if !all_types.contains( &bonus_type ) {
+				all_types.push( bonus_type );
+			}
+		}
+		
+		Self {
+			attrs: Vec::new(),
+			vis: syn::parse_quote!( pub ),
+			min_ecs_name,
+			ca_name,
+			entity_attrs: Vec::new(),
+			entity_name,
+			all_types,
+			generic_comps,
+			named_comps,
+			resources,
+			relations: Vec::new(),
+			tags: Vec::new(),
+			derives,
+			snapshot: false,
+			parallel: false,
+		}
+	}
+	
+	/// Sets the visibility of generated types and attributes forwarded to the ecs, fe. docs.
+	pub(crate) fn with_attrs( mut self, vis: syn::Visibility, attrs: Vec< syn::Attribute > ) -> Self {
+		self.vis = vis;
+		self.attrs = attrs;
+		self
+	}
+	
+	/// Adds attributes forwarded to the entity, fe. docs.
+	pub(crate) fn with_entity_attrs( mut self, attrs: Vec< syn::Attribute > ) -> Self {
+		self.entity_attrs = attrs;
+		self
+	}
+	
+	/// Enables generating `EcsSnapshot` impl.
+	pub(crate) const fn with_snapshot( mut self, snapshot: bool ) -> Self {
+		self.snapshot = snapshot;
+		self
+	}
+	
+	/// Enables generating `EcsColumns` impl.
+	pub(crate) const fn with_parallel( mut self, parallel: bool ) -> Self {
+		self.parallel = parallel;
+		self
+	}
+	
+	/// Adds relation kinds declared in `relations [...]`.
+	pub(crate) fn with_relations( mut self, relations: Vec< RelationDecl > ) -> Self {
+		self.relations = relations;
+		self
+	}
+	
+	/// Adds tags declared in `tags [...]`.
+	pub(crate) fn with_tags( mut self, tags: Vec< syn::Ident > ) -> Self {
+		self.tags = tags;
+		self
+	}
 }
 
 impl Parse for CompArray {
 	fn parse( input: ParseStream ) -> syn::Result<Self> {
 		let mut derives = DeriveType::new_vec();
 		
-		if input.parse::< Token![#] >().is_ok() {
+		let mut snapshot = false;
+		let mut parallel = false;
+		
+		while input.parse::< Token![#] >().is_ok() {
 			let inner;
 			_ = syn::bracketed!( inner in input );
 			
+			if kw::snapshot::parse( &inner ).is_ok() {
+				snapshot = true;
+				continue
+			}
+			if kw::parallel::parse( &inner ).is_ok() {
+				parallel = true;
+				continue
+			}
+			
 			inner.step( |cursor| {
 				let rest = *cursor;
 				let derive_ident = syn::Ident::new( "derive", Span::mixed_site() );
@@ -33,7 +131,7 @@ impl Parse for CompArray {
 							if bracketed_ident == derive_ident {
 								Ok(((), next))
 							} else {
-								Err( cursor.error( "invalid attribute: expected `derive`" ))
+								Err( cursor.error( "invalid attribute: expected `derive`, `snapshot` or `parallel`" ))
 							}
 						}
 						_ => Err( cursor.error("unexpected token in input") )
@@ -46,8 +144,8 @@ impl Parse for CompArray {
 			let very_inner;
 			_ = syn::parenthesized!( very_inner in inner );
 			let vec: Vec<DeriveType> = very_inner.parse_terminated( DeriveType::parse, syn::Token![,])?.into_iter().collect();
-			derives.extend( vec );
-		};
+			DeriveType::extend_vec( &mut derives, vec )?;
+		}
 		_ = input.parse::< Token![,] >();// ignore trailing comma
 		
 		_ = kw::ecs::parse( input )?;
@@ -62,10 +160,37 @@ impl Parse for CompArray {
 		let fld_tokens;
 		_ = syn::braced!( fld_tokens in input );
 		
-		let generic_types = if kw::types::parse( &fld_tokens ).is_ok() {
+		let generic_types: Vec< ( CompFlags, Type ) > = if kw::types::parse( &fld_tokens ).is_ok() {
 			let inner;
 			_ = syn::bracketed!( inner in fld_tokens );
-			inner.parse_terminated( syn::Type::parse, syn::Token![,])?.into_iter().collect()
+			inner.parse_terminated( parse_generic_type, syn::Token![,])?.into_iter().collect()
+		} else {
+			Vec::new()
+		};
+		
+		// `relations` followed by a colon is a field name
+		let relations: Vec< RelationDecl > = if fld_tokens.peek( kw::relations ) && fld_tokens.peek2( syn::token::Bracket ) {
+			_ = fld_tokens.parse::< kw::relations >()?;
+			let inner;
+			_ = syn::bracketed!( inner in fld_tokens );
+			inner.parse_terminated( RelationDecl::parse, syn::Token![,])?.into_iter()
+				.enumerate()
+				.map( |( idx, decl )| decl.with_field( idx ))
+				.collect()
+		} else {
+			Vec::new()
+		};
+		
+		// `tags` followed by a colon is a field name
+		let tags: Vec< syn::Ident > = if fld_tokens.peek( kw::tags ) && fld_tokens.peek2( syn::token::Bracket ) {
+			_ = fld_tokens.parse::< kw::tags >()?;
+			let inner;
+			_ = syn::bracketed!( inner in fld_tokens );
+			let list: Vec< syn::Ident > = inner.parse_terminated( syn::Ident::parse, syn::Token![,])?.into_iter().collect();
+			if let Some( extra ) = list.get( MAX_TAGS ) {
+				return Err( syn::Error::new( extra.span(), format!( "too many tags: at most {MAX_TAGS} tags can be declared" )))
+			}
+			list
 		} else {
 			Vec::new()
 		};
@@ -73,33 +198,30 @@ impl Parse for CompArray {
 		let named_comps: Vec<IdentTypePair> = fld_tokens.parse_terminated( IdentTypePair::parse, syn::Token![,])?.into_iter().collect();
 		_ = fld_tokens.parse::< Token![,] >();// ignore trailing comma
 		
-		let mut all_types = generic_types.clone();
-		if !named_comps.is_empty() {
-			let bonus_types: Vec< syn::Type > = named_comps.iter().map( syn::Type::from ).collect();
-			
-			for bonus_type in &bonus_types {
-				if !all_types.contains( bonus_type ) {
-					all_types.push( bonus_type.clone() );
-				}
-			}
+		let mut generic_comps = Vec::new();
+		let mut s_ident = String::new();
+		for ( idx, ( flags, ty )) in generic_types.into_iter().enumerate() {
+			_ = write!( &mut s_ident, "type_{idx}" );
+			generic_comps.push( IdentTypePair::new( syn::Ident::new( &s_ident, Span::mixed_site() ), ty ).with_flags( flags ));
+			s_ident.clear();
 		}
 		
-		
-		Ok( Self {
-			min_ecs_name, 
-			ca_name,
-			entity_name,
-			all_types,
-			generic_types,
-			named_comps,
-			derives,
-		})
+		Ok( Self::new( min_ecs_name, ca_name, entity_name, generic_comps, named_comps, Vec::new(), derives )
+			.with_snapshot( snapshot )
+			.with_parallel( parallel )
+			.with_relations( relations )
+			.with_tags( tags ))
 	}
 }
 
+/// Amount of bits in `TagSet`.
+const MAX_TAGS: usize = 64;
+
 impl ToTokens for CompArray {
 	fn to_tokens( &self, tokens: &mut TokenStream2 ) {
 		let CompArray {
+			attrs,
+			vis,
 			min_ecs_name,
 			ca_name,
 			entity_name,
@@ -109,15 +231,45 @@ impl ToTokens for CompArray {
 		
 		let entity = MinEcsEntity::from( self );
 		let ca = MinEcsCa::from( self );
+		let snapshot = self.snapshot.then( || MinEcsSnapshot::from( self ));
+		let merge = MinEcsMerge::from( self );
+		let debug = MinEcsDebug::from( self );
+		let equality = MinEcsEq::from( self );
+		let hierarchy = MinEcsHierarchy::from( self );
+		let relations = MinEcsRelations::from( self );
+		let tags = MinEcsTags::from( self );
+		let parallel = self.parallel.then( || MinEcsParallel::from( self ));
+		let assert = MinEcsAssert::from( self );
 		
 		let macro_args: Vec<_> = self.named_comps.iter().map( MinEcsMacroArg::from ).collect();
+		let resource_args: Vec<_> = self.resources.iter().map( MinEcsMacroArg::from ).collect();
+		let relation_args: Vec<_> = self.relations.iter().map( MinEcsMacroArg::from ).collect();
+		let ecs_derives = derives.iter().filter( |derive| !derive.is_comparison() );
 		
 		tokens.extend( quote! {
 			#entity
 			
 			#ca
 			
-			new_ecs!( #min_ecs_name, #ca_name, #entity_name #( #macro_args )*; #( #derives , )* );
+			new_ecs!( #( #attrs )* #vis #min_ecs_name, #ca_name, #entity_name #( #macro_args )*; resources [ #( #resource_args )* ]; relations [ #( #relation_args )* ]; #( #ecs_derives , )* );
+			
+			#merge
+			
+			#debug
+			
+			#equality
+			
+			#hierarchy
+			
+			#relations
+			
+			#tags
+			
+			#snapshot
+			
+			#parallel
+			
+			#assert
 		});
 	}
 }
@@ -126,34 +278,155 @@ impl ToTokens for CompArray {
 
 #[ derive( Clone ) ]
 pub struct IdentTypePair {
+	attrs: Vec< syn::Attribute >,
 	ident: syn::Ident,
 	of_type: syn::Type,
+	flags: CompFlags,
 }
 
 impl IdentTypePair {
 	pub const fn new( ident: syn::Ident, of_type: syn::Type, ) -> Self {
 		IdentTypePair {
+			attrs: Vec::new(),
 			ident,
 			of_type,
+			flags: CompFlags::new(),
 		}
 	}
+	
+	pub const fn with_flags( mut self, flags: CompFlags ) -> Self {
+		self.flags = flags;
+		self
+	}
+	
+	/// Adds attributes forwarded to generated fields and accessors, fe. docs.
+	pub fn with_attrs( mut self, attrs: Vec< syn::Attribute > ) -> Self {
+		self.attrs = attrs;
+		self
+	}
 }
 
 impl Parse for IdentTypePair {
 	fn parse( input: ParseStream ) -> syn::Result<Self> {
+		let flags = CompFlags::parse( input )?;
 		let ident = syn::Ident::parse( input )?;
 		_ = input.parse::< Token![:] >()?;
 		let of_type = syn::Type::parse( input )?;
 		
 		Ok(
 			Self {
+				attrs: Vec::new(),
 				ident,
 				of_type,
+				flags,
 			}
 		)
 	}
 }
 
+/// Parses a single entry of `types [...]`, fe. `#[transient] f64`.
+fn parse_generic_type( input: ParseStream ) -> syn::Result< ( CompFlags, Type ) > {
+	let flags = CompFlags::parse( input )?;
+	Ok(( flags, syn::Type::parse( input )? ))
+}
+
+//------------------------------------------------------------------------------
+
+/// Single entry of `relations [...]`, fe. `Owns( u32 )`.
+#[ derive( Clone ) ]
+pub struct RelationDecl {
+	ident: syn::Ident,
+	data: Option< Type >,
+	field: syn::Ident,
+}
+
+impl RelationDecl {
+	/// Names the field of the ECS storing edges of this relation.
+	fn with_field( mut self, idx: usize ) -> Self {
+		self.field = syn::Ident::new( &format!( "relation_{idx}" ), Span::mixed_site() );
+		self
+	}
+	
+	/// Returns the name under which edges are stored in a snapshot, fe. `@Owns`.
+	fn key( &self ) -> String {
+		format!( "@{}", self.ident )
+	}
+}
+
+impl Parse for RelationDecl {
+	fn parse( input: ParseStream ) -> syn::Result<Self> {
+		let ident = syn::Ident::parse( input )?;
+		let data = if input.peek( syn::token::Paren ) {
+			let inner;
+			_ = syn::parenthesized!( inner in input );
+			Some( Type::parse( &inner )? )
+		} else {
+			None
+		};
+		
+		Ok( Self {
+			field: ident.clone(),
+			ident,
+			data,
+		})
+	}
+}
+
+impl From< &RelationDecl > for MinEcsMacroArg {
+	fn from( value: &RelationDecl ) -> Self {
+		let field = value.field.clone();
+		let ident = value.ident.clone();
+		
+		Self {
+			tokens: quote! {
+				,#field: #ident
+			},
+		}
+	}
+}
+
+//------------------------------------------------------------------------------
+
+/// Optional attributes of a single component.
+#[ derive( Clone, Copy, Default ) ]
+pub struct CompFlags {
+	/// `#[transient]` - runtime-only, skipped by snapshots.
+	pub transient: bool,
+	/// `#[map_entities]` - implements `MapEntities`, mapped when merging worlds.
+	pub map_entities: bool,
+}
+
+impl CompFlags {
+	pub const fn new() -> Self {
+		Self {
+			transient: false,
+			map_entities: false,
+		}
+	}
+}
+
+impl Parse for CompFlags {
+	fn parse( input: ParseStream ) -> syn::Result<Self> {
+		let mut flags = Self::new();
+		
+		while input.peek( Token![#] ) {
+			_ = input.parse::< Token![#] >()?;
+			let inner;
+			_ = syn::bracketed!( inner in input );
+			
+			if kw::transient::parse( &inner ).is_ok() {
+				flags.transient = true;
+			} else if kw::map_entities::parse( &inner ).is_ok() {
+				flags.map_entities = true;
+			} else {
+				return Err( inner.error( "invalid attribute: expected `transient` or `map_entities`" ))
+			}
+		}
+		
+		Ok( flags )
+	}
+}
+
 impl From< &IdentTypePair > for syn::Ident {
 	fn from( value: &IdentTypePair ) -> Self {
 		value.ident.clone()
@@ -174,10 +447,12 @@ pub struct EntityArg {
 
 impl From< &IdentTypePair > for EntityArg {
 	fn from( value: &IdentTypePair ) -> Self {
+		let attrs = &value.attrs;
 		let ident = value.ident.clone();
 		let ty = value.of_type.clone();
 		
 		let tokens = quote! {
+			#( #attrs )*
 			#ident: Option< CompId< #ty, Self >>,
 		};
 		
@@ -201,11 +476,12 @@ pub struct MinEcsMacroArg {
 
 impl From< &IdentTypePair > for MinEcsMacroArg {
 	fn from( value: &IdentTypePair ) -> Self {
+		let attrs = &value.attrs;
 		let ident = value.ident.clone();
 		let ty = value.of_type.clone();
 		
 		let tokens = quote! {
-			,#ident: #ty
+			,#( #attrs )* #ident: #ty
 		};
 		
 		Self {
@@ -223,12 +499,46 @@ impl ToTokens for MinEcsMacroArg {
 //------------------------------------------------------------------------------
 
 #[derive( Clone )]
-enum DeriveType {
+pub enum DeriveType {
 	Ident ( syn::Ident ),
 	Path ( syn::Path ),
 }
 
 impl DeriveType {
+	/// Returns the name of the derive, fe. `Serialize` for `serde::Serialize`.
+	fn name ( &self ) -> Option< &syn::Ident > {
+		match *self {
+			Self::Ident( ref ident ) => Some( ident ),
+			Self::Path( ref path ) => path.segments.last().map( |seg| &seg.ident ),
+		}
+	}
+	
+	/// Adds user supplied derives to the ones returned by `new_vec`, skipping those already present.
+	/// 
+	/// # Errors
+	/// 
+	/// when a derive cannot be implemented for generated types, fe. `Default`
+	pub fn extend_vec< I: IntoIterator< Item = DeriveType >> ( derives: &mut Vec< DeriveType >, list: I ) -> syn::Result< () > {
+		for derive in list {
+			if let Some( name ) = derive.name() {
+				if name == "Debug" || name == "Clone" || name == "PartialEq" {
+					continue
+				}
+				if name == "Default" || name == "Copy" {
+					return Err( syn::Error::new( name.span(), format!( "`{name}` cannot be derived for the ecs" )))
+				}
+			}
+			derives.push( derive );
+		}
+		
+		Ok(())
+	}
+	
+	/// Returns `true` for `PartialEq`, implemented by hand for the ecs, as its observers are not compared.
+	fn is_comparison ( &self ) -> bool {
+		self.name().is_some_and( |name| name == "PartialEq" )
+	}
+	
 	pub fn new_vec () -> Vec< DeriveType > {
 		let debug = Self::Ident( syn::Ident::new( "Debug", Span::mixed_site()));
 		let clone = Self::Ident( syn::Ident::new( "Clone", Span::mixed_site()));
@@ -262,6 +572,8 @@ impl ToTokens for DeriveType {
 
 pub struct MinEcsEntity {
 	derives: Vec< DeriveType >,
+	vis: syn::Visibility,
+	attrs: Vec< syn::Attribute >,
 	entity_name: syn::Ident,
 	
 	named_pairs: Vec< IdentTypePair >,
@@ -272,36 +584,23 @@ pub struct MinEcsEntity {
 impl From< &CompArray > for MinEcsEntity {
 	fn from( value: &CompArray ) -> Self {
 		let CompArray {
+			vis,
+			entity_attrs,
 			entity_name,
-			generic_types,
+			generic_comps,
 			named_comps,
 			derives,
 			..
 		} = &value;
 		
-		let mut generic_pairs = Vec::new();
-		let mut named_pairs = Vec::new();
-		
-		let mut s_ident = String::new();
-		let mut ident;
-		for ( idx, t ) in generic_types.iter().enumerate() {
-			_ = write!( &mut s_ident, "type_{idx}" );
-			ident = syn::Ident::new( &s_ident, Span::mixed_site() );
-			s_ident.clear();
-			
-			generic_pairs.push( IdentTypePair::new( ident.clone(), t.clone() ) );
-		}
-		
-		for pair in named_comps {
-			named_pairs.push( pair.clone() );
-		}
-		
 		MinEcsEntity {
 			derives: derives.clone(),
+			vis: vis.clone(),
+			attrs: entity_attrs.clone(),
 			entity_name: entity_name.clone(),
 			
-			generic_pairs,
-			named_pairs,
+			generic_pairs: generic_comps.clone(),
+			named_pairs: named_comps.clone(),
 		}
 	}
 }
@@ -311,6 +610,8 @@ impl ToTokens for MinEcsEntity {
 	fn to_tokens( &self, tokens: &mut TokenStream2 ) {
 		let MinEcsEntity {
 			derives,
+			vis,
+			attrs,
 			entity_name,
 			
 			named_pairs,
@@ -325,7 +626,8 @@ impl ToTokens for MinEcsEntity {
 		
 		tokens.extend( quote! {
 			#[derive( #( #derives , )* )]
-			pub struct #entity_name {
+			#( #attrs )*
+			#vis struct #entity_name {
 				#(
 					#entity_fields
 				)*
@@ -421,7 +723,7 @@ impl ToTokens for MinEcsEntity {
 			});
 		}
 		// */
-		
+	
 	}
 }
 
@@ -429,21 +731,25 @@ impl ToTokens for MinEcsEntity {
 
 pub struct MinEcsCa {
 	derives: Vec< DeriveType >,
+	vis: syn::Visibility,
 	ca_name: syn::Ident,
 	entity_name: syn::Ident,
 	
 	component_names: Vec< syn::Ident >,
 	recycle_names: Vec< syn::Ident >,
 	fld_types: Vec< syn::Type >,
+	parallel: bool,
 }
 
 impl From< &CompArray > for MinEcsCa {
 	fn from( value: &CompArray ) -> Self {
 		let CompArray {
+			vis,
 			ca_name,
 			entity_name,
 			all_types,
 			derives,
+			parallel,
 			..
 		} = &value;
 		
@@ -468,12 +774,14 @@ impl From< &CompArray > for MinEcsCa {
 		
 		MinEcsCa {
 			derives: derives.clone(),
+			vis: vis.clone(),
 			ca_name: ca_name.clone(),
 			entity_name: entity_name.clone(),
 			
 			component_names,
 			recycle_names,
 			fld_types: all_types.clone(),
+			parallel: *parallel,
 		}
 	}
 }
@@ -483,17 +791,19 @@ impl ToTokens for MinEcsCa {
 	fn to_tokens( &self, tokens: &mut TokenStream2 ) {
 		let MinEcsCa {
 			derives,
+			vis,
 			ca_name,
 			entity_name,
 			
 			component_names,
 			recycle_names,
 			fld_types,
+			..
 		} = self;
 		
 		tokens.extend( quote! {
 			#[derive( #( #derives , )* )]
-			pub struct #ca_name {
+			#vis struct #ca_name {
 				#(
 					#component_names: Vec< Component< #fld_types, #entity_name >>,
 				)*
@@ -529,6 +839,51 @@ impl ToTokens for MinEcsCa {
 			}
 		});
 		
+		self.fn_tokens( tokens );
+		self.pair_tokens( tokens );
+		if self.parallel {
+			self.columns_tokens( tokens );
+		}
+	}
+}
+
+impl MinEcsCa {
+	/// Implements `CompColumns`, splitting storage into a column per component type.
+	fn columns_tokens( &self, tokens: &mut TokenStream2 ) {
+		let MinEcsCa {
+			ref ca_name,
+			ref entity_name,
+			
+			ref component_names,
+			ref recycle_names,
+			ref fld_types,
+			..
+		} = *self;
+		
+		tokens.extend( quote! {
+			impl CompColumns for #ca_name {
+				fn columns_mut ( &mut self ) -> Vec< Column< '_ > > {
+					vec![
+						#( Column::component::< #fld_types, #entity_name >( &mut self.#component_names, &self.#recycle_names ), )*
+					]
+				}
+			}
+		});
+	}
+	
+	/// Implements `CompVecFn` for every component type.
+	#[allow( clippy::min_ident_chars )]
+	fn fn_tokens( &self, tokens: &mut TokenStream2 ) {
+		let MinEcsCa {
+			ref ca_name,
+			ref entity_name,
+			
+			ref component_names,
+			ref recycle_names,
+			ref fld_types,
+			..
+		} = *self;
+		
 		let tmp_iter = component_names.iter()
 			.zip( recycle_names.iter() )
 			.zip( fld_types.iter() );
@@ -554,11 +909,12 @@ impl ToTokens for MinEcsCa {
 					
 					fn remove ( &mut self, id: CompId< #t, #entity_name > ) -> Result< (), EcsErr > {
 						let idx = usize::from( id );
-						if self.#ident.len() < idx {
-							return Err( EcsErr::NoSuchCompId( idx ) )
-						} else if !self.#ident_recycle.contains( &idx ) {
-							self.#ident_recycle.push( idx );
+						if self.#ident.len() <= idx {
+							return Err( EcsErr::NoSuchCompId( CompName::of::< #t >(), idx ) )
+						} else if self.#ident_recycle.contains( &idx ) {
+							return Err( EcsErr::DoubleRemoval( CompName::of::< #t >(), idx ) )
 						}
+						self.#ident_recycle.push( idx );
 						Ok(())
 					}
 					
@@ -579,22 +935,1065 @@ impl ToTokens for MinEcsCa {
 						self.#ident.len()
 					}
 					
-					fn iter ( &self ) -> CompIter< #t, #entity_name > {
+					fn iter ( &self ) -> CompIter< '_, #t, #entity_name > {
 						self.#ident.iter().into()
 					}
 					
-					fn iter_mut ( &mut self ) -> CompIterMut< #t, #entity_name > {
+					fn iter_mut ( &mut self ) -> CompIterMut< '_, #t, #entity_name > {
 						self.#ident.iter_mut().into()
 					}
+					
+					fn get_many_mut< const N: usize > ( &mut self, ids: [CompId< #t, #entity_name >; N] ) -> Result< [&mut Component< #t, #entity_name >; N], EcsErr > {
+						let indices = ids.map( usize::from );
+						if let Some( idx ) = indices.iter().find( |idx| self.#ident_recycle.contains( idx ) ) {
+							return Err( EcsErr::NoSuchCompId( CompName::of::< #t >(), *idx ) )
+						}
+						
+						slice_get_many_mut( CompName::of::< #t >(), &mut self.#ident, indices )
+					}
 				}
 			});
 		}
 	}
+	
+	/// Implements `CompVecPair` for every ordered pair of distinct component types, splitting the borrow of their storages.
+	fn pair_tokens( &self, tokens: &mut TokenStream2 ) {
+		let MinEcsCa {
+			ref ca_name,
+			ref entity_name,
+			ref component_names,
+			ref fld_types,
+			..
+		} = *self;
+		
+		for ( idx_a, ( ident_a, t_a )) in component_names.iter().zip( fld_types.iter() ).enumerate() {
+			for ( idx_b, ( ident_b, t_b )) in component_names.iter().zip( fld_types.iter() ).enumerate() {
+				if idx_a == idx_b {
+					continue
+				}
+				
+				tokens.extend( quote! {
+					impl CompVecPair< #t_a, #t_b, #entity_name > for #ca_name {
+						#[allow( clippy::type_complexity )]
+						fn split_mut ( &mut self ) -> ( &mut [Component< #t_a, #entity_name >], &mut [Component< #t_b, #entity_name >] ) {
+							( self.#ident_a.as_mut_slice(), self.#ident_b.as_mut_slice() )
+						}
+					}
+				});
+			}
+		}
+	}
+}
+
+
+//------------------------------------------------------------------------------
+
+pub struct MinEcsSnapshot {
+	min_ecs_name: syn::Ident,
+	entity_name: syn::Ident,
+	
+	keys: Vec< String >,
+	fld_idents: Vec< syn::Ident >,
+	fld_types: Vec< syn::Type >,
+	fld_values: Vec< TokenStream2 >,
+	transient_keys: Vec< String >,
+	relation_keys: Vec< String >,
+	relation_fields: Vec< syn::Ident >,
+	tag_keys: Vec< String >,
+	tag_bits: Vec< u32 >,
+}
+
+impl From< &CompArray > for MinEcsSnapshot {
+	fn from( value: &CompArray ) -> Self {
+		let CompArray {
+			ref min_ecs_name,
+			ref entity_name,
+			ref generic_comps,
+			ref named_comps,
+			ref relations,
+			ref tags,
+			..
+		} = *value;
+		
+		let mut keys = Vec::new();
+		let mut fld_idents = Vec::new();
+		let mut fld_types = Vec::new();
+		let mut fld_values = Vec::new();
+		let mut transient_keys = Vec::new();
+		
+		for pair in generic_comps {
+			let ty = syn::Type::from( pair );
+			if pair.flags.transient {
+				transient_keys.push( type_key( &ty ));
+				continue
+			}
+			keys.push( type_key( &ty ));
+			fld_idents.push( syn::Ident::from( pair ));
+			fld_types.push( ty );
+			fld_values.push( snap_value( pair.flags.map_entities ));
+		}
+		
+		for pair in named_comps {
+			let ident = syn::Ident::from( pair );
+			if pair.flags.transient {
+				transient_keys.push( ident.to_string() );
+				continue
+			}
+			keys.push( ident.to_string() );
+			fld_idents.push( ident );
+			fld_types.push( syn::Type::from( pair ));
+			fld_values.push( snap_value( pair.flags.map_entities ));
+		}
+		
+		MinEcsSnapshot {
+			min_ecs_name: min_ecs_name.clone(),
+			entity_name: entity_name.clone(),
+			
+			keys,
+			fld_idents,
+			fld_types,
+			fld_values,
+			transient_keys,
+			relation_keys: relations.iter().map( RelationDecl::key ).collect(),
+			relation_fields: relations.iter().map( |decl| decl.field.clone() ).collect(),
+			tag_keys: tags.iter().map( |tag| format!( "#{tag}" )).collect(),
+			tag_bits: tag_bits( tags ),
+		}
+	}
+}
+
+/// Returns the bit of each tag in `TagSet`, in order of declaration.
+fn tag_bits( tags: &[syn::Ident] ) -> Vec< u32 > {
+	( 0..MAX_TAGS ).filter_map( |bit| u32::try_from( bit ).ok() ).take( tags.len() ).collect()
+}
+
+/// Returns the conversion of component `comp` into `SnapValue`, rewriting stored entity ids by `map` when `mapped`.
+fn snap_value( mapped: bool ) -> TokenStream2 {
+	if mapped {
+		quote!( SnapComp::to_snap( &MapEntities::mapped( comp.inner(), &map ))? )
+	} else {
+		quote!( SnapComp::to_snap( comp.inner() )? )
+	}
+}
+
+/// Returns the name under which generic component of type `ty` is stored in a snapshot, fe. `Vec<Points>`.
+fn type_key( ty: &Type ) -> String {
+	quote!( #ty ).to_string().chars().filter( |ch| !ch.is_whitespace() ).collect()
+}
+
+impl MinEcsSnapshot {
+	/// Generates `impl EcsPrefab`, converting all values before spawning the entity.
+	fn prefab_tokens( &self, tokens: &mut TokenStream2 ) {
+		let MinEcsSnapshot {
+			ref min_ecs_name,
+			ref entity_name,
+			
+			ref keys,
+			ref fld_idents,
+			ref fld_types,
+			..
+		} = *self;
+		
+		let locals: Vec< syn::Ident > = fld_idents.iter().map( |ident| format_ident!( "prefab_{}", ident )).collect();
+		
+		tokens.extend( quote! {
+			impl EcsPrefab< #entity_name > for #min_ecs_name {
+				fn spawn_prefab ( &mut self, prefab: &Prefab ) -> Result< EntityId< #entity_name >, SnapErr > {
+					#( let mut #locals: Option< #fld_types > = None; )*
+					
+					for entry in &prefab.components {
+						match entry.0.as_str() {
+							#(
+								#keys => #locals = Some(
+									< #fld_types as SnapComp >::from_snap( entry.1.clone() )
+										.map_err( |err| SnapErr::Value( format!( "prefab: {}, component: {}: {}", prefab.name, entry.0, err )))?
+								),
+							)*
+							_ => return Err( SnapErr::UnknownPrefabComponent {
+								prefab: prefab.name.clone(),
+								component: entry.0.clone(),
+								expected: vec![ #( #keys ),* ],
+							}),
+						}
+					}
+					
+					let id = self.new_entity();
+					#(
+						if let Some( item ) = #locals {
+							let comp_id = CompVecFn::insert( &mut self.components, Component::new( id, item ));
+							if let Some( entity ) = self.entities.get_mut( usize::from( id )) {
+								entity.#fld_idents = Some( comp_id );
+							}
+						}
+					)*
+					
+					Ok( id )
+				}
+			}
+		});
+	}
+}
+
+impl ToTokens for MinEcsSnapshot {
+	fn to_tokens( &self, tokens: &mut TokenStream2 ) {
+		let MinEcsSnapshot {
+			ref min_ecs_name,
+			
+			ref keys,
+			ref fld_idents,
+			ref fld_types,
+			ref fld_values,
+			ref transient_keys,
+			ref relation_keys,
+			ref relation_fields,
+			ref tag_keys,
+			ref tag_bits,
+			..
+		} = *self;
+		
+		self.prefab_tokens( tokens );
+		
+		tokens.extend( quote! {
+			impl EcsSnapshot for #min_ecs_name {
+				fn snapshot ( &self ) -> Result< Snapshot, SnapErr > {
+					// live entities receive consecutive ids, skipping despawned ones
+					let map = self.despawned.compact( self.entities.len() );
+					let mut snapshot = Snapshot::new( map.len() );
+					
+					#(
+						let mut section = SnapSection::new( #keys );
+						for ( old_idx, new_idx ) in map.iter() {
+							let Some( entity ) = self.entities.get( old_idx ) else {
+								continue
+							};
+							if let Some( comp ) = entity.#fld_idents.and_then( |cid| CompVecFn::get( &self.components, cid )) {
+								section.push( new_idx, #fld_values );
+							}
+						}
+						snapshot.components.push( section );
+					)*
+					
+					if let Some( section ) = self.hierarchy.to_section( &map )? {
+						snapshot.components.push( section );
+					}
+					#(
+						if let Some( section ) = self.#relation_fields.to_section( #relation_keys, &map )? {
+							snapshot.components.push( section );
+						}
+					)*
+					#(
+						if let Some( section ) = self.tags.to_section( #tag_keys, #tag_bits, &map ) {
+							snapshot.components.push( section );
+						}
+					)*
+					
+					Ok( snapshot )
+				}
+				
+				fn from_snapshot ( snapshot: Snapshot ) -> Result< Self, SnapErr > {
+					snapshot.check_format()?;
+					
+					let mut ecs = Self::new();
+					for _ in 0 .. snapshot.entities {
+						_ = ecs.new_entity();
+					}
+					
+					for section in snapshot.components {
+						match section.name.as_str() {
+							#(
+								#keys => for ( idx, value ) in section.entries {
+									let item = < #fld_types as SnapComp >::from_snap( value )?;
+									let entity = ecs.entities.get_mut( idx ).ok_or( SnapErr::NoSuchEntityId( idx ))?;
+									let comp_id = CompVecFn::insert( &mut ecs.components, Component::new( EntityId::from( idx ), item ));
+									entity.#fld_idents = Some( comp_id );
+								},
+							)*
+							Hierarchy::SNAPSHOT_KEY => ecs.hierarchy.load_section( section, snapshot.entities )?,
+							#( #relation_keys => ecs.#relation_fields.load_section( section, snapshot.entities )?, )*
+							#( #tag_keys => ecs.tags.load_section( section, #tag_bits, snapshot.entities )?, )*
+							// transient components, fe. saved before becoming transient, are left absent
+							#( #transient_keys => {}, )*
+							_ => return Err( SnapErr::UnknownComponent( section.name )),
+						}
+					}
+					
+					Ok( ecs )
+				}
+			}
+		});
+	}
+}
+
+//------------------------------------------------------------------------------
+
+pub struct MinEcsMerge {
+	min_ecs_name: syn::Ident,
+	entity_name: syn::Ident,
+	
+	fld_idents: Vec< syn::Ident >,
+	relation_fields: Vec< syn::Ident >,
+	mapped_idents: Vec< syn::Ident >,
+}
+
+impl From< &CompArray > for MinEcsMerge {
+	fn from( value: &CompArray ) -> Self {
+		let CompArray {
+			ref min_ecs_name,
+			ref entity_name,
+			ref generic_comps,
+			ref named_comps,
+			ref relations,
+			..
+		} = *value;
+		
+		let all_comps = || generic_comps.iter().chain( named_comps );
+		
+		MinEcsMerge {
+			min_ecs_name: min_ecs_name.clone(),
+			entity_name: entity_name.clone(),
+			
+			fld_idents: all_comps().map( syn::Ident::from ).collect(),
+			relation_fields: relations.iter().map( |decl| decl.field.clone() ).collect(),
+			mapped_idents: all_comps().filter( |pair| pair.flags.map_entities ).map( syn::Ident::from ).collect(),
+		}
+	}
+}
+
+impl ToTokens for MinEcsMerge {
+	fn to_tokens( &self, tokens: &mut TokenStream2 ) {
+		let MinEcsMerge {
+			ref min_ecs_name,
+			ref entity_name,
+			
+			ref fld_idents,
+			ref relation_fields,
+			ref mapped_idents,
+		} = *self;
+		
+		let map_tokens = ( !mapped_idents.is_empty() ).then( || quote! {
+			for ( _, new_idx ) in map.iter() {
+				let Some( entity ) = self.entities.get( new_idx ) else {
+					continue
+				};
+				
+				#(
+					if let Some( comp ) = entity.#mapped_idents.and_then( |cid| CompVecFn::get_mut( &mut self.components, cid )) {
+						MapEntities::map_entities( comp.inner_mut(), &map );
+					}
+				)*
+			}
+		});
+		
+		tokens.extend( quote! {
+			impl EcsMerge for #min_ecs_name {
+				fn merge ( &mut self, other: &Self ) -> EntityMap {
+					let mut map = EntityMap::new();
+					
+					for ( old_idx, other_entity ) in other.entities.iter().enumerate() {
+						// despawned entities are not copied, their ids are absent from the map
+						if other.despawned.contains( old_idx ) {
+							continue
+						}
+						
+						let id = self.new_entity();
+						_ = map.insert( old_idx, usize::from( id ));
+						self.tags.set( usize::from( id ), other.tags.get( old_idx ));
+						
+						#(
+							if let Some( comp ) = other_entity.#fld_idents.and_then( |cid| CompVecFn::get( &other.components, cid )) {
+								let comp_id = CompVecFn::insert( &mut self.components, Component::new( id, Clone::clone( comp.inner() )));
+								if let Some( entity ) = self.entities.get_mut( usize::from( id )) {
+									entity.#fld_idents = Some( comp_id );
+								}
+							}
+						)*
+					}
+					
+					for old_parent in 0 .. other.entities.len() {
+						let Some( parent ) = map.get( EntityId::< #entity_name >::from( old_parent )) else {
+							continue
+						};
+						for old_child in other.hierarchy.children( EntityId::< #entity_name >::from( old_parent )) {
+							if let Some( child ) = map.get( old_child ) {
+								_ = self.hierarchy.set_parent( child, parent );
+							}
+						}
+					}
+					
+					#(
+						for ( old_source, old_target, data ) in other.#relation_fields.iter::< #entity_name >() {
+							if let ( Some( source ), Some( target )) = ( map.get( old_source ), map.get( old_target )) {
+								_ = self.#relation_fields.insert( source, target, Clone::clone( data ));
+							}
+						}
+					)*
+					
+					#map_tokens
+					
+					map
+				}
+			}
+		});
+	}
+}
+
+//------------------------------------------------------------------------------
+
+pub struct MinEcsDebug {
+	min_ecs_name: syn::Ident,
+	ca_name: syn::Ident,
+	entity_name: syn::Ident,
+	
+	labels: Vec< TokenStream2 >,
+	names: Vec< TokenStream2 >,
+	fld_idents: Vec< syn::Ident >,
+	fld_types: Vec< syn::Type >,
+	
+	storage_types: Vec< syn::Type >,
+	storage_names: Vec< TokenStream2 >,
+	storage_fields: Vec< Vec< syn::Ident > >,
+}
+
+impl From< &CompArray > for MinEcsDebug {
+	fn from( value: &CompArray ) -> Self {
+		let CompArray {
+			ref min_ecs_name,
+			ref ca_name,
+			ref entity_name,
+			ref generic_comps,
+			ref named_comps,
+			..
+		} = *value;
+		
+		let generic_labels = generic_comps.iter().map( |pair| {
+			let ty = syn::Type::from( pair );
+			quote!( std::any::type_name::< #ty >() )
+		});
+		let named_labels = named_comps.iter().map( |pair| {
+			let label = syn::Ident::from( pair ).to_string();
+			quote!( #label )
+		});
+		
+		// fields of the same type share one storage, it is checked once against all of them
+		let mut storages: Vec< ( syn::Type, Vec< syn::Ident > ) > = Vec::new();
+		for pair in generic_comps.iter().chain( named_comps ) {
+			let ty = syn::Type::from( pair );
+			match storages.iter_mut().find( |storage| storage.0 == ty ) {
+				Some( storage ) => storage.1.push( syn::Ident::from( pair )),
+				None => storages.push(( ty, vec![ syn::Ident::from( pair ) ] )),
+			}
+		}
+		let storage_names = storages.iter().map( |storage| {
+			let ty = &storage.0;
+			match *storage.1.as_slice() {
+				[ ref field ] if named_comps.iter().any( |pair| syn::Ident::from( pair ) == *field ) => {
+					let label = field.to_string();
+					quote!( CompName::of::< #ty >().with_field( #label ) )
+				}
+				_ => quote!( CompName::of::< #ty >() ),
+			}
+		}).collect();
+		
+		MinEcsDebug {
+			min_ecs_name: min_ecs_name.clone(),
+			ca_name: ca_name.clone(),
+			entity_name: entity_name.clone(),
+			
+			labels: generic_labels.chain( named_labels ).collect(),
+			names: comp_names( generic_comps, named_comps ),
+			fld_idents: generic_comps.iter().chain( named_comps ).map( syn::Ident::from ).collect(),
+			fld_types: generic_comps.iter().chain( named_comps ).map( syn::Type::from ).collect(),
+			
+			storage_names,
+			storage_types: storages.iter().map( |storage| storage.0.clone() ).collect(),
+			storage_fields: storages.into_iter().map( |storage| storage.1 ).collect(),
+		}
+	}
+}
+
+/// Returns expressions creating `CompName` of every component, generic ones first.
+fn comp_names( generic_comps: &[IdentTypePair], named_comps: &[IdentTypePair] ) -> Vec< TokenStream2 > {
+	let generic_names = generic_comps.iter().map( |pair| {
+		let ty = syn::Type::from( pair );
+		quote!( CompName::of::< #ty >() )
+	});
+	let named_names = named_comps.iter().map( |pair| {
+		let ty = syn::Type::from( pair );
+		let label = syn::Ident::from( pair ).to_string();
+		quote!( CompName::of::< #ty >().with_field( #label ) )
+	});
+	
+	generic_names.chain( named_names ).collect()
+}
+
+impl ToTokens for MinEcsDebug {
+	fn to_tokens( &self, tokens: &mut TokenStream2 ) {
+		let MinEcsDebug {
+			ref min_ecs_name,
+			
+			ref labels,
+			ref fld_idents,
+			ref fld_types,
+			..
+		} = *self;
+		
+		tokens.extend( quote! {
+			impl EcsDump for #min_ecs_name {
+				fn dump_with ( &self, options: &DumpOptions ) -> String {
+					let live = self.entities.len() - self.despawned.as_slice().len();
+					let mut writer = DumpWriter::new( stringify!( #min_ecs_name ), live, options );
+					let mut components: Vec< ( &str, String ) > = Vec::new();
+					
+					for ( idx, entity ) in self.entities.iter().enumerate() {
+						if !options.shows_entity( idx ) || self.despawned.contains( idx ) {
+							continue
+						}
+						if writer.is_full() {
+							writer.skip( false #( || entity.#fld_idents.is_some() && options.shows_component( #labels, std::any::type_name::< #fld_types >() ) )* );
+							continue
+						}
+						
+						components.clear();
+						#(
+							if options.shows_component( #labels, std::any::type_name::< #fld_types >() ) {
+								if let Some( comp ) = entity.#fld_idents.and_then( |cid| CompVecFn::get( &self.components, cid )) {
+									components.push(( #labels, format!( "{:?}", comp.inner() )));
+								}
+							}
+						)*
+						writer.entity( idx, &components );
+					}
+					
+					writer.finish()
+				}
+			}
+		});
+		
+		self.validate_tokens( tokens );
+	}
+}
+
+impl MinEcsDebug {
+	/// Implements `EcsValidate`.
+	fn validate_tokens( &self, tokens: &mut TokenStream2 ) {
+		let MinEcsDebug {
+			ref min_ecs_name,
+			ref ca_name,
+			ref entity_name,
+			
+			ref names,
+			ref fld_idents,
+			
+			ref storage_types,
+			ref storage_names,
+			ref storage_fields,
+			..
+		} = *self;
+		
+		tokens.extend( quote! {
+			impl EcsValidate for #min_ecs_name {
+				fn validate ( &self ) -> Result< (), Vec< EcsErr > > {
+					let mut errors = Vec::new();
+					
+					for ( idx, entity ) in self.entities.iter().enumerate() {
+						#(
+							if let Some( cid ) = entity.#fld_idents {
+								match CompVecFn::get( &self.components, cid ) {
+									None => errors.push( EcsErr::CompVecMissingComponent( #names, idx, usize::from( cid ))),
+									Some( comp ) if usize::from( comp.id() ) != idx => {
+										errors.push( EcsErr::ComponentOwnerMismatch( #names, idx, usize::from( cid ), usize::from( comp.id() )));
+									}
+									Some( _ ) => {}
+								}
+							}
+						)*
+					}
+					
+					#(
+						for c_idx in 0 .. < #ca_name as CompVecFn< #storage_types, #entity_name > >::len( &self.components, std::marker::PhantomData ) {
+							let cid = CompId::< #storage_types, #entity_name >::new( c_idx );
+							if let Some( comp ) = CompVecFn::get( &self.components, cid ) {
+								let owner = usize::from( comp.id() );
+								// owned when any field of this type in the owner points at it
+								if self.entities.get( owner ).map_or( true, |entity| #( entity.#storage_fields != Some( cid ) )&&* ) {
+									errors.push( EcsErr::OrphanComponent( #storage_names, c_idx, owner ));
+								}
+							}
+						}
+					)*
+					
+					if errors.is_empty() { Ok(()) } else { Err( errors ) }
+				}
+			}
+		});
+	}
+}
+//------------------------------------------------------------------------------
+
+/// Comparison of the ecs by value: observers are ignored, and components are compared per live entity,
+/// regardless of the slots storing them.
+pub struct MinEcsEq {
+	min_ecs_name: syn::Ident,
+	
+	fld_idents: Vec< syn::Ident >,
+	fld_values: Vec< TokenStream2 >,
+	other_values: Vec< TokenStream2 >,
+	relation_fields: Vec< syn::Ident >,
+	resource_idents: Vec< syn::Ident >,
+}
+
+impl From< &CompArray > for MinEcsEq {
+	fn from( value: &CompArray ) -> Self {
+		let CompArray {
+			ref min_ecs_name,
+			ref generic_comps,
+			ref named_comps,
+			ref resources,
+			ref relations,
+			..
+		} = *value;
+		
+		let all_comps = || generic_comps.iter().chain( named_comps );
+		let compared = |pair: &IdentTypePair, map: TokenStream2| if pair.flags.map_entities {
+			quote!( |comp| MapEntities::mapped( comp.inner(), &#map ))
+		} else {
+			quote!( Component::inner )
+		};
+		
+		MinEcsEq {
+			min_ecs_name: min_ecs_name.clone(),
+			
+			fld_idents: all_comps().map( syn::Ident::from ).collect(),
+			fld_values: all_comps().map( |pair| compared( pair, quote!( map ))).collect(),
+			other_values: all_comps().map( |pair| compared( pair, quote!( other_map ))).collect(),
+			relation_fields: relations.iter().map( |decl| decl.field.clone() ).collect(),
+			resource_idents: resources.iter().map( syn::Ident::from ).collect(),
+		}
+	}
+}
+
+impl ToTokens for MinEcsEq {
+	fn to_tokens( &self, tokens: &mut TokenStream2 ) {
+		let MinEcsEq {
+			ref min_ecs_name,
+			
+			ref fld_idents,
+			ref fld_values,
+			ref other_values,
+			ref relation_fields,
+			ref resource_idents,
+		} = *self;
+		
+		tokens.extend( quote! {
+			impl PartialEq for #min_ecs_name {
+				fn eq ( &self, other: &Self ) -> bool {
+					// live entities are compared in order under consecutive ids, the same way they are saved in snapshots
+					let map = self.despawned.compact( self.entities.len() );
+					let other_map = other.despawned.compact( other.entities.len() );
+					
+					map.len() == other_map.len()
+						&& self.hierarchy.eq_mapped( &map, &other.hierarchy, &other_map )
+						&& self.tags.eq_mapped( &map, &other.tags, &other_map )
+						#( && self.#relation_fields.eq_mapped( &map, &other.#relation_fields, &other_map ) )*
+						#( && self.#resource_idents == other.#resource_idents )*
+						&& map.iter().zip( other_map.iter() ).all( |( pair, other_pair )| {
+							let ( Some( entity ), Some( other_entity )) = ( self.entities.get( pair.0 ), other.entities.get( other_pair.0 )) else {
+								return false
+							};
+							true #(
+								&& entity.#fld_idents.and_then( |cid| CompVecFn::get( &self.components, cid )).map( #fld_values )
+									== other_entity.#fld_idents.and_then( |cid| CompVecFn::get( &other.components, cid )).map( #other_values )
+							)*
+						})
+				}
+			}
+		});
+	}
+}
+
+//------------------------------------------------------------------------------
+
+pub struct MinEcsHierarchy {
+	min_ecs_name: syn::Ident,
+	entity_name: syn::Ident,
+	
+	names: Vec< TokenStream2 >,
+	fld_idents: Vec< syn::Ident >,
+	relation_fields: Vec< syn::Ident >,
+}
+
+impl From< &CompArray > for MinEcsHierarchy {
+	fn from( value: &CompArray ) -> Self {
+		let CompArray {
+			ref min_ecs_name,
+			ref entity_name,
+			ref generic_comps,
+			ref named_comps,
+			ref relations,
+			..
+		} = *value;
+		
+		MinEcsHierarchy {
+			min_ecs_name: min_ecs_name.clone(),
+			entity_name: entity_name.clone(),
+			
+			names: comp_names( generic_comps, named_comps ),
+			fld_idents: generic_comps.iter().chain( named_comps ).map( syn::Ident::from ).collect(),
+			relation_fields: relations.iter().map( |decl| decl.field.clone() ).collect(),
+		}
+	}
+}
+
+impl ToTokens for MinEcsHierarchy {
+	fn to_tokens( &self, tokens: &mut TokenStream2 ) {
+		let MinEcsHierarchy {
+			ref min_ecs_name,
+			ref entity_name,
+			
+			ref names,
+			ref fld_idents,
+			ref relation_fields,
+		} = *self;
+		
+		tokens.extend( quote! {
+			impl EcsHierarchy< #entity_name > for #min_ecs_name {
+				fn hierarchy ( &self ) -> &Hierarchy {
+					&self.hierarchy
+				}
+				
+				fn hierarchy_mut ( &mut self ) -> &mut Hierarchy {
+					&mut self.hierarchy
+				}
+				
+				#[allow( unused_variables )]
+				fn check_despawn ( &self, id: EntityId< #entity_name > ) -> Result< (), EcsErr > {
+					let idx = usize::from( id );
+					let entity = self.despawned.get( &self.entities, idx ).ok_or( EcsErr::NoSuchEntityId( idx ))?;
+					#(
+						if let Some( cid ) = entity.#fld_idents {
+							if CompVecFn::get( &self.components, cid ).is_none() {
+								return Err( EcsErr::CompVecMissingComponent( #names, idx, usize::from( cid )))
+							}
+						}
+					)*
+					Ok(())
+				}
+				
+				#[allow( unused_variables )]
+				fn despawn ( &mut self, id: EntityId< #entity_name > ) -> Result< (), EcsErr > {
+					// every component is checked first, so removing them cannot fail halfway
+					self.check_despawn( id )?;
+					
+					let idx = usize::from( id );
+					if let Some( entity ) = self.despawned.get_mut( &mut self.entities, idx ) {
+						#(
+							if let Some( cid ) = entity.#fld_idents.take() {
+								CompVecFn::remove( &mut self.components, cid )?;
+							}
+						)*
+					}
+					
+					self.hierarchy.detach( id );
+					self.observers.detach( id );
+					self.tags.detach( id );
+					#( self.#relation_fields.detach( id ); )*
+					_ = self.despawned.insert( idx );
+					Ok(())
+				}
+			}
+			
+			impl EcsObserve< #entity_name > for #min_ecs_name {
+				fn observers ( &self ) -> &Observers< Self, #entity_name > {
+					&self.observers
+				}
+				
+				fn observers_mut ( &mut self ) -> &mut Observers< Self, #entity_name > {
+					&mut self.observers
+				}
+			}
+		});
+	}
+}
+
+//------------------------------------------------------------------------------
+
+pub struct MinEcsRelations {
+	derives: Vec< DeriveType >,
+	min_ecs_name: syn::Ident,
+	entity_name: syn::Ident,
+	
+	relations: Vec< RelationDecl >,
+}
+
+impl From< &CompArray > for MinEcsRelations {
+	fn from( value: &CompArray ) -> Self {
+		let CompArray {
+			ref derives,
+			ref min_ecs_name,
+			ref entity_name,
+			ref relations,
+			..
+		} = *value;
+		
+		MinEcsRelations {
+			derives: derives.clone(),
+			min_ecs_name: min_ecs_name.clone(),
+			entity_name: entity_name.clone(),
+			
+			relations: relations.clone(),
+		}
+	}
+}
+
+impl ToTokens for MinEcsRelations {
+	fn to_tokens( &self, tokens: &mut TokenStream2 ) {
+		let MinEcsRelations {
+			ref derives,
+			ref min_ecs_name,
+			ref entity_name,
+			
+			ref relations,
+		} = *self;
+		
+		for decl in relations {
+			let RelationDecl {
+				ref ident,
+				ref data,
+				ref field,
+			} = *decl;
+			let data_type = data.as_ref().map_or_else( || quote!( () ), ToTokens::to_token_stream );
+			
+			tokens.extend( quote! {
+				#[derive( #( #derives , )* )]
+				pub struct #ident;
+				
+				impl Relation for #ident {
+					type Data = #data_type;
+				}
+				
+				impl EcsRelationFn< #ident > for #min_ecs_name {
+					fn relations ( &self ) -> &Relations< #ident > {
+						&self.#field
+					}
+					
+					fn relations_mut ( &mut self ) -> &mut Relations< #ident > {
+						&mut self.#field
+					}
+				}
+			});
+		}
+		
+		tokens.extend( quote! {
+			impl EcsRelation< #entity_name > for #min_ecs_name {}
+		});
+	}
+}
+
+//------------------------------------------------------------------------------
+
+pub struct MinEcsTags {
+	derives: Vec< DeriveType >,
+	min_ecs_name: syn::Ident,
+	entity_name: syn::Ident,
+	
+	tags: Vec< syn::Ident >,
+	bits: Vec< u32 >,
+}
+
+impl From< &CompArray > for MinEcsTags {
+	fn from( value: &CompArray ) -> Self {
+		let CompArray {
+			ref derives,
+			ref min_ecs_name,
+			ref entity_name,
+			ref tags,
+			..
+		} = *value;
+		
+		MinEcsTags {
+			derives: derives.clone(),
+			min_ecs_name: min_ecs_name.clone(),
+			entity_name: entity_name.clone(),
+			
+			tags: tags.clone(),
+			bits: tag_bits( tags ),
+		}
+	}
+}
+
+impl ToTokens for MinEcsTags {
+	fn to_tokens( &self, tokens: &mut TokenStream2 ) {
+		let MinEcsTags {
+			ref derives,
+			ref min_ecs_name,
+			ref entity_name,
+			
+			ref tags,
+			ref bits,
+		} = *self;
+		
+		for ( tag, bit ) in tags.iter().zip( bits ) {
+			tokens.extend( quote! {
+				#[derive( #( #derives , )* )]
+				pub struct #tag;
+				
+				impl EcsTagFn< #tag > for #min_ecs_name {
+					const BIT: u32 = #bit;
+				}
+			});
+		}
+		
+		tokens.extend( quote! {
+			impl EcsTag< #entity_name > for #min_ecs_name {
+				fn tags ( &self ) -> &Tags {
+					&self.tags
+				}
+				
+				fn tags_mut ( &mut self ) -> &mut Tags {
+					&mut self.tags
+				}
+				
+				fn tagged ( &self, filter: &TagFilter< Self > ) -> Tagged< '_, #entity_name > {
+					Tagged::new( self.tags.as_slice(), self.entities.len(), filter ).with_despawned( self.despawned.as_slice() )
+				}
+			}
+		});
+	}
+}
+
+//------------------------------------------------------------------------------
+
+pub struct MinEcsParallel {
+	min_ecs_name: syn::Ident,
+	entity_name: syn::Ident,
+	
+	resource_idents: Vec< syn::Ident >,
+}
+
+impl From< &CompArray > for MinEcsParallel {
+	fn from( value: &CompArray ) -> Self {
+		let CompArray {
+			ref min_ecs_name,
+			ref entity_name,
+			ref resources,
+			..
+		} = *value;
+		
+		MinEcsParallel {
+			min_ecs_name: min_ecs_name.clone(),
+			entity_name: entity_name.clone(),
+			
+			resource_idents: resources.iter().map( syn::Ident::from ).collect(),
+		}
+	}
+}
+
+impl ToTokens for MinEcsParallel {
+	fn to_tokens( &self, tokens: &mut TokenStream2 ) {
+		let MinEcsParallel {
+			ref min_ecs_name,
+			ref entity_name,
+			
+			ref resource_idents,
+		} = *self;
+		
+		tokens.extend( quote! {
+			impl EcsColumns< #entity_name > for #min_ecs_name {
+				fn columns_mut ( &mut self ) -> Columns< '_, #entity_name > {
+					let resources = vec![ #( Column::resource( &mut self.#resource_idents ), )* ];
+					Columns::new( &self.entities, self.components.columns_mut(), resources ).with_tags( self.tags.as_slice() ).with_despawned( self.despawned.as_slice() )
+				}
+			}
+		});
+	}
+}
+
+//------------------------------------------------------------------------------
+
+/// Compile-time assertions of auto traits of generated types.
+pub struct MinEcsAssert {
+	min_ecs_name: syn::Ident,
+	entity_name: syn::Ident,
+	
+	fld_types: Vec< syn::Type >,
+	resource_types: Vec< syn::Type >,
+	relation_types: Vec< syn::Ident >,
+	parallel: bool,
+}
+
+impl From< &CompArray > for MinEcsAssert {
+	fn from( value: &CompArray ) -> Self {
+		let CompArray {
+			ref min_ecs_name,
+			ref entity_name,
+			ref all_types,
+			ref resources,
+			ref relations,
+			parallel,
+			..
+		} = *value;
+		
+		MinEcsAssert {
+			min_ecs_name: min_ecs_name.clone(),
+			entity_name: entity_name.clone(),
+			
+			fld_types: all_types.clone(),
+			resource_types: resources.iter().map( syn::Type::from ).collect(),
+			relation_types: relations.iter().map( |decl| decl.ident.clone() ).collect(),
+			parallel,
+		}
+	}
+}
+
+impl ToTokens for MinEcsAssert {
+	fn to_tokens( &self, tokens: &mut TokenStream2 ) {
+		let MinEcsAssert {
+			ref min_ecs_name,
+			ref entity_name,
+			
+			ref fld_types,
+			ref resource_types,
+			ref relation_types,
+			parallel,
+		} = *self;
+		
+		// ids are always `Send + Sync`, the ECS only when its components, resources and relation data are, which `#[parallel]` requires
+		let ecs_assert = parallel.then( || quote! {
+			assert_send_sync::< #min_ecs_name >();
+		});
+		
+		tokens.extend( quote! {
+			const _: () = {
+				const fn assert_send_sync< X: Send + Sync > () {}
+				
+				assert_send_sync::< EntityId< #entity_name > >();
+				#( assert_send_sync::< CompId< #fld_types, #entity_name > >(); )*
+				#ecs_assert
+				
+				// higher-ranked bounds are not checked eagerly, so this holds for every ECS, assuming its components, resources and relation data are `Send + Sync`
+				#[allow( dead_code )]
+				fn assert_ecs_send_sync ()
+				where
+					#( for<'any> #fld_types: Send + Sync, )*
+					#( for<'any> #resource_types: Send + Sync, )*
+					#( for<'any> <#relation_types as Relation>::Data: Send + Sync, )*
+				{
+					assert_send_sync::< #min_ecs_name >();
+				}
+			};
+		});
+	}
 }
 
 //------------------------------------------------------------------------------
 
 mod kw {
 	syn::custom_keyword!( ecs );
+	syn::custom_keyword!( map_entities );
+	syn::custom_keyword!( parallel );
+	syn::custom_keyword!( relations );
+	syn::custom_keyword!( snapshot );
+	syn::custom_keyword!( tags );
+	syn::custom_keyword!( transient );
 	syn::custom_keyword!( types );
 }
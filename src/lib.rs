@@ -206,6 +206,8 @@
 //! 	1. angled braces surrounding two identifiers separated by a comma: component_array and entity,
 //! 1. curly braces `{}` surrounding component declarations ( either or both )
 //! 	- keyword `types` followed by square brackets `[]` surrounding comma separated list of not-repeating types; fe. `types [usize, f64]`,
+//! 	- keyword `relations` after `types`, followed by square brackets `[]` surrounding comma separated list of relation names, each optionally followed by the type of edge data in parentheses; fe. `relations [Targets, Owns(u32)]`,
+//! 	- keyword `tags` after `relations`, followed by square brackets `[]` surrounding comma separated list of up to 64 tag names; fe. `tags [Player, Dead]`,
 //! 	- comma separated field declarations, such as for struct, in form: identifier, colon, type; fe. `names: Vec< Rc< str >>`.
 //! */
 //! 
@@ -221,13 +223,21 @@
 //! // additional fields have dedicated insert methods
 //! ecs.insert_some_fld( entity_id_0, 422 );
 //! 
+//! // as well as accessors taking the entity id
+//! assert_eq!( ecs.some_fld( entity_id_0 ), Some( &422 ) );
+//! if let Some( val ) = ecs.some_fld_mut( entity_id_0 ) {
+//! 	*val += 1;
+//! }
+//! assert!( ecs.has_some_fld( entity_id_0 ) );
+//! assert_eq!( ecs.call_some_fld( entity_id_0, |val| val * 2 ), Some( 846 ) );
+//! 
 //! // iteration over &T
 //! for comp in ecs.iter::< f64 >() {
 //! 	println!( "{comp:#?}" );
 //! }
 //! 
 //! // accessing a specific entity
-//! let entity = ecs.borrow_entity( entity_id_0 ).unwrap();
+//! let entity = ecs.entity( entity_id_0 ).unwrap();
 //! 
 //! // accessing a generic component of the entity, component type specified in next line
 //! let comp_id = entity.get().unwrap();
@@ -237,6 +247,37 @@
 //! // without specifying type of the `comp` we'd need to change the previous line to:
 //! //`let comp_id: CompId< f64, TestEntity > = entity.get().unwrap();`
 //! 
+//! // components can also be accessed directly with the entity id
+//! assert_eq!( ecs.get_for::< f64 >( entity_id_0 ), Ok( &67.0 ) );
+//! assert!( ecs.has::< f64 >( entity_id_0 ) );
+//! assert_eq!( ecs.get_for::< usize >( entity_id_0 ), Err( EcsErr::EntityMissingComponent( CompName::of::< usize >(), 0 ) ) );
+//! 
+//! // several components can be borrowed mutably at once
+//! let entity_id_1 = ecs.new_entity();
+//! ecs.insert( entity_id_1, 33.0 );
+//! let comp_id_1 = ecs.entity( entity_id_1 ).unwrap().get().unwrap();
+//! let [ val_0, val_1 ] = ecs.get_many_mut( [ comp_id, comp_id_1 ] ).unwrap();
+//! std::mem::swap( val_0, val_1 );
+//! assert_eq!( ecs.get_many_mut( [ comp_id, comp_id ] ), Err( EcsErr::DuplicateCompId( CompName::of::< f64 >(), 0 ) ) );
+//! 
+//! // as well as two components of different types of the same entity
+//! ecs.insert( entity_id_1, 7_usize );
+//! let ( float, int ) = ecs.get2_mut::< f64, usize >( entity_id_1 ).unwrap();
+//! *float += *int as f64;
+//! assert_eq!( ecs.get_for::< f64 >( entity_id_1 ), Ok( &74.0 ) );
+//! 
+//! // components can be updated in place, or inserted if absent
+//! *ecs.entry::< usize >( entity_id_0 ).unwrap().or_insert( 0 ).unwrap() += 5;
+//! ecs.entry::< usize >( entity_id_0 ).unwrap().and_modify( |val| *val += 1 ).or_default().unwrap();
+//! assert_eq!( ecs.get_for::< usize >( entity_id_0 ), Ok( &6 ) );
+//! assert_eq!( ecs.iter::< usize >().count(), 3 ); // the existing component was reused
+//! ecs.entry_some_fld( entity_id_1 ).unwrap().or_insert_with( || 1 ).unwrap();
+//! 
+//! // named fields, which may share a type with other components, are borrowed by accessors of the entity
+//! let [ int, fld ] = ecs.get_fields_mut( entity_id_1, [ EntityFn::get, TestEntity::some_fld ] ).unwrap();
+//! std::mem::swap( int, fld );
+//! assert_eq!( ecs.some_fld( entity_id_1 ), Some( &7 ) );
+//! 
 //! // running systems for each entity; may be re-worked in the future.
 //! // currently requires function / closure which directly manipulates `&mut V: CompVec` and `&E: Entity`. Ecs runs such fn / closure for each entity.
 //!	ecs.run_system( |comp_vec, entity| {
@@ -248,14 +289,915 @@
 //! 		println!( "comp_vec.get( id ) = {:#?}", opt );
 //! 	}
 //! 	if let Some( id ) = entity.get() {
-//! 		let opt: Option< &f64 > = comp_vec.get( id ).map( Component::borrow );
-//! 		println!( "comp_vec.get( id ).map( Component::borrow ) = {:#?}", opt );
+//! 		let opt: Option< &f64 > = comp_vec.get( id ).map( Component::inner );
+//! 		println!( "comp_vec.get( id ).map( Component::inner ) = {:#?}", opt );
 //! 	}
 //! 	if let Some( id ) = entity.some_fld() {
-//! 		let val = comp_vec.get( id ).unwrap().borrow();
-//! 		println!( "comp_vec.get( id ).unwrap().borrow() = {val}" );
+//! 		let val = comp_vec.get( id ).unwrap().inner();
+//! 		println!( "comp_vec.get( id ).unwrap().inner() = {val}" );
+//! 	}
+//! });
+//! ```
+//! 
+//! # Snapshots
+//! 
+//! With `#[snapshot]` attribute the ecs implements `EcsSnapshot`, which saves the whole world into a `Snapshot` and restores it back.
+//! Components are stored in sections keyed by their name: field name for named components, type for `types [...]`.
+//! Every component has to implement `SnapComp`, which with `serde` feature is done for every `Serialize + DeserializeOwned` type.
+//! `Snapshot` itself implements `Serialize` and `Deserialize`, so any serde format can be used to write it to disk.
+//! ```
+//! use min_ecs::*;
+//! 
+//! minecs!(
+//! 	#[snapshot]
+//! 	ecs MinEcs< CompArray, TestEntity > {
+//! 		types [f64]
+//! 		name: String,
+//! 	}
+//! );
+//! 
+//! let mut ecs = MinEcs::new();
+//! let entity_id = ecs.new_entity();
+//! ecs.insert( entity_id, 1.5 );
+//! ecs.insert_name( entity_id, "Bob".to_owned() );
+//! _ = ecs.new_entity();
+//! 
+//! let mut snapshot = ecs.snapshot().unwrap();
+//! assert_eq!( snapshot.entities, 2 );
+//! assert!( snapshot.section( "f64" ).is_some() );
+//! assert_eq!( snapshot.section( "name" ).unwrap().entries, vec![( 0, SnapValue::Str( "Bob".to_owned() ))] );
+//! 
+//! // version is chosen by the user, fe. to tell apart snapshots made by older builds
+//! snapshot.version = 1;
+//! let loaded = MinEcs::from_snapshot( snapshot.clone() ).unwrap();
+//! assert_eq!( loaded, ecs );
+//! 
+//! // sections not known to the ecs are reported
+//! snapshot.components.push( SnapSection::new( "armor" ));
+//! assert_eq!( MinEcs::from_snapshot( snapshot ), Err( SnapErr::UnknownComponent( "armor".to_owned() )));
+//! ```
+//! 
+//! Snapshots can be stored in a compact binary form, without any extra dependencies: varint-encoded, one section per component with the shape of its values and field names stored once, and a checksum.
+//! Decoding checks lengths against the remaining input, and both directions limit nesting to `BinReader::MAX_DEPTH`, so `to_bytes` fails rather than produce bytes `from_bytes` rejects.
+//! Components may provide their own encoding through `BinCodec`, and store it as `SnapValue::Bytes`.
+//! ```
+//! use min_ecs::*;
+//! 
+//! #[derive( Debug, Clone, PartialEq )]
+//! pub struct Packed( u16 );
+//! 
+//! impl BinCodec for Packed {
+//! 	fn encode ( &self, out: &mut Vec< u8 > ) {
+//! 		self.0.encode( out );
+//! 	}
+//! 	
+//! 	fn decode ( reader: &mut BinReader<'_> ) -> Result< Self, SnapErr > {
+//! 		u16::decode( reader ).map( Packed )
+//! 	}
+//! }
+//! 
+//! impl SnapComp for Packed {
+//! 	fn to_snap ( &self ) -> Result< SnapValue, SnapErr > {
+//! 		Ok( self.to_snap_bytes() )
+//! 	}
+//! 	
+//! 	fn from_snap ( value: SnapValue ) -> Result< Self, SnapErr > {
+//! 		Self::from_snap_bytes( value )
+//! 	}
+//! }
+//! 
+//! minecs!(
+//! 	#[snapshot]
+//! 	ecs MinEcs< CompArray, TestEntity > {
+//! 		types [ f64, Packed ]
+//! 	}
+//! );
+//! 
+//! let mut ecs = MinEcs::new();
+//! let entity_id = ecs.new_entity();
+//! ecs.insert( entity_id, 1.5 );
+//! ecs.insert( entity_id, Packed( 3 ));
+//! 
+//! let mut bytes = ecs.snapshot().unwrap().to_bytes().unwrap();
+//! let loaded = MinEcs::from_snapshot( Snapshot::from_bytes( &bytes ).unwrap() ).unwrap();
+//! assert_eq!( loaded, ecs );
+//! 
+//! // corrupted data is detected
+//! if let Some( byte ) = bytes.get_mut( 20 ) {
+//! 	*byte ^= 1;
+//! }
+//! assert_eq!( Snapshot::from_bytes( &bytes ), Err( SnapErr::ChecksumMismatch ));
+//! ```
+//! 
+//! To store only what changed, fe. for autosaves, a `SnapDelta` between two snapshots lists spawned and despawned entities,
+//! and inserted, removed and modified components. Applying it to the older snapshot reproduces the newer one.
+//! ```
+//! use min_ecs::*;
+//! 
+//! minecs!(
+//! 	#[snapshot]
+//! 	ecs MinEcs< CompArray, TestEntity > {
+//! 		types [f64]
+//! 		name: String,
+//! 	}
+//! );
+//! 
+//! let mut ecs = MinEcs::new();
+//! let entity_0 = ecs.new_entity();
+//! let entity_1 = ecs.new_entity();
+//! ecs.insert( entity_0, 1.5 );
+//! ecs.insert_name( entity_1, "Bob".to_owned() );
+//! let old = ecs.snapshot().unwrap();
+//! 
+//! let entity_2 = ecs.new_entity();
+//! ecs.insert_name( entity_2, "Alice".to_owned() );
+//! *ecs.get_for_mut::< f64 >( entity_0 ).unwrap() += 1.0;
+//! ecs.remove_name( entity_1 ).unwrap();
+//! let new = ecs.snapshot().unwrap();
+//! 
+//! let delta = old.delta( &new );
+//! assert_eq!( delta.spawned(), 2 .. 3 );
+//! assert_eq!( delta.components.len(), 2 );
+//! 
+//! let mut applied = old.clone();
+//! applied.apply_delta( &delta ).unwrap();
+//! assert_eq!( applied, new );
+//! assert_eq!( MinEcs::from_snapshot( applied ), Ok( ecs ));
+//! 
+//! // a delta only applies to the snapshot it was created from
+//! let mut other = MinEcs::new().snapshot().unwrap();
+//! assert_eq!( other.apply_delta( &delta ), Err( SnapErr::DeltaMismatch ));
+//! ```
+//! 
+//! Runtime-only components, fe. caches or render handles, can be marked `#[transient]`. Those are skipped on save and left absent on load,
+//! so they do not need to implement `SnapComp`.
+//! ```
+//! use min_ecs::*;
+//! 
+//! #[derive( Debug, Clone, PartialEq )]
+//! pub struct RenderHandle( u32 ); // not serializable
+//! 
+//! minecs!(
+//! 	#[snapshot]
+//! 	ecs MinEcs< CompArray, TestEntity > {
+//! 		types [ f64, #[transient] RenderHandle ]
+//! 		#[transient] cached_path: Vec< usize >,
+//! 	}
+//! );
+//! 
+//! let mut ecs = MinEcs::new();
+//! let entity_id = ecs.new_entity();
+//! ecs.insert( entity_id, 1.5 );
+//! ecs.insert( entity_id, RenderHandle( 7 ));
+//! ecs.insert_cached_path( entity_id, vec![ 1, 2 ] );
+//! 
+//! let snapshot = ecs.snapshot().unwrap();
+//! assert_eq!( snapshot.components.len(), 1 );
+//! 
+//! let loaded = MinEcs::from_snapshot( snapshot ).unwrap();
+//! assert!( loaded.has::< f64 >( entity_id ));
+//! assert!( !loaded.has::< RenderHandle >( entity_id ));
+//! assert!( !loaded.has_cached_path( entity_id ));
+//! ```
+//! 
+//! Snapshots made by older versions of the ecs can be upgraded with `Migrations`, which rename, convert and drop components before they reach the ecs.
+//! ```
+//! use min_ecs::*;
+//! 
+//! #[derive( Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize )]
+//! pub struct ArmorStats { value: u32, weight: u32 }
+//! 
+//! minecs!(
+//! 	#[snapshot]
+//! 	ecs MinEcs< CompArray, TestEntity > {
+//! 		armor_stats: ArmorStats,
+//! 	}
+//! );
+//! 
+//! // version 0 stored `armor: u32`
+//! fn armor_to_stats ( value: SnapValue ) -> Result< SnapValue, SnapErr > {
+//! 	ArmorStats { value: u32::from_snap( value )?, weight: 1 }.to_snap()
+//! }
+//! 
+//! let migrations = Migrations::new( 1 )
+//! 	.rename( 0, "armor", "armor_stats" )
+//! 	.convert( 0, "armor_stats", armor_to_stats );
+//! 
+//! let mut old = Snapshot::new( 1 );
+//! let mut armor = SnapSection::new( "armor" );
+//! armor.push( 0, 5_u32.to_snap().unwrap() );
+//! old.components.push( armor );
+//! 
+//! let ecs = MinEcs::from_snapshot_with( old, &migrations ).unwrap();
+//! assert_eq!( ecs.armor_stats( EntityId::from( 0 )), Some( &ArmorStats { value: 5, weight: 1 } ));
+//! ```
+//! 
+//! # Prefabs
+//! 
+//! A `Prefab` is a named template of an entity: component values keyed by the same names as in `Snapshot`.
+//! It can be written in a simple built-in text format, or with `serde` feature loaded from formats like JSON or RON.
+//! An ECS declared `#[snapshot]` implements `EcsPrefab`, spawning entities out of prefabs.
+//! ```
+//! use min_ecs::*;
+//! 
+//! #[derive( Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize )]
+//! pub struct Armor { value: u32, weight: f32 }
+//! 
+//! #[derive( Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize )]
+//! pub enum Mood { Calm, Angry( u8 ) }
+//! 
+//! minecs!(
+//! 	#[snapshot]
+//! 	ecs MinEcs< CompArray, TestEntity > {
+//! 		types [ Mood ]
+//! 		health: u32,
+//! 		name: String,
+//! 		armor: Armor,
+//! 	}
+//! );
+//! 
+//! let prefabs = Prefab::parse_all( r#"
+//! 	[goblin]
+//! 	health = 30
+//! 	name = "Goblin"
+//! 	armor = { value = 5, weight = 1.5 }
+//! 	Mood = Angry( 3 ) # generic components are named by their type
+//! 	
+//! 	[typo]
+//! 	helth = 10
+//! "# ).unwrap();
+//! 
+//! let mut ecs = MinEcs::new();
+//! let goblin = ecs.spawn_prefab( &prefabs[ 0 ] ).unwrap();
+//! assert_eq!( ecs.health( goblin ), Some( &30 ));
+//! assert_eq!( ecs.armor( goblin ), Some( &Armor { value: 5, weight: 1.5 } ));
+//! assert_eq!( ecs.get_for::< Mood >( goblin ), Ok( &Mood::Angry( 3 )));
+//! 
+//! let err = ecs.spawn_prefab( &prefabs[ 1 ] ).unwrap_err();
+//! assert_eq!( err.to_string(), "prefab: typo contains unknown component: helth, expected one of: Mood, health, name, armor" );
+//! 
+//! assert_eq!( Prefab::parse( "[orc]\nhealth = ]" ), Err( SnapErr::Parse( 2, "expected value, found `]`".to_owned() )));
+//! ```
+//! 
+//! # Debugging
+//! 
+//! `Debug` of the ECS shows its raw storage. `EcsDump::dump` shows each entity with its components instead,
+//! labelled with the field name or `std::any::type_name`; `dump_with` filters entities and components and caps the output.
+//! ```
+//! use min_ecs::*;
+//! 
+//! minecs!(
+//! 	ecs MinEcs< CompArray, TestEntity > {
+//! 		types [f64]
+//! 		name: String,
+//! 	}
+//! );
+//! 
+//! let mut ecs = MinEcs::new();
+//! let entity_0 = ecs.new_entity();
+//! ecs.insert( entity_0, 1.5 );
+//! ecs.insert_name( entity_0, "Bob".to_owned() );
+//! let entity_1 = ecs.new_entity();
+//! ecs.insert_name( entity_1, "Alice".to_owned() );
+//! let entity_2 = ecs.new_entity();
+//! 
+//! assert_eq!( ecs.dump(), "\
+//! MinEcs: 3 entities
+//! entity 0:
+//! 	f64: 1.5
+//! 	name: \"Bob\"
+//! entity 1:
+//! 	name: \"Alice\"
+//! entity 2: no components
+//! " );
+//! 
+//! let options = DumpOptions::new().component::< String >().limit( 1 );
+//! assert_eq!( ecs.dump_with( &options ), "MinEcs: 3 entities\nentity 0:\n\tname: \"Bob\"\n... 1 more entities\n" );
+//! 
+//! let options = DumpOptions::new().entity( entity_1 ).max_value_len( 3 );
+//! assert_eq!( ecs.dump_with( &options ), "MinEcs: 3 entities\nentity 1:\n\tname: \"Al...\n" );
+//! 
+//! // errors name the component involved, along with the field of named components
+//! let err = ecs.remove_name( entity_2 ).unwrap_err();
+//! assert_eq!( err, EcsErr::EntityMissingComponent( CompName::of::< String >().with_field( "name" ), 2 ));
+//! assert_eq!( err.to_string(), "missing component `name` (alloc::string::String) in entity with id: 2" );
+//! ```
+//! 
+//! `EcsValidate::validate` cross-checks the `CompId`s stored in entities against the component storage,
+//! and the entity each component is associated with; fields of the same type share a storage, and a component in it is owned
+//! when any of those fields in its entity points at it.
+//! `debug_validate` panics on failure in debug builds only; it runs after every `Schedule::run`, and can be called explicitly after other batches of changes.
+//! ```
+//! use min_ecs::*;
+//! 
+//! minecs!(
+//! 	ecs MinEcs< CompArray, TestEntity > {
+//! 		types [f64]
+//! 	}
+//! );
+//! 
+//! let mut ecs = MinEcs::new();
+//! let entity_0 = ecs.new_entity();
+//! let entity_1 = ecs.new_entity();
+//! let cid = ecs.insert( entity_0, 1.5 ).unwrap();
+//! assert_eq!( ecs.validate(), Ok(()) );
+//! 
+//! // moving the id to another entity leaves the component associated with the first one
+//! _ = EntityFn::< f64 >::remove( ecs.entity_mut( entity_0 ).unwrap() );
+//! _ = ecs.entity_mut( entity_1 ).unwrap().set( cid );
+//! assert_eq!( ecs.validate(), Err( vec![
+//! 	EcsErr::ComponentOwnerMismatch( CompName::of::< f64 >(), 1, 0, 0 ),
+//! 	EcsErr::OrphanComponent( CompName::of::< f64 >(), 0, 0 ),
+//! ]));
+//! ```
+//! ```
+//! use min_ecs::*;
+//! 
+//! minecs!(
+//! 	ecs MinEcs< CompArray, TestEntity > {
+//! 		health: u32,
+//! 		mana: u32,
+//! 	}
+//! );
+//! 
+//! let mut ecs = MinEcs::new();
+//! let entity_0 = ecs.new_entity();
+//! let entity_1 = ecs.new_entity();
+//! ecs.insert_health( entity_0, 10 );
+//! ecs.insert_mana( entity_0, 5 );
+//! ecs.insert_mana( entity_1, 3 );
+//! assert_eq!( ecs.validate(), Ok(()) );
+//! ecs.debug_validate();
+//! ```
+//! 
+//! # Hierarchy
+//! 
+//! Every ECS implements `EcsHierarchy`, relating entities as parents and children, fe. a UI tree or a weapon attached to a character.
+//! Both directions are kept consistent, and relations are preserved by `merge` and snapshots.
+//! ```
+//! use min_ecs::*;
+//! 
+//! minecs!(
+//! 	ecs MinEcs< CompArray, TestEntity > {
+//! 		name: String,
+//! 	}
+//! );
+//! 
+//! let mut ecs = MinEcs::new();
+//! let [ root, panel, button, label ] = [ "root", "panel", "button", "label" ].map( |name| {
+//! 	let id = ecs.new_entity();
+//! 	ecs.insert_name( id, name.to_owned() );
+//! 	id
+//! });
+//! 
+//! ecs.set_parent( panel, root ).unwrap();
+//! ecs.set_parent( button, panel ).unwrap();
+//! ecs.set_parent( label, panel ).unwrap();
+//! assert_eq!( ecs.children( panel ).collect::< Vec<_> >(), [ button, label ] );
+//! assert_eq!( ecs.ancestors( label ).collect::< Vec<_> >(), [ panel, root ] );
+//! assert_eq!( ecs.descendants( root ).collect::< Vec<_> >(), [ panel, button, label ] );
+//! 
+//! // an entity cannot become a child of its descendant
+//! assert_eq!( ecs.set_parent( root, button ), Err( EcsErr::HierarchyCycle( 0, 2 ) ));
+//! 
+//! // moving an entity updates both its old and new parent
+//! assert_eq!( ecs.set_parent( label, root ), Ok( Some( panel ) ));
+//! assert_eq!( ecs.children( panel ).collect::< Vec<_> >(), [ button ] );
+//! 
+//! // despawning removes components of the entity and all its descendants, ids are not reused
+//! ecs.despawn_recursive( panel ).unwrap();
+//! assert!( ecs.name( button ).is_none() );
+//! assert!( ecs.entity( button ).is_none() );
+//! assert_eq!( ecs.despawn( button ), Err( EcsErr::NoSuchEntityId( 2 )));
+//! assert_eq!( ecs.parent( panel ), None );
+//! assert_eq!( ecs.children( root ).collect::< Vec<_> >(), [ label ] );
+//! assert_eq!( ecs.validate(), Ok(()) );
+//! ```
+//! 
+//! # Relations
+//! 
+//! Relations declared in `relations [...]` are typed many-to-many edges between entities, fe. a unit targeting others or owning items.
+//! Each relation becomes a unit struct implementing `Relation`, used to select it in the methods of `EcsRelation`.
+//! Edges are removed when either end is despawned, and preserved by `merge` and snapshots.
+//! ```
+//! use min_ecs::*;
+//! 
+//! minecs!(
+//! 	ecs MinEcs< CompArray, TestEntity > {
+//! 		relations [ Targets, Owns( u32 ) ]
+//! 		name: String,
+//! 	}
+//! );
+//! 
+//! let mut ecs = MinEcs::new();
+//! let [ knight, archer, goblin, sword ] = [ (); 4 ].map( |()| ecs.new_entity() );
+//! 
+//! ecs.relate::< Targets >( knight, goblin, () ).unwrap();
+//! ecs.relate::< Targets >( archer, goblin, () ).unwrap();
+//! ecs.relate::< Owns >( knight, sword, 1 ).unwrap();
+//! 
+//! // relating again replaces the data of the edge
+//! assert_eq!( ecs.relate::< Owns >( knight, sword, 2 ), Ok( Some( 1 ) ));
+//! assert_eq!( ecs.relation::< Owns >( knight, sword ), Some( &2 ));
+//! 
+//! let attackers: Vec<_> = ecs.incoming::< Targets >( goblin ).map( |( source, _, _ )| source ).collect();
+//! assert_eq!( attackers, [ knight, archer ] );
+//! assert_eq!( ecs.outgoing::< Owns >( knight ).count(), 1 );
+//! 
+//! assert_eq!( ecs.unrelate::< Targets >( archer, goblin ), Some( () ));
+//! 
+//! // despawning removes all edges starting or ending at the entity
+//! ecs.despawn( goblin ).unwrap();
+//! assert_eq!( ecs.outgoing::< Targets >( knight ).count(), 0 );
+//! ```
+//! 
+//! # Tags
+//! 
+//! Tags declared in `tags [...]` are marker types without data, stored as bits of a per-entity `TagSet` rather than as components.
+//! Each tag becomes a unit struct, used to select it in the methods of `EcsTag`; `tagged` iterates over entities matching a `TagFilter`.
+//! Tags are removed on despawn, and preserved by `merge` and snapshots.
+//! ```
+//! use min_ecs::*;
+//! 
+//! minecs!(
+//! 	#[snapshot]
+//! 	ecs MinEcs< CompArray, TestEntity > {
+//! 		types [ f64 ]
+//! 		tags [ Player, Enemy, Dead ]
 //! 	}
+//! );
+//! 
+//! let mut ecs = MinEcs::new();
+//! let [ hero, orc, ghost ] = [ (); 3 ].map( |()| ecs.new_entity() );
+//! 
+//! assert_eq!( ecs.add_tag::< Player >( hero ), Ok( true ));
+//! assert_eq!( ecs.add_tag::< Player >( hero ), Ok( false ));
+//! ecs.add_tag::< Enemy >( orc ).unwrap();
+//! ecs.add_tag::< Enemy >( ghost ).unwrap();
+//! ecs.add_tag::< Dead >( ghost ).unwrap();
+//! assert!( ecs.has_tag::< Dead >( ghost ));
+//! assert_eq!( ecs.tag_set( ghost ).len(), 2 );
+//! 
+//! let alive_enemies: Vec<_> = ecs.tagged( &ecs.tag_filter().with::< Enemy >().without::< Dead >() ).collect();
+//! assert_eq!( alive_enemies, [ orc ] );
+//! 
+//! // snapshots keep tags in sections named after them
+//! let snapshot = ecs.snapshot().unwrap();
+//! assert_eq!( snapshot.section( "#Dead" ).unwrap().entries, vec![( 2, SnapValue::Unit )] );
+//! assert_eq!( MinEcs::from_snapshot( snapshot ).as_ref(), Ok( &ecs ));
+//! 
+//! assert!( ecs.remove_tag::< Dead >( ghost ));
+//! assert_eq!( ecs.tagged( &ecs.tag_filter().with::< Enemy >() ).count(), 2 );
+//! 
+//! // despawning removes all tags of the entity, despawned entities cannot be tagged nor are they listed
+//! ecs.despawn( hero ).unwrap();
+//! assert!( !ecs.has_tag::< Player >( hero ));
+//! assert_eq!( ecs.add_tag::< Player >( hero ), Err( EcsErr::NoSuchEntityId( 0 )));
+//! assert_eq!( ecs.tagged( &ecs.tag_filter().without::< Player >() ).collect::< Vec<_> >(), [ orc, ghost ] );
+//! ```
+//! 
+//! # Observers
+//! 
+//! Every ECS implements `EcsObserve`: closures registered via `observe` for a `Trigger` type, or via `observe_entity` on a specific entity,
+//! run with mutable access to the ECS on `trigger( value, target )`, which fails for a despawned target. Triggers propagate up the parent chain when `Trigger::PROPAGATE`
+//! is `true`, or an observer requests it via `Triggered::propagate`. Observers registered on an entity are removed when it is despawned.
+//! ```
+//! use min_ecs::*;
+//! use std::sync::atomic::{ AtomicU32, Ordering };
+//! 
+//! pub struct Hit {
+//! 	damage: u32,
+//! }
+//! 
+//! impl Trigger for Hit {
+//! 	const PROPAGATE: bool = true;
+//! }
+//! 
+//! minecs!( ecs MinEcs< CompArray, Unit > { health: u32, } );
+//! 
+//! let mut ecs = MinEcs::new();
+//! let ship = ecs.new_entity();
+//! let shield = ecs.new_entity();
+//! ecs.insert_health( ship, 100 );
+//! ecs.insert_health( shield, 20 );
+//! ecs.set_parent( shield, ship ).unwrap();
+//! 
+//! // the shield absorbs damage up to its health, the rest propagates to the ship
+//! ecs.observe_entity( shield, |ecs, hit: &mut Triggered< Hit, Unit >| {
+//! 	let health = hit.entity().and_then( |id| ecs.health_mut( id )).unwrap();
+//! 	let absorbed = hit.get().damage.min( *health );
+//! 	*health -= absorbed;
+//! 	hit.get_mut().damage -= absorbed;
+//! 	hit.propagate( hit.get().damage > 0 );
+//! });
+//! ecs.observe_entity( ship, |ecs, hit: &mut Triggered< Hit, Unit >| {
+//! 	*hit.entity().and_then( |id| ecs.health_mut( id )).unwrap() -= hit.get().damage;
+//! });
+//! static HITS: AtomicU32 = AtomicU32::new( 0 );
+//! ecs.observe( |_, _: &mut Triggered< Hit, Unit >| {
+//! 	HITS.fetch_add( 1, Ordering::Relaxed );
 //! });
+//! 
+//! ecs.trigger( Hit { damage: 15 }, shield ).unwrap();
+//! assert_eq!(( ecs.health( shield ), ecs.health( ship )), ( Some( &5 ), Some( &100 )));
+//! let hit = ecs.trigger( Hit { damage: 15 }, shield ).unwrap();
+//! assert_eq!( hit.damage, 10 );
+//! assert_eq!(( ecs.health( shield ), ecs.health( ship )), ( Some( &0 ), Some( &90 )));
+//! assert_eq!( HITS.load( Ordering::Relaxed ), 2 );
+//! 
+//! // despawned entities cannot be targeted
+//! ecs.despawn( shield ).unwrap();
+//! assert!( matches!( ecs.trigger( Hit { damage: 15 }, shield ), Err( EcsErr::NoSuchEntityId( 1 ))));
+//! assert_eq!( ecs.health( ship ), Some( &90 ));
+//! assert_eq!( HITS.load( Ordering::Relaxed ), 2 );
+//! assert_eq!( ecs.observers().len(), 2 );
+//! ```
+//! 
+//! # Parallel iteration
+//! 
+//! With the `rayon` feature, `EcsFn::par_iter` and `par_iter_mut` return parallel iterators over components of one type,
+//! while `par_for_each` visits entities having components of two types in parallel. `rayon` is re-exported for its prelude.
+//! ```
+//! # #[cfg( feature = "rayon" )] {
+//! use min_ecs::*;
+//! use min_ecs::rayon::prelude::*;
+//! 
+//! minecs!(
+//! 	ecs MinEcs< CompArray, TestEntity > {
+//! 		types [ f64, f32 ]
+//! 	}
+//! );
+//! 
+//! let mut ecs = MinEcs::new();
+//! for idx in 0..1000 {
+//! 	let id = ecs.new_entity();
+//! 	ecs.insert( id, 0.0_f64 );
+//! 	if idx % 2 == 0 {
+//! 		ecs.insert( id, 2.0_f32 );
+//! 	}
+//! }
+//! 
+//! // only entities with both position and velocity are visited
+//! ecs.par_for_each::< f64, f32, _ >( |_, position, velocity| *position += f64::from( *velocity ));
+//! assert_eq!( ecs.par_iter::< f64 >().sum::< f64 >(), 1000.0 );
+//! 
+//! ecs.par_iter_mut::< f32 >().for_each( |velocity| *velocity *= 0.5 );
+//! assert!( ecs.par_iter::< f32 >().all( |velocity| *velocity == 1.0 ));
+//! # }
+//! ```
+//! 
+//! # Systems
+//! 
+//! ECS declared with `#[parallel]` can be run by a `Schedule`. Each `System` declares the component types and resources it reads and writes,
+//! systems without conflicting access run at the same time, each on a separate thread, and borrow only what they declared via `SystemData`.
+//! Writing a component type or resource subsumes reading it. Threads are spawned for every run, unless the `rayon` feature provides a thread pool.
+//! Conflicting systems are ordered by `after` and `before`, or run in the order they were added, which is listed by `Schedule::ambiguities`
+//! and, in debug builds, reported when a run builds the stages: printed to stderr, or passed to a function set by `Schedule::with_ambiguity_reporter`.
+//! ```
+//! use min_ecs::*;
+//! use std::sync::{ Arc, atomic::{ AtomicBool, Ordering } };
+//! 
+//! #[ecs( CompArray )]
+//! #[parallel]
+//! pub struct World {
+//! 	#[entities]
+//! 	entities: Vec< TestEntity >,
+//! 	#[component( generic )]
+//! 	position: f64,
+//! 	#[component( generic )]
+//! 	velocity: f32,
+//! 	#[resource]
+//! 	tick: u64,
+//! }
+//! 
+//! let mut world = World::new();
+//! for idx in 0..4_u8 {
+//! 	let id = world.new_entity();
+//! 	world.insert( id, 0.0_f64 );
+//! 	world.insert( id, f32::from( idx ));
+//! }
+//! 
+//! let mut schedule = Schedule::new();
+//! schedule
+//! 	.add_system( System::new( "tick", |data| {
+//! 		*data.resource_mut::< u64 >()? += 1;
+//! 		Ok(())
+//! 	}).writes_resource::< u64 >() )
+//! 	.add_system( System::new( "movement", |data| {
+//! 		let velocities = data.read::< f32 >()?;
+//! 		let mut positions = data.write::< f64 >()?;
+//! 		for ( e_id, velocity ) in velocities.iter() {
+//! 			if let Some( position ) = data.entity( e_id ).and_then( EntityFn::< f64 >::get ).and_then( |id| positions.get_mut( id )) {
+//! 				*position += f64::from( *velocity );
+//! 			}
+//! 		}
+//! 		Ok(())
+//! 	}).reads::< f32 >().writes::< f64 >() )
+//! 	.add_system( System::new( "friction", |data| {
+//! 		data.write::< f32 >()?.iter_mut().for_each( |( _, velocity )| *velocity *= 0.5 );
+//! 		Ok(())
+//! 	}).writes::< f32 >().after( "movement" ));
+//! 
+//! // `tick` does not conflict with `movement`, `friction` writes velocity read by `movement`
+//! assert_eq!( schedule.stages(), Ok( vec![ vec![ "tick", "movement" ], vec![ "friction" ]] ));
+//! assert_eq!( schedule.ambiguities(), Ok( vec![] ));
+//! 
+//! schedule.run( &mut world ).unwrap();
+//! schedule.run( &mut world ).unwrap();
+//! assert_eq!( *world.tick(), 2 );
+//! assert_eq!( world.iter::< f64 >().copied().collect::< Vec<_> >(), [ 0.0, 1.5, 3.0, 4.5 ] );
+//! 
+//! // systems may borrow only what they declared
+//! let mut schedule = Schedule::new();
+//! schedule.add_system( System::new( "sneaky", |data| data.write::< f32 >().map( |_| () )).reads::< f32 >() );
+//! assert_eq!(
+//! 	schedule.run( &mut world ),
+//! 	Err( ScheduleErr::System( "sneaky".to_owned(), EcsErr::UndeclaredAccess( CompName::of::< f32 >() )))
+//! );
+//! 
+//! // conflicting systems never run at the same time
+//! let busy = Arc::new( AtomicBool::new( false ));
+//! let mut schedule = Schedule::new();
+//! for name in [ "first", "second", "third" ] {
+//! 	let busy = Arc::clone( &busy );
+//! 	schedule.add_system( System::new( name, move |data| {
+//! 		let _positions = data.write::< f64 >()?;
+//! 		assert!( !busy.swap( true, Ordering::SeqCst ));
+//! 		std::thread::sleep( std::time::Duration::from_millis( 10 ));
+//! 		busy.store( false, Ordering::SeqCst );
+//! 		Ok(())
+//! 	}).writes::< f64 >() );
+//! }
+//! assert_eq!( schedule.stages().unwrap().len(), 3 );
+//! assert_eq!( schedule.ambiguities().unwrap().len(), 3 );
+//! schedule.run( &mut world ).unwrap();
+//! ```
+//! 
+//! # Game loop
+//! 
+//! `App` owns an ECS declared with `#[parallel]` along with two schedules. Each `App::frame` runs the update schedule once per fixed step
+//! of accumulated time, then the render schedule once. `Time` has to be a resource of the ECS, it is updated before each run and read by systems.
+//! `App::step` runs fixed updates only, for deterministic tests without real time.
+//! ```
+//! use min_ecs::*;
+//! use std::time::Duration;
+//! 
+//! #[ecs( CompArray )]
+//! #[parallel]
+//! pub struct World {
+//! 	#[entities]
+//! 	entities: Vec< TestEntity >,
+//! 	#[component( generic )]
+//! 	position: f64,
+//! 	#[resource]
+//! 	time: Time,
+//! 	#[resource]
+//! 	frames: u32,
+//! }
+//! 
+//! let mut world = World::new();
+//! let id = world.new_entity();
+//! world.insert( id, 0.0_f64 );
+//! 
+//! let mut app = App::new( world, Duration::from_millis( 10 ));
+//! app
+//! 	.add_update_system( System::new( "movement", |data| {
+//! 		let delta = data.resource::< Time >()?.delta_secs();
+//! 		data.write::< f64 >()?.iter_mut().for_each( |( _, position )| *position += 2.0 * delta );
+//! 		Ok(())
+//! 	}).reads_resource::< Time >().writes::< f64 >() )
+//! 	.add_render_system( System::new( "render", |data| {
+//! 		*data.resource_mut::< u32 >()? += 1;
+//! 		Ok(())
+//! 	}).writes_resource::< u32 >() );
+//! 
+//! app.step( 100 ).unwrap();
+//! assert_eq!( app.world().time().ticks(), 100 );
+//! assert_eq!( app.world().time().elapsed(), Duration::from_secs( 1 ));
+//! assert!(( app.world().get_for::< f64 >( id ).unwrap() - 2.0 ).abs() < 1e-9 );
+//! 
+//! // 25 ms run 2 fixed updates and leave half a step for the next frame
+//! assert_eq!( app.frame( Duration::from_millis( 25 )), Ok( 2 ));
+//! assert_eq!( app.world().time().alpha(), 0.5 );
+//! assert_eq!( app.frame( Duration::from_millis( 25 )), Ok( 3 ));
+//! assert_eq!( *app.world().frames(), 2 );
+//! assert_eq!( app.world().time().ticks(), 105 );
+//! ```
+//! 
+//! # Timers
+//! 
+//! `Timer` and `Cooldown` are components ticked by systems created by `Timer::system` and `Cooldown::system`, using `Time::delta`.
+//! Timers are one-shot or repeating and can be paused; each time one finishes, or a cooldown becomes ready, `TimerFinished`
+//! with the owning entity is sent to the `Events` resource, kept until drained as nothing clears events automatically.
+//! ```
+//! use min_ecs::*;
+//! use std::time::Duration;
+//! 
+//! #[ecs( CompArray )]
+//! #[parallel]
+//! pub struct World {
+//! 	#[entities]
+//! 	entities: Vec< TestEntity >,
+//! 	#[component]
+//! 	timer: Timer,
+//! 	#[component]
+//! 	cooldown: Cooldown,
+//! 	#[resource]
+//! 	time: Time,
+//! 	#[resource]
+//! 	finished: Events< TimerFinished< TestEntity > >,
+//! }
+//! 
+//! let mut world = World::new();
+//! let bomb = world.new_entity();
+//! let spawner = world.new_entity();
+//! world.insert_timer( bomb, Timer::once( Duration::from_millis( 30 )));
+//! world.insert_timer( spawner, Timer::repeating( Duration::from_millis( 20 )));
+//! world.insert_cooldown( spawner, Cooldown::new( Duration::from_millis( 50 )));
+//! 
+//! let mut app = App::new( world, Duration::from_millis( 10 ));
+//! app
+//! 	.add_update_system( Timer::system( "timers" ))
+//! 	.add_update_system( Cooldown::system( "cooldowns" ).after( "timers" ));
+//! 
+//! assert!( app.world_mut().cooldown_mut( spawner ).unwrap().trigger() );
+//! assert!( !app.world_mut().cooldown_mut( spawner ).unwrap().trigger() );
+//! 
+//! app.step( 4 ).unwrap();
+//! let finished: Vec<_> = app.world_mut().finished_mut().drain().map( |event| event.entity ).collect();
+//! assert_eq!( finished, [ spawner, bomb, spawner ] );
+//! assert!( app.world().timer( bomb ).unwrap().is_finished() );
+//! assert_eq!( app.world().cooldown( spawner ).unwrap().remaining(), Duration::from_millis( 10 ));
+//! 
+//! // paused timers do not advance, the cooldown becomes ready
+//! app.world_mut().timer_mut( spawner ).unwrap().pause();
+//! app.step( 4 ).unwrap();
+//! let finished: Vec<_> = app.world_mut().finished_mut().drain().map( |event| event.entity ).collect();
+//! assert_eq!( finished, [ spawner ] );
+//! assert!( app.world_mut().cooldown_mut( spawner ).unwrap().trigger() );
+//! ```
+//! 
+//! # State machines
+//! 
+//! `State< S >` is a component holding the current state, `NextState< S >` requests a transition.
+//! The system created by `StateMachine::system` applies requests, runs `on_exit` and `on_enter` hooks for each transition
+//! and sends `StateTransition` events, so that other systems can react.
+//! ```
+//! use min_ecs::*;
+//! 
+//! #[derive( Debug, Clone, Copy, PartialEq )]
+//! pub enum Ai { Idle, Chase, Flee }
+//! 
+//! #[ecs( CompArray )]
+//! #[parallel]
+//! pub struct World {
+//! 	#[entities]
+//! 	entities: Vec< TestEntity >,
+//! 	#[component]
+//! 	ai: State< Ai >,
+//! 	#[component]
+//! 	ai_next: NextState< Ai >,
+//! 	#[component( generic )]
+//! 	speed: f32,
+//! 	#[resource]
+//! 	transitions: Events< StateTransition< Ai, TestEntity > >,
+//! }
+//! 
+//! fn set_speed ( entity: EntityId< TestEntity >, data: &mut SystemData< '_, TestEntity >, value: f32 ) -> Result< (), EcsErr > {
+//! 	let comp_id = data.entity( entity ).and_then( EntityFn::< f32 >::get );
+//! 	data.with_write( |mut speeds| if let Some( speed ) = comp_id.and_then( |id| speeds.get_mut( id )) {
+//! 		*speed = value;
+//! 	})
+//! }
+//! 
+//! let mut world = World::new();
+//! let wolf = world.new_entity();
+//! world.insert_ai( wolf, State::new( Ai::Idle ));
+//! world.insert_ai_next( wolf, NextState::new() );
+//! world.insert( wolf, 0.0_f32 );
+//! 
+//! let machine = StateMachine::new()
+//! 	.on_enter( Ai::Chase, |entity, data| set_speed( entity, data, 2.0 ))
+//! 	.on_exit( Ai::Chase, |entity, data| set_speed( entity, data, 0.0 ))
+//! 	.on_enter( Ai::Flee, |entity, data| set_speed( entity, data, 3.0 ));
+//! let mut schedule = Schedule::new();
+//! schedule.add_system( machine.system( "ai" ).writes::< f32 >() );
+//! 
+//! world.ai_next_mut( wolf ).unwrap().set( Ai::Chase );
+//! schedule.run( &mut world ).unwrap();
+//! assert_eq!( world.ai( wolf ).unwrap().get(), &Ai::Chase );
+//! assert_eq!( world.ai_next( wolf ).unwrap().get(), None );
+//! assert_eq!( world.get_for::< f32 >( wolf ), Ok( &2.0 ));
+//! 
+//! // exit and enter hooks of one transition borrow speeds one after another
+//! world.ai_next_mut( wolf ).unwrap().set( Ai::Flee );
+//! schedule.run( &mut world ).unwrap();
+//! assert_eq!( world.get_for::< f32 >( wolf ), Ok( &3.0 ));
+//! assert_eq!(
+//! 	world.transitions_mut().drain().map( |event| ( event.from, event.to )).collect::< Vec<_> >(),
+//! 	[ ( Ai::Idle, Ai::Chase ), ( Ai::Chase, Ai::Flee ) ]
+//! );
+//! ```
+//! 
+//! # Thread safety
+//! 
+//! `EntityId` and `CompId` are `Send` and `Sync` for any entity type. The generated ECS is `Send` and `Sync` when all its components,
+//! resources and relation data are, which is asserted at compile time for every ECS, and unconditionally for ECS declared `#[parallel]`.
+//! `SharedEcs` shares an ECS between threads behind a read-write lock, fe. to let a loader thread insert components while the main thread reads them.
+//! ```
+//! use min_ecs::*;
+//! use std::rc::Rc;
+//! 
+//! minecs!( ecs MinEcs< CompArray, Unit > { types [ f64 ] name: String, } );
+//! minecs!( ecs LocalEcs< LocalArray, LocalUnit > { label: Rc< str >, } );
+//! minecs!( ecs SharedItems< ItemArray, Holder > { relations [ Owns( Rc< u32 > ) ] } );
+//! 
+//! let mut items = SharedItems::new();
+//! let ( holder, item ) = ( items.new_entity(), items.new_entity() );
+//! items.relate::< Owns >( holder, item, Rc::new( 3 )).unwrap();
+//! assert_eq!( items.relation::< Owns >( holder, item ).map( |data| **data ), Some( 3 ));
+//! 
+//! fn assert_send_sync< X: Send + Sync > () {}
+//! assert_send_sync::< MinEcs >();
+//! assert_send_sync::< EntityId< LocalUnit > >();
+//! assert_send_sync::< CompId< Rc< str >, LocalUnit > >();
+//! 
+//! let shared = SharedEcs::new( MinEcs::new() );
+//! let loader = {
+//! 	let shared = shared.clone();
+//! 	std::thread::spawn( move || {
+//! 		let mut last = None;
+//! 		for idx in 0..100_u8 {
+//! 			let mut ecs = shared.write();
+//! 			let id = ecs.new_entity();
+//! 			ecs.insert( id, f64::from( idx ));
+//! 			ecs.insert_name( id, format!( "unit {idx}" ));
+//! 			last = Some( id );
+//! 		}
+//! 		last.unwrap()
+//! 	})
+//! };
+//! while !loader.is_finished() {
+//! 	let loaded = shared.with( |ecs| ecs.iter::< f64 >().count() );
+//! 	assert!( loaded <= 100 );
+//! }
+//! let last = loader.join().unwrap();
+//! 
+//! let ecs = shared.into_inner().unwrap();
+//! assert_eq!( ecs.iter::< f64 >().count(), 100 );
+//! assert_eq!( ecs.name( last ).map( String::as_str ), Some( "unit 99" ));
+//! ```
+//! An ECS holding components that are not `Send` cannot be shared.
+//! ```compile_fail
+//! use min_ecs::*;
+//! use std::rc::Rc;
+//! 
+//! minecs!( ecs LocalEcs< LocalArray, LocalUnit > { label: Rc< str >, } );
+//! 
+//! let shared = SharedEcs::new( LocalEcs::new() );
+//! std::thread::spawn( move || drop( shared ));
+//! ```
+//! 
+//! # Merging worlds
+//! 
+//! `EcsMerge::merge` copies all entities of another ECS, giving them new ids. Components storing `EntityId`s can implement `MapEntities`,
+//! fe. via `#[derive( MapEntities )]`, and be declared `#[map_entities]`, so that stored ids follow their entities.
+//! The same happens when loading a snapshot into a non-empty ECS via `EcsSnapshot::load_into`.
+//! ```
+//! use min_ecs::*;
+//! 
+//! #[derive( Debug, Clone, PartialEq, MapEntities, serde::Serialize, serde::Deserialize )]
+//! pub struct Target {
+//! 	#[entity]
+//! 	entity: EntityId< Character >,
+//! }
+//! 
+//! minecs!(
+//! 	#[snapshot]
+//! 	ecs MinEcs< CompArray, Character > {
+//! 		name: String,
+//! 		#[map_entities] target: Target,
+//! 	}
+//! );
+//! 
+//! let mut level = MinEcs::new();
+//! let guard = level.new_entity();
+//! let thief = level.new_entity();
+//! level.insert_name( thief, "thief".to_owned() );
+//! level.insert_target( guard, Target { entity: thief } );
+//! 
+//! let mut world = MinEcs::new();
+//! _ = world.new_entity();
+//! let map = world.merge( &level );
+//! let new_guard = map.get( guard ).unwrap();
+//! let new_thief = map.get( thief ).unwrap();
+//! assert_eq!( world.target( new_guard ), Some( &Target { entity: new_thief } ));
+//! assert_eq!( world.name( new_thief ).map( String::as_str ), Some( "thief" ));
+//! 
+//! let map = world.load_into( level.snapshot().unwrap() ).unwrap();
+//! let thief_2 = map.get( thief ).unwrap();
+//! assert_eq!( usize::from( thief_2 ), 4 );
+//! assert_eq!( world.target( map.get( guard ).unwrap() ), Some( &Target { entity: thief_2 } ));
 //! ```
 //! 
 //! # Recommendation